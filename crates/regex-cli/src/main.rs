@@ -2,10 +2,14 @@ mod error;
 
 use crate::error::CommandLineError;
 use clap::{ArgAction, Parser};
-use regex_core::{Regex, error::RegexError};
+use regex_core::{
+    Syntax,
+    bytes::{Regex, RegexBuilder},
+    error::RegexError,
+};
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Stdin, stdin},
+    io::{BufRead, BufReader, Stdin, Write, stdin, stdout},
 };
 
 // 入力ファイルが stdin の場合、ファイル名を (standard input) とする。
@@ -40,6 +44,14 @@ pub struct Args {
     /// マッチしなかった行を表示する
     pub invert_match: bool,
 
+    #[arg(short = 'G', long = "basic-regexp", overrides_with = "extended_regexp")]
+    /// パターンを POSIX 基本正規表現 (BRE) として解釈する
+    pub basic_regexp: bool,
+
+    #[arg(short = 'E', long = "extended-regexp", overrides_with = "basic_regexp")]
+    /// パターンを POSIX 拡張正規表現 (ERE) として解釈する
+    pub extended_regexp: bool,
+
     #[arg(short = 'h', long = "no-filename")]
     /// 出力する行の前にファイル名を付けない。検索ファイルが1つの場合、こちらがデフォルト
     pub no_filename: bool,
@@ -82,6 +94,18 @@ impl Args {
 
         Ok(&self.patterns)
     }
+
+    /// -G / -E オプションから正規表現の構文を決定して返す。
+    /// どちらも指定されていない場合は Perl 互換の構文となる。
+    pub fn syntax(&self) -> Syntax {
+        if self.basic_regexp {
+            Syntax::Basic
+        } else if self.extended_regexp {
+            Syntax::Extended
+        } else {
+            Syntax::Perl
+        }
+    }
 }
 
 /// 入力ソース（ファイルまたは標準入力）を処理し、正規表現マッチングを実行する関数
@@ -147,8 +171,12 @@ fn main() {
     };
 
     // パターンをコンパイルして正規表現オブジェクトのリストを取得
-    let regexes: Vec<Regex> = match compile_patterns(&patterns, args.ignore_case, args.invert_match)
-    {
+    let regexes: Vec<Regex> = match compile_patterns(
+        &patterns,
+        args.ignore_case,
+        args.invert_match,
+        args.syntax(),
+    ) {
         Ok(regexes) => regexes,
         Err(e) => {
             eprintln!("RegexError: {e}");
@@ -166,8 +194,11 @@ fn main() {
 }
 
 /// ファイルもしくは、標準入力を1行ずつ read し、マッチングを実行する関数
+///
+/// 行はバイト列として読むため、UTF-8 として不正な行が含まれていても処理を続ける。
+/// 行末の `\n`（`\r\n` の場合は `\r` も）は取り除く。
 fn match_file<T: BufRead>(
-    buf_reader: T,
+    mut buf_reader: T,
     file: &str,
     regexes: &[Regex],
     args: &Args,
@@ -177,14 +208,24 @@ fn match_file<T: BufRead>(
     let is_line_number = args.line_number;
 
     let mut matching_count: usize = 0;
-    for (i, result) in buf_reader.lines().enumerate() {
-        let line = match result {
-            Ok(line) => line,
+    // 行ごとに確保し直さないよう、バッファを使い回す
+    let mut line: Vec<u8> = Vec::new();
+    for i in 0.. {
+        line.clear();
+        match buf_reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
             Err(e) => {
                 eprint!("{e}");
                 break;
             }
-        };
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
 
         // read した行を指定したパターンとマッチ
         for regex in regexes {
@@ -216,12 +257,21 @@ fn match_file<T: BufRead>(
 ///
 /// * 行数を表示する・しない  
 /// * ファイル名を表示する・しない。
-fn print(filename: &str, line: &str, line_number: usize, is_filename: bool, is_line_number: bool) {
-    match (is_filename, is_line_number) {
-        (true, true) => println!("{filename}:{line_number}:{line}"),
-        (true, false) => println!("{filename}:{line}"),
-        (false, true) => println!("{line_number}:{line}"),
-        (false, false) => println!("{line}"),
+///
+/// 行は UTF-8 として不正なバイトを含むことがあるため、そのまま書き出す。
+fn print(filename: &str, line: &[u8], line_number: usize, is_filename: bool, is_line_number: bool) {
+    let mut out = stdout().lock();
+    let result = match (is_filename, is_line_number) {
+        (true, true) => write!(out, "{filename}:{line_number}:"),
+        (true, false) => write!(out, "{filename}:"),
+        (false, true) => write!(out, "{line_number}:"),
+        (false, false) => Ok(()),
+    }
+    .and_then(|()| out.write_all(line))
+    .and_then(|()| out.write_all(b"\n"));
+
+    if let Err(e) = result {
+        eprintln!("{e}");
     }
 }
 
@@ -243,6 +293,7 @@ fn is_print_filename(file_count: usize, no_filename: bool, with_filename: bool)
 /// * `patterns` - コンパイルするパターン文字列のリスト
 /// * `ignore_case` - 大文字と小文字を区別するかどうか
 /// * `invert_match` - マッチングの結果を反転するかどうか
+/// * `syntax` - パターンの構文 (BRE / ERE / Perl 互換)
 ///
 /// # 返り値
 ///
@@ -252,12 +303,17 @@ fn compile_patterns(
     patterns: &[String],
     ignore_case: bool,
     invert_match: bool,
+    syntax: Syntax,
 ) -> Result<Vec<Regex>, RegexError> {
     let mut regexes = Vec::with_capacity(patterns.len());
 
     for pattern in patterns {
         // パターンを正規表現オブジェクトにコンパイル
-        let regex = Regex::new(pattern, ignore_case, invert_match)?;
+        let regex = RegexBuilder::new(pattern)
+            .ignore_case(ignore_case)
+            .invert_match(invert_match)
+            .syntax(syntax)
+            .build()?;
         regexes.push(regex);
     }
 
@@ -271,7 +327,7 @@ mod tests {
     use std::{fs::File, io::BufReader};
 
     use crate::error::CommandLineError;
-    use regex_core::Regex;
+    use regex_core::bytes::Regex;
 
     use crate::{is_print_filename, match_file};
 
@@ -304,6 +360,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -320,6 +378,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -338,6 +398,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -365,6 +427,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -390,6 +454,8 @@ mod tests {
             count: true, // count オプションを有効
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -399,6 +465,33 @@ mod tests {
         assert_eq!(match_file(buf_reader, "test", &regexes, &args), Some(3));
     }
 
+    #[test]
+    fn test_match_file_with_invalid_utf8() {
+        use std::io::Cursor;
+
+        // UTF-8 として不正な行があっても、以降の行の処理を続ける
+        let test_data: &[u8] = b"apple\n\xFF\xFE apple\nbanana\r\napple \xE9\r\n";
+        let buf_reader = BufReader::new(Cursor::new(test_data));
+
+        let regexes: Vec<Regex> = vec![Regex::new("apple$", false, false).unwrap()];
+        let args = super::Args {
+            pattern: None,
+            files: vec![],
+            patterns: vec![],
+            count: true,
+            ignore_case: false,
+            invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
+            no_filename: false,
+            with_filename: false,
+            line_number: false,
+            help: None,
+            version: None,
+        };
+        assert_eq!(match_file(buf_reader, "test", &regexes, &args), Some(2));
+    }
+
     #[test]
     fn test_match_file_with_line_numbers() {
         use std::io::Cursor;
@@ -415,6 +508,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: true, // line_number オプションを有効
@@ -440,6 +535,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: true,
             line_number: false,
@@ -467,6 +564,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -486,6 +585,8 @@ mod tests {
             count: false,
             ignore_case: false,
             invert_match: false,
+            basic_regexp: false,
+            extended_regexp: false,
             no_filename: false,
             with_filename: false,
             line_number: false,
@@ -507,10 +608,11 @@ mod tests {
         // 関数が正常に呼び出せることを確認
 
         // 各組み合わせで関数を呼び出し
-        super::print("test.txt", "test line", 1, true, true);
-        super::print("test.txt", "test line", 1, true, false);
-        super::print("test.txt", "test line", 1, false, true);
-        super::print("test.txt", "test line", 1, false, false);
+        super::print("test.txt", b"test line", 1, true, true);
+        super::print("test.txt", b"test line", 1, true, false);
+        super::print("test.txt", b"test line", 1, false, true);
+        super::print("test.txt", b"test line", 1, false, false);
+        super::print("test.txt", b"invalid \xFF line", 1, false, false);
 
         // エラーが発生しなければテスト成功
     }
@@ -1,5 +1,5 @@
 use crate::compile_patterns;
-use regex_core::error::RegexError;
+use regex_core::{Syntax, error::RegexError};
 
 #[test]
 fn test_compile_valid_patterns() {
@@ -7,7 +7,7 @@ fn test_compile_valid_patterns() {
     let patterns = vec!["abc".to_string(), "a(b|c)d".to_string(), "x.*y".to_string()];
 
     // デフォルトオプションでコンパイル
-    let result = compile_patterns(&patterns, false, false);
+    let result = compile_patterns(&patterns, false, false, Syntax::Perl);
 
     // 結果が成功であることを確認
     assert!(result.is_ok());
@@ -23,22 +23,22 @@ fn test_compile_with_ignore_case() {
     let patterns = vec!["abc".to_string()];
 
     // ignore_case = true でコンパイル
-    let result = compile_patterns(&patterns, true, false);
+    let result = compile_patterns(&patterns, true, false, Syntax::Perl);
     assert!(result.is_ok());
     let regexes = result.unwrap();
 
     // 大文字小文字を区別してマッチすることを確認
-    assert!(regexes[0].is_match("ABC").unwrap());
-    assert!(regexes[0].is_match("abc").unwrap());
+    assert!(regexes[0].is_match(b"ABC").unwrap());
+    assert!(regexes[0].is_match(b"abc").unwrap());
 
     // ignore_case = false でコンパイル
-    let result = compile_patterns(&patterns, false, false);
+    let result = compile_patterns(&patterns, false, false, Syntax::Perl);
     assert!(result.is_ok());
     let regexes = result.unwrap();
 
     // 大文字小文字を区別してマッチすることを確認
-    assert!(!regexes[0].is_match("ABC").unwrap());
-    assert!(regexes[0].is_match("abc").unwrap());
+    assert!(!regexes[0].is_match(b"ABC").unwrap());
+    assert!(regexes[0].is_match(b"abc").unwrap());
 }
 
 #[test]
@@ -47,13 +47,13 @@ fn test_compile_with_invert_match() {
     let patterns = vec!["abc".to_string()];
 
     // invert_match = true でコンパイル
-    let result = compile_patterns(&patterns, false, true);
+    let result = compile_patterns(&patterns, false, true, Syntax::Perl);
     assert!(result.is_ok());
     let regexes = result.unwrap();
 
     // マッチ結果が反転することを確認
-    assert!(!regexes[0].is_match("abc").unwrap());
-    assert!(regexes[0].is_match("def").unwrap());
+    assert!(!regexes[0].is_match(b"abc").unwrap());
+    assert!(regexes[0].is_match(b"def").unwrap());
 }
 
 #[test]
@@ -66,7 +66,7 @@ fn test_compile_invalid_pattern() {
     ];
 
     // コンパイル結果がエラーであることを確認
-    let result = compile_patterns(&patterns, false, false);
+    let result = compile_patterns(&patterns, false, false, Syntax::Perl);
     assert!(result.is_err());
 
     // エラーの種類を確認（ParseError::MissingParenthesis）
@@ -87,7 +87,7 @@ fn test_compile_empty_patterns() {
     let patterns: Vec<String> = vec![];
 
     // 空のリストをコンパイル
-    let result = compile_patterns(&patterns, false, false);
+    let result = compile_patterns(&patterns, false, false, Syntax::Perl);
 
     // 結果が成功であることを確認
     assert!(result.is_ok());
@@ -106,7 +106,7 @@ fn test_compile_multiple_invalid_patterns() {
     ];
 
     // コンパイル結果がエラーであることを確認
-    let result = compile_patterns(&patterns, false, false);
+    let result = compile_patterns(&patterns, false, false, Syntax::Perl);
     assert!(result.is_err());
 
     // 最初のエラー（* に関するエラー）が返されることを確認
@@ -120,3 +120,19 @@ fn test_compile_multiple_invalid_patterns() {
         }
     }
 }
+
+#[test]
+fn test_compile_with_syntax() {
+    // -G (BRE) では \( \) \| が演算子、+ は通常の文字として扱われる
+    let patterns = vec!["\\(ab\\)\\|c+".to_string()];
+    let regexes = compile_patterns(&patterns, false, false, Syntax::Basic).unwrap();
+    assert!(regexes[0].is_match(b"xab").unwrap());
+    assert!(regexes[0].is_match(b"c+").unwrap());
+    assert!(!regexes[0].is_match(b"cc").unwrap());
+
+    // -E (ERE) では ( ) | + が演算子として扱われる
+    let patterns = vec!["(ab)|c+".to_string()];
+    let regexes = compile_patterns(&patterns, false, false, Syntax::Extended).unwrap();
+    assert!(regexes[0].is_match(b"cc").unwrap());
+    assert!(!regexes[0].is_match(b"x+").unwrap());
+}
@@ -17,6 +17,8 @@ fn test_process_single_file() {
         count: false,
         ignore_case: false,
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
@@ -54,6 +56,8 @@ fn test_process_multiple_files() {
         count: false,
         ignore_case: false,
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: true,
         line_number: false,
@@ -81,6 +85,8 @@ fn test_process_nonexistent_file() {
         count: false,
         ignore_case: false,
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
@@ -111,6 +117,8 @@ fn test_process_with_count_option() {
         count: true, // count option enabled
         ignore_case: false,
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
@@ -143,6 +151,8 @@ fn test_process_with_ignore_case() {
         count: false,
         ignore_case: true, // ignore_case option enabled
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
@@ -167,6 +177,8 @@ fn test_process_with_ignore_case() {
         count: false,
         ignore_case: false, // ignore_case option disabled
         invert_match: false,
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
@@ -199,6 +211,8 @@ fn test_process_with_invert_match() {
         count: false,
         ignore_case: false,
         invert_match: true, // invert_match option enabled
+        basic_regexp: false,
+        extended_regexp: false,
         no_filename: false,
         with_filename: false,
         line_number: false,
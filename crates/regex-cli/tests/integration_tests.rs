@@ -319,3 +319,127 @@ fn test_cli_conflicting_options() {
     let stderr = String::from_utf8(output.stderr).unwrap();
     assert!(stderr.contains("DuplicateFilenameOption") || stderr.contains("same time"));
 }
+
+#[test]
+fn test_cli_basic_and_extended_regexp_options() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "a+b").unwrap();
+    writeln!(temp_file, "aab").unwrap();
+
+    // -G では + は通常の文字
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "regex-cli",
+            "--bin",
+            "regex",
+            "--",
+            "-G",
+            "a+b",
+            temp_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "a+b\n");
+
+    // -E では + は繰り返し演算子
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "regex-cli",
+            "--bin",
+            "regex",
+            "--",
+            "-E",
+            "a+b",
+            temp_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "aab\n");
+}
+
+#[test]
+fn test_cli_posix_classes_and_literal_brace() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "abc").unwrap();
+    writeln!(temp_file, "a1").unwrap();
+    writeln!(temp_file, "b{{").unwrap();
+
+    // 文字クラス名は -G と -E の両方で使え、-E で区間にならない { は通常の文字
+    for (syntax, pattern, expected) in [
+        ("-E", "[[:digit:]]", "a1\n"),
+        ("-G", "a[[:digit:]]", "a1\n"),
+        ("-E", "[[:alpha:]]{3}", "abc\n"),
+        ("-E", "b{", "b{\n"),
+    ] {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "regex-cli",
+                "--bin",
+                "regex",
+                "--",
+                syntax,
+                pattern,
+                temp_file.path().to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, expected, "{syntax} {pattern}");
+    }
+}
+
+#[test]
+fn test_cli_invalid_utf8_lines() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file
+        .write_all(b"log \xFF\xFE start\nplain line\nlog end \xE9\n")
+        .unwrap();
+
+    // UTF-8 として不正な行があっても最後まで検索し、行はそのまま出力される
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "regex-cli",
+            "--bin",
+            "regex",
+            "--",
+            "-n",
+            "^log",
+            temp_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(
+        output.stdout,
+        b"1:log \xFF\xFE start\n3:log end \xE9\n".to_vec()
+    );
+
+    // (?-u) で生のバイトにマッチする
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "regex-cli",
+            "--bin",
+            "regex",
+            "--",
+            "-c",
+            "(?-u)\\xFF",
+            temp_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "1");
+}
@@ -1,5 +1,5 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
-use regex_core::Regex;
+use regex_core::{Regex, bytes};
 
 fn bench_compile(c: &mut Criterion) {
     let mut group = c.benchmark_group("compile");
@@ -60,5 +60,56 @@ fn bench_backreference(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_compile, bench_match, bench_backreference);
+fn bench_literal_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("literal_search");
+    let line = format!("{}needle", "haystack without the word ".repeat(400));
+
+    let must_literal = Regex::new("need(le|ed)+", false, false).unwrap();
+    group.bench_function("regex_must_literal_long_line", |b| {
+        b.iter(|| {
+            let matched = must_literal.is_match(black_box(&line)).unwrap();
+            black_box(matched);
+        })
+    });
+
+    let literals = Regex::new("needle|pin|thread", false, false).unwrap();
+    group.bench_function("regex_literals_long_line", |b| {
+        b.iter(|| {
+            let matched = literals.is_match(black_box(&line)).unwrap();
+            black_box(matched);
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_bytes_lines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes_lines");
+    // Lines as the CLI reads them, none containing a match.
+    let lines: Vec<Vec<u8>> = (0..1000)
+        .map(|i| format!("{i}: the quick brown fox jumps over the lazy dog").into_bytes())
+        .collect();
+
+    for pattern in ["zebra", "Quick[a-z]*z", "[a-z]+@example\\.com"] {
+        let regex = bytes::Regex::new(pattern, false, false).unwrap();
+        group.bench_function(format!("bytes_no_match/{pattern}"), |b| {
+            b.iter(|| {
+                for line in &lines {
+                    black_box(regex.is_match(black_box(line)).unwrap());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_compile,
+    bench_match,
+    bench_backreference,
+    bench_literal_search,
+    bench_bytes_lines
+);
 criterion_main!(benches);
@@ -0,0 +1,122 @@
+//! Builder for configuring how a `Regex` is compiled.
+
+use crate::{
+    Regex,
+    engine::{DEFAULT_STATE_LIMIT, MatchKind, ParseOptions, Syntax},
+    error::RegexError,
+};
+
+/// Configures and builds a `Regex`.
+///
+/// `Regex::new` covers the common options; the builder exposes the rest.
+#[derive(Debug, Clone)]
+pub struct RegexBuilder {
+    pattern: String,
+    options: ParseOptions,
+    invert_match: bool,
+    match_kind: MatchKind,
+    dfa: bool,
+    dfa_state_limit: usize,
+    anchored: bool,
+}
+
+impl RegexBuilder {
+    /// Creates a builder for `pattern` with every option disabled.
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            options: ParseOptions::default(),
+            invert_match: false,
+            match_kind: MatchKind::default(),
+            dfa: false,
+            dfa_state_limit: DEFAULT_STATE_LIMIT,
+            anchored: false,
+        }
+    }
+
+    /// Enables case-insensitive matching.
+    pub fn ignore_case(&mut self, yes: bool) -> &mut Self {
+        self.options.ignore_case = yes;
+        self
+    }
+
+    /// Inverts the result of `Regex::is_match`.
+    pub fn invert_match(&mut self, yes: bool) -> &mut Self {
+        self.invert_match = yes;
+        self
+    }
+
+    /// Enables CRLF mode.
+    ///
+    /// `^` and `$` treat `\r\n` as a single line terminator (and also accept
+    /// a lone `\r` or `\n`), and `.` matches neither `\r` nor `\n`.
+    pub fn crlf(&mut self, yes: bool) -> &mut Self {
+        self.options.crlf = yes;
+        self
+    }
+
+    /// Selects the syntax dialect of the pattern (Perl-like by default).
+    pub fn syntax(&mut self, syntax: Syntax) -> &mut Self {
+        self.options.syntax = syntax;
+        self
+    }
+
+    /// Enables POSIX leftmost-longest semantics.
+    ///
+    /// Among the matches starting at the leftmost position, `find`,
+    /// `find_iter` and `captures` report the longest one instead of the first
+    /// in priority order, so `a|ab` matches `ab` in `abc`. Ties are broken
+    /// by POSIX subexpression rules: each group in order prefers the
+    /// leftmost start, then the longest span. `is_match` is unaffected.
+    pub fn leftmost_longest(&mut self, yes: bool) -> &mut Self {
+        self.match_kind = if yes {
+            MatchKind::LeftmostLongest
+        } else {
+            MatchKind::LeftmostFirst
+        };
+        self
+    }
+
+    /// Compiles the pattern ahead of time into a minimized DFA for
+    /// `is_match`.
+    ///
+    /// Building the DFA can be slow and memory-hungry, but each line is then
+    /// matched with one table lookup per character. `build` fails with
+    /// `CompileError::DfaTooLarge` if the DFA needs more states than
+    /// `dfa_state_limit`, and with `CompileError::DfaUnsupported` if the
+    /// pattern uses backreferences, subroutine calls or `\X`.
+    pub fn dfa(&mut self, yes: bool) -> &mut Self {
+        self.dfa = yes;
+        self
+    }
+
+    /// Sets how many states the DFA enabled by `dfa` may have (10,000 by
+    /// default).
+    pub fn dfa_state_limit(&mut self, states: usize) -> &mut Self {
+        self.dfa_state_limit = states;
+        self
+    }
+
+    /// Only reports matches that start where the search starts, as if the
+    /// pattern began with `\G`.
+    ///
+    /// `is_match`, `find` and `captures` then match at the start of the
+    /// text only, `Regex::is_match_at` at the given offset, and `find_iter`
+    /// stops at the first gap between matches.
+    pub fn anchored(&mut self, yes: bool) -> &mut Self {
+        self.anchored = yes;
+        self
+    }
+
+    /// Compiles the pattern with the configured options.
+    pub fn build(&self) -> Result<Regex, RegexError> {
+        Regex::with_options(
+            &self.pattern,
+            self.options,
+            self.invert_match,
+            self.match_kind,
+            self.dfa.then_some(self.dfa_state_limit),
+            self.anchored,
+        )
+    }
+}
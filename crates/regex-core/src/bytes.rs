@@ -0,0 +1,664 @@
+//! Regular expressions over byte slices that need not be valid UTF-8.
+//!
+//! Patterns use the same syntax as `crate::Regex`, and may additionally
+//! disable Unicode mode with `(?-u)` (or `(?-u:...)` for a group). Without
+//! Unicode mode, `.`, `[...]` and `\xHH` match single raw bytes, so
+//! `(?-u)\xFF` finds a byte that never occurs in UTF-8 text. In Unicode
+//! mode, characters only match their well-formed UTF-8 encodings and
+//! invalid bytes are skipped over.
+//!
+//! Valid UTF-8 haystacks are searched by `crate::Regex`, so they get the
+//! same engine selection and literal prefilters. Other haystacks run on a
+//! byte program; patterns with backreferences, subroutine calls or `\X`
+//! run on the character engines, which see each invalid sequence as U+FFFD.
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::{
+    Strategy,
+    engine::{
+        self, ByteInstruction, Instruction, LazyDfa, MatchKind, ParseOptions, Syntax, compile_utf8,
+    },
+    error::RegexError,
+    matches::{SearchText, char_boundary},
+};
+
+/// Regular expression matching `&[u8]` haystacks.
+///
+/// Offsets in matches are byte offsets into the haystack. Valid UTF-8
+/// haystacks are searched as `crate::Regex` searches them, with the same
+/// engines and literal prefilters, unless `(?-u)` lets the pattern match
+/// inside a character.
+pub struct Regex {
+    /// The pattern compiled for `str` haystacks, which also holds the
+    /// instruction sequence.
+    regex: crate::Regex,
+    /// Whether `regex` answers valid UTF-8 haystacks.
+    is_unicode: bool,
+    /// UTF-8 byte program, unless the pattern needs a character engine.
+    program: Option<Vec<ByteInstruction>>,
+    /// `program` with `\G` never holding, for `find_iter` searches resuming
+    /// past the end of the previous match; `None` without `\G`.
+    past_search: Option<Vec<ByteInstruction>>,
+    /// Lazy DFA answering `is_match`, when the program supports one.
+    lazy_dfa: Option<LazyDfa>,
+    /// Enables case-insensitive matching by lowercasing pattern/input.
+    is_ignore_case: bool,
+    /// Inverts the result of `is_match`.
+    is_invert_match: bool,
+}
+
+impl Regex {
+    /// Create a new `bytes::Regex`.
+    ///
+    /// Use `bytes::RegexBuilder` for options beyond case-insensitivity and
+    /// inversion.
+    pub fn new(
+        pattern: &str,
+        is_ignore_case: bool,
+        is_invert_match: bool,
+    ) -> Result<Self, RegexError> {
+        RegexBuilder::new(pattern)
+            .ignore_case(is_ignore_case)
+            .invert_match(is_invert_match)
+            .build()
+    }
+
+    /// Compiles `pattern` with parser `options`.
+    fn with_options(
+        pattern: &str,
+        options: ParseOptions,
+        is_invert_match: bool,
+    ) -> Result<Self, RegexError> {
+        let ast = engine::parse_with_options(pattern, options)?;
+        let regex = crate::Regex::from_parsed(
+            &ast,
+            options.ignore_case,
+            false,
+            MatchKind::default(),
+            None,
+            false,
+        )?;
+        let is_unicode = !regex
+            .code
+            .iter()
+            .any(|instruction| matches!(instruction, Instruction::ByteClass(_)));
+        Ok(Self {
+            program: compile_utf8(&regex.code),
+            past_search: regex.past_search.as_deref().and_then(compile_utf8),
+            lazy_dfa: LazyDfa::new(&regex.code),
+            regex,
+            is_unicode,
+            is_ignore_case: options.ignore_case,
+            is_invert_match,
+        })
+    }
+
+    /// Returns the engine chosen to answer `is_match` for valid UTF-8
+    /// haystacks; see `crate::Regex::strategy`.
+    pub fn strategy(&self) -> Strategy {
+        self.regex.strategy()
+    }
+
+    /// Match a line against the compiled pattern.
+    pub fn is_match(&self, line: &[u8]) -> Result<bool, RegexError> {
+        if let Some(text) = self.as_text(line) {
+            return Ok(self.regex.is_match(text)? ^ self.is_invert_match);
+        }
+        let input = self.prepare(line);
+        let dfa_result = match (&self.lazy_dfa, &input) {
+            (Some(lazy_dfa), SearchInput::Bytes { bytes, .. }) => lazy_dfa.is_match(bytes)?,
+            _ => None,
+        };
+        let is_match = match dfa_result {
+            Some(is_match) => is_match,
+            None => input.find_at(0)?.is_some(),
+        };
+
+        Ok(is_match ^ self.is_invert_match)
+    }
+
+    /// Returns the leftmost match in `haystack`.
+    ///
+    /// The reported span honors `\K`; `is_invert_match` does not apply here.
+    pub fn find<'h>(&self, haystack: &'h [u8]) -> Result<Option<Match<'h>>, RegexError> {
+        if let Some(text) = self.as_text(haystack) {
+            let found = self.regex.find(text)?;
+            return Ok(found.map(|m| Match::new(haystack, m.start(), m.end())));
+        }
+        let input = self.prepare(haystack);
+        let span = input.find_at(0)?;
+        Ok(span.map(|(start, end)| {
+            let (start, end) = input.span(start, end);
+            Match::new(haystack, start, end)
+        }))
+    }
+
+    /// Returns an iterator over successive non-overlapping matches in
+    /// `haystack`.
+    pub fn find_iter<'r, 'h>(&'r self, haystack: &'h [u8]) -> FindMatches<'r, 'h> {
+        let searcher = match self.as_text(haystack) {
+            Some(text) => Searcher::Text(self.regex.find_iter(text)),
+            None => Searcher::Input {
+                input: self.prepare(haystack),
+                is_unicode: self.is_unicode,
+                next_start: 0,
+                last_end: None,
+                done: false,
+            },
+        };
+        FindMatches { haystack, searcher }
+    }
+
+    /// Returns `haystack` as text if `regex` can search it.
+    fn as_text<'h>(&self, haystack: &'h [u8]) -> Option<&'h str> {
+        if !self.is_unicode {
+            return None;
+        }
+        std::str::from_utf8(haystack).ok()
+    }
+
+    /// Prepares `haystack` for the engine that runs this pattern.
+    fn prepare<'r, 'h>(&'r self, haystack: &'h [u8]) -> SearchInput<'r, 'h> {
+        match &self.program {
+            Some(program) if self.is_ignore_case => {
+                let (bytes, offsets) = lowercase(haystack);
+                SearchInput::Bytes {
+                    program,
+                    past_search: self.past_search.as_deref(),
+                    bytes: Cow::Owned(bytes),
+                    offsets: Some(offsets),
+                }
+            }
+            Some(program) => SearchInput::Bytes {
+                program,
+                past_search: self.past_search.as_deref(),
+                bytes: Cow::Borrowed(haystack),
+                offsets: None,
+            },
+            None => SearchInput::Chars {
+                code: &self.regex.code,
+                past_search: self.regex.past_search.as_deref(),
+                text: SearchText::from_bytes(haystack, self.is_ignore_case),
+            },
+        }
+    }
+}
+
+/// Haystack prepared for the engine running a pattern.
+enum SearchInput<'r, 'h> {
+    /// Input of the byte Pike VM. Lowercasing can change byte lengths, so
+    /// lowercased bytes carry their haystack offsets followed by the
+    /// haystack length.
+    Bytes {
+        program: &'r [ByteInstruction],
+        past_search: Option<&'r [ByteInstruction]>,
+        bytes: Cow<'h, [u8]>,
+        offsets: Option<Vec<usize>>,
+    },
+    /// Input of the character engines.
+    Chars {
+        code: &'r [Instruction],
+        past_search: Option<&'r [Instruction]>,
+        text: SearchText,
+    },
+}
+
+impl SearchInput<'_, '_> {
+    /// Returns the last position a search can start from.
+    fn len(&self) -> usize {
+        match self {
+            SearchInput::Bytes { bytes, .. } => bytes.len(),
+            SearchInput::Chars { text, .. } => text.chars.len(),
+        }
+    }
+
+    /// Returns the input position one character after `index`, or one
+    /// byte after it without `is_unicode` or inside invalid UTF-8.
+    fn next_position(&self, index: usize, is_unicode: bool) -> usize {
+        let SearchInput::Bytes { bytes, .. } = self else {
+            return index + 1;
+        };
+        let len = match bytes.get(index) {
+            Some(0xC2..=0xDF) => 2,
+            Some(0xE0..=0xEF) => 3,
+            Some(0xF0..=0xF4) => 4,
+            _ => 1,
+        };
+        let is_char = bytes
+            .get(index..index + len)
+            .is_some_and(|encoded| std::str::from_utf8(encoded).is_ok());
+        index + if is_unicode && is_char { len } else { 1 }
+    }
+
+    /// Returns the haystack offsets of each input position, or `None`
+    /// where positions are haystack offsets.
+    fn offsets(&self) -> Option<&[usize]> {
+        match self {
+            SearchInput::Bytes { offsets, .. } => offsets.as_deref(),
+            SearchInput::Chars { text, .. } => Some(&text.offsets),
+        }
+    }
+
+    /// Returns the first input position at or after `index` where a
+    /// haystack character starts; see `SearchText::boundary`.
+    fn boundary(&self, index: usize) -> usize {
+        match self.offsets() {
+            Some(offsets) => char_boundary(offsets, index),
+            None => index,
+        }
+    }
+
+    /// Returns the haystack span of input positions `start..end`, widened
+    /// to whole haystack characters as `SearchText::span` does.
+    fn span(&self, start: usize, end: usize) -> (usize, usize) {
+        match self.offsets() {
+            Some(offsets) => (offsets[start], offsets[char_boundary(offsets, end)]),
+            None => (start, end),
+        }
+    }
+
+    /// Finds the leftmost-first match at or after input position `start`.
+    ///
+    /// Returns the matched `(start, end)` span as input positions.
+    fn find_at(&self, start: usize) -> Result<Option<(usize, usize)>, RegexError> {
+        match self {
+            SearchInput::Bytes { program, bytes, .. } => engine::find_bytes(program, bytes, start),
+            SearchInput::Chars { code, text, .. } => {
+                engine::find_line(code, &text.chars, start, MatchKind::LeftmostFirst)
+            }
+        }
+    }
+
+    /// Finds the leftmost-first match at or after input position `start`
+    /// when `\G` holds before it, so that no match can use `\G`.
+    fn find_past_search(&self, start: usize) -> Result<Option<(usize, usize)>, RegexError> {
+        match self {
+            SearchInput::Bytes {
+                past_search: Some(program),
+                bytes,
+                ..
+            } => engine::find_bytes(program, bytes, start),
+            SearchInput::Chars {
+                past_search: Some(code),
+                text,
+                ..
+            } => engine::find_line(code, &text.chars, start, MatchKind::LeftmostFirst),
+            _ => self.find_at(start),
+        }
+    }
+}
+
+/// Lowercases the valid UTF-8 parts of `haystack`.
+///
+/// Returns the lowercased bytes and the haystack offset of each of them,
+/// followed by the haystack length. Invalid bytes are kept as they are.
+fn lowercase(haystack: &[u8]) -> (Vec<u8>, Vec<usize>) {
+    let mut bytes = Vec::with_capacity(haystack.len());
+    let mut offsets = Vec::with_capacity(haystack.len() + 1);
+    let mut offset = 0;
+    for chunk in haystack.utf8_chunks() {
+        for (index, ch) in chunk.valid().char_indices() {
+            let mut buffer = [0; 4];
+            for lower in ch.to_lowercase() {
+                let encoded = lower.encode_utf8(&mut buffer);
+                bytes.extend_from_slice(encoded.as_bytes());
+                offsets.extend(std::iter::repeat_n(offset + index, encoded.len()));
+            }
+        }
+        offset += chunk.valid().len();
+        for &byte in chunk.invalid() {
+            bytes.push(byte);
+            offsets.push(offset);
+            offset += 1;
+        }
+    }
+    offsets.push(haystack.len());
+    (bytes, offsets)
+}
+
+/// Configures and builds a `bytes::Regex`.
+#[derive(Debug, Clone)]
+pub struct RegexBuilder {
+    pattern: String,
+    options: ParseOptions,
+    invert_match: bool,
+}
+
+impl RegexBuilder {
+    /// Creates a builder for `pattern` with every option disabled.
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            options: ParseOptions {
+                allow_invalid_utf8: true,
+                ..ParseOptions::default()
+            },
+            invert_match: false,
+        }
+    }
+
+    /// Enables case-insensitive matching.
+    pub fn ignore_case(&mut self, yes: bool) -> &mut Self {
+        self.options.ignore_case = yes;
+        self
+    }
+
+    /// Inverts the result of `Regex::is_match`.
+    pub fn invert_match(&mut self, yes: bool) -> &mut Self {
+        self.invert_match = yes;
+        self
+    }
+
+    /// Enables CRLF mode; see `crate::RegexBuilder::crlf`.
+    pub fn crlf(&mut self, yes: bool) -> &mut Self {
+        self.options.crlf = yes;
+        self
+    }
+
+    /// Selects the syntax dialect of the pattern (Perl-like by default).
+    pub fn syntax(&mut self, syntax: Syntax) -> &mut Self {
+        self.options.syntax = syntax;
+        self
+    }
+
+    /// Compiles the pattern with the configured options.
+    pub fn build(&self) -> Result<Regex, RegexError> {
+        Regex::with_options(&self.pattern, self.options, self.invert_match)
+    }
+}
+
+/// One match in a byte haystack.
+///
+/// Offsets are byte offsets into the haystack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'h> {
+    haystack: &'h [u8],
+    start: usize,
+    end: usize,
+}
+
+impl<'h> Match<'h> {
+    /// Creates a match for `haystack[start..end]`.
+    fn new(haystack: &'h [u8], start: usize, end: usize) -> Self {
+        Self {
+            haystack,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the byte offset where the match starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte offset where the match ends (exclusive).
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the byte range of the match.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns whether the match is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the length of the match in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns the matched bytes.
+    pub fn as_bytes(&self) -> &'h [u8] {
+        &self.haystack[self.range()]
+    }
+}
+
+/// Iterator over successive non-overlapping matches in a byte haystack.
+///
+/// Created by `bytes::Regex::find_iter`. Each search starts where the
+/// previous match ended, which is the position `\G` refers to.
+pub struct FindMatches<'r, 'h> {
+    haystack: &'h [u8],
+    searcher: Searcher<'r, 'h>,
+}
+
+/// How `FindMatches` searches its haystack.
+enum Searcher<'r, 'h> {
+    /// A valid UTF-8 haystack, searched as text.
+    Text(crate::FindMatches<'r, 'h>),
+    /// Any other haystack. `last_end` is the input position where the
+    /// previous match ended.
+    Input {
+        input: SearchInput<'r, 'h>,
+        is_unicode: bool,
+        next_start: usize,
+        last_end: Option<usize>,
+        done: bool,
+    },
+}
+
+impl<'h> Iterator for FindMatches<'_, 'h> {
+    type Item = Result<Match<'h>, RegexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (input, is_unicode, next_start, last_end, done) = match &mut self.searcher {
+            Searcher::Text(matches) => {
+                let found = matches.next()?;
+                return Some(found.map(|m| Match::new(self.haystack, m.start(), m.end())));
+            }
+            Searcher::Input {
+                input,
+                is_unicode,
+                next_start,
+                last_end,
+                done,
+            } => (input, *is_unicode, next_start, last_end, done),
+        };
+
+        while !*done && *next_start <= input.len() {
+            // `\G` stays at the previous match end when an empty match
+            // moved the search past it.
+            let found = if last_end.is_none_or(|end| end == *next_start) {
+                input.find_at(*next_start)
+            } else {
+                input.find_past_search(*next_start)
+            };
+            let (start, end) = match found {
+                Ok(Some(span)) => span,
+                Ok(None) => break,
+                Err(e) => {
+                    *done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            if start == end && *last_end == Some(end) {
+                // An empty match right after the previous one would be
+                // reported twice.
+                *next_start = input.boundary(input.next_position(end, is_unicode));
+                continue;
+            }
+
+            // The next search starts after the whole haystack character
+            // the match ends in.
+            let (start_offset, end_offset) = input.span(start, end);
+            *next_start = input.boundary(end);
+            *last_end = Some(*next_start);
+            return Some(Ok(Match::new(self.haystack, start_offset, end_offset)));
+        }
+
+        *done = true;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn test_is_match_invalid_utf8() {
+        let regex = Regex::new("ab(c|d)", false, false).unwrap();
+        assert!(regex.is_match(b"\xFFabc\xFE").unwrap());
+        assert!(!regex.is_match(b"ab\xFFc").unwrap());
+
+        let regex = Regex::new("(?-u)\\xFF", false, false).unwrap();
+        assert!(regex.is_match(b"a\xFFb").unwrap());
+        assert!(!regex.is_match("ÿ".as_bytes()).unwrap());
+
+        let regex = Regex::new("ab(c|d)", false, true).unwrap();
+        assert!(regex.is_match(b"\xFF").unwrap());
+    }
+
+    #[test]
+    fn test_find_byte_offsets() {
+        let regex = Regex::new("(?-u)[\\x80-\\xFF]+", false, false).unwrap();
+        let m = regex.find(b"ab\xE9\xFFc").unwrap().unwrap();
+        assert_eq!(m.range(), 2..4);
+        assert_eq!(m.as_bytes(), b"\xE9\xFF");
+
+        let regex = Regex::new("é+", false, false).unwrap();
+        let m = regex.find(b"\xE9 \xC3\xA9\xC3\xA9").unwrap().unwrap();
+        assert_eq!(m.range(), 2..6);
+
+        let regex = Regex::new("key=\\K[a-z]+", false, false).unwrap();
+        let m = regex.find(b"\xFF key=value").unwrap().unwrap();
+        assert_eq!(m.as_bytes(), b"value");
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let regex = Regex::new("[0-9]+", false, false).unwrap();
+        let found: Vec<_> = regex
+            .find_iter(b"1\xFF23\xC3x456")
+            .map(|m| m.unwrap().as_bytes())
+            .collect();
+        assert_eq!(found, vec![&b"1"[..], b"23", b"456"]);
+
+        let regex = Regex::new("(?-u).", false, false).unwrap();
+        assert_eq!(regex.find_iter("日".as_bytes()).count(), 3);
+        let regex = Regex::new("a*", false, false).unwrap();
+        let ranges: Vec<_> = regex
+            .find_iter(b"\xFFaa")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(ranges, vec![0..0, 1..3]);
+    }
+
+    #[test]
+    fn test_find_iter_empty_matches_between_characters() {
+        for (pattern, ignore_case) in [("c*", false), ("c*", true), ("x?", true), ("É|", true)] {
+            let regex = Regex::new(pattern, ignore_case, false).unwrap();
+            let text_regex = crate::Regex::new(pattern, ignore_case, false).unwrap();
+            for text in ["É", "é", "日本", "aÉb", "İİ", "İa"] {
+                let expected: Vec<_> = text_regex
+                    .find_iter(text)
+                    .map(|m| m.unwrap().range())
+                    .collect();
+                let found: Vec<_> = regex
+                    .find_iter(text.as_bytes())
+                    .map(|m| m.unwrap().range())
+                    .collect();
+                assert_eq!(found, expected, "{pattern} on {text:?}");
+
+                // The same text after an invalid byte takes the byte engine.
+                let haystack = [b"\xFF", text.as_bytes()].concat();
+                let found: Vec<_> = regex
+                    .find_iter(&haystack)
+                    .map(|m| m.unwrap().range())
+                    .collect();
+                let shifted = expected.iter().map(|range| range.start + 1..range.end + 1);
+                let expected: Vec<_> = std::iter::once(0..0).chain(shifted).collect();
+                assert_eq!(found, expected, "{pattern} on \\xFF{text:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_iter_ignore_case_keeps_characters_whole() {
+        // `İ` lowercases to `i` followed by U+0307.
+        let regex = Regex::new("i", true, false).unwrap();
+        let ranges: Vec<_> = regex
+            .find_iter(b"\xC4\xB0\xFF")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(ranges, vec![0..2]);
+        assert_eq!(regex.find(b"\xFF\xC4\xB0").unwrap().unwrap().range(), 1..3);
+    }
+
+    #[test]
+    fn test_find_iter_start_of_search_after_empty_match() {
+        for pattern in ["\\Gx*", "\\G(x*)\\1"] {
+            let regex = Regex::new(pattern, false, false).unwrap();
+            let ranges: Vec<_> = regex
+                .find_iter(b"\xFFxx")
+                .map(|m| m.unwrap().range())
+                .collect();
+            assert_eq!(ranges, vec![0..0], "{pattern}");
+        }
+    }
+
+    #[test]
+    fn test_valid_utf8_uses_selected_engine() {
+        let regex = Regex::new("zebra", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Literal);
+        let regex = Regex::new("[a-z]+@example\\.com", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::ReverseSuffix);
+        assert!(regex.as_text(b"me@example.com").is_some());
+        assert!(regex.as_text(b"\xFFme@example.com").is_none());
+        assert!(regex.is_match(b"\xFFme@example.com").unwrap());
+
+        // `(?-u)` patterns may match inside a character, so only the byte
+        // engine runs them.
+        let regex = Regex::new("(?-u)\\xA9", false, false).unwrap();
+        assert!(regex.as_text(b"caf\xC3\xA9").is_none());
+        assert!(regex.is_match("café".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let regex = Regex::new("straße", true, false).unwrap();
+        let m = regex.find(b"\xFF STRA\xC3\x9FE!").unwrap().unwrap();
+        assert_eq!(m.range(), 2..9);
+
+        let regex = Regex::new("(?-u)[A-Z]\\xC9", true, false).unwrap();
+        assert!(regex.is_match(b"x\xC9").unwrap());
+        assert!(!regex.is_match(b"x\xE9").unwrap());
+    }
+
+    #[test]
+    fn test_linebreak_escape_does_not_split_crlf() {
+        let regex = Regex::new("a\\R{2}b", false, false).unwrap();
+        assert!(!regex.is_match(b"\xFFa\r\nb").unwrap());
+        assert!(regex.is_match(b"\xFFa\r\n\rb").unwrap());
+    }
+
+    #[test]
+    fn test_character_engine_fallback() {
+        let regex = Regex::new("(a.)\\1", false, false).unwrap();
+        let m = regex.find(b"\xFFab\xFEabab\xFE").unwrap().unwrap();
+        assert_eq!(m.range(), 4..8);
+        assert!(!regex.is_match(b"ab\xFEab").unwrap());
+    }
+
+    #[test]
+    fn test_unicode_mode_requires_bytes_regex() {
+        assert_eq!(
+            crate::Regex::new("(?-u)a", false, false).err(),
+            Some(RegexError::Parse(ParseError::UnicodeRequired))
+        );
+        assert!(
+            crate::Regex::new("\\xFF", false, false)
+                .unwrap()
+                .is_match("ÿ")
+                .unwrap()
+        );
+    }
+}
@@ -1,23 +1,53 @@
 //! Core functions for compiling and matching regex patterns.
+mod aho_corasick;
+mod anchor;
 mod ast;
+mod bitap;
+mod bounded_backtracker;
+mod byte_pike_vm;
 mod compiler;
+mod dfa;
 mod evaluator;
+mod grapheme;
 mod instruction;
+mod lazy_dfa;
+mod literal_set;
+mod memchr;
+mod onepass;
 mod parser;
+mod pike_vm;
+mod reverse_suffix;
+mod syntax;
+mod teddy;
+mod utf8;
 
 use thiserror::Error;
 
 use crate::engine::{
-    compiler::compile,
-    evaluator::{eval, eval_from_starts},
-    parser::parse,
+    anchor::anchored_starts,
+    compiler::{compile, compile_anchored},
+    evaluator::{eval_captures, eval_find, eval_from_starts},
 };
 
-pub(crate) use ast::{Ast, AstAnalysis, analyze_ast};
+pub(crate) use aho_corasick::AhoCorasick;
+pub(crate) use anchor::{end_anchor, start_anchor, without_search_anchor};
+pub(crate) use ast::{Ast, AstAnalysis, CharClass, CharRange, Predicate, analyze_ast};
+pub(crate) use bitap::Bitap;
 pub use compiler::CompileError;
+pub(crate) use compiler::compile_reverse;
+pub(crate) use dfa::{DEFAULT_STATE_LIMIT, Dfa};
 pub use evaluator::EvalError;
+pub(crate) use evaluator::{MatchKind, Slots};
 pub use instruction::Instruction;
+pub(crate) use lazy_dfa::LazyDfa;
+pub(crate) use literal_set::LiteralSet;
+pub(crate) use memchr::memmem;
+pub(crate) use onepass::OnePass;
 pub use parser::ParseError;
+pub(crate) use parser::{ParseOptions, parse_with_options};
+pub(crate) use reverse_suffix::ReverseSuffix;
+pub use syntax::Syntax;
+pub(crate) use utf8::{ByteInstruction, compile_utf8};
 
 /// Unified error type for parse, compile, and evaluation stages.
 #[derive(Debug, Error, PartialEq)]
@@ -64,10 +94,29 @@ where
 /// Parse, analyze, and compile a pattern.
 pub(crate) fn compile_pattern_with_analysis(
     pattern: &str,
+    options: ParseOptions,
 ) -> Result<(Vec<Instruction>, AstAnalysis), RegexError> {
-    let ast: Ast = parse(pattern)?;
-    let analysis = analyze_ast(&ast);
-    let instructions = compile(&ast)?;
+    let ast: Ast = parse_with_options(pattern, options)?;
+    compile_ast_with_analysis(&ast, false)
+}
+
+/// Analyze and compile an already built AST.
+///
+/// With `anchored`, matches may only start where the search starts, as if
+/// the pattern began with `\G`.
+pub(crate) fn compile_ast_with_analysis(
+    ast: &Ast,
+    anchored: bool,
+) -> Result<(Vec<Instruction>, AstAnalysis), RegexError> {
+    if anchored {
+        let analysis = analyze_ast(&Ast::Concat(vec![
+            Ast::Assertion(Predicate::StartOfSearch),
+            ast.clone(),
+        ]));
+        return Ok((compile_anchored(ast)?, analysis));
+    }
+    let analysis = analyze_ast(ast);
+    let instructions = compile(ast)?;
     Ok((instructions, analysis))
 }
 
@@ -76,13 +125,111 @@ pub(crate) fn compile_pattern_with_analysis(
 pub(crate) fn compile_pattern_with_must_literals(
     pattern: &str,
 ) -> Result<(Vec<Instruction>, Vec<String>), RegexError> {
-    let (instructions, analysis) = compile_pattern_with_analysis(pattern)?;
+    let (instructions, analysis) = compile_pattern_with_analysis(pattern, ParseOptions::default())?;
     Ok((instructions, analysis.must_literals))
 }
 
+/// Runs a leftmost-first search with the cheapest engine that supports `code`.
+///
+/// The bounded backtracker handles small searches, the Pike VM larger
+/// ones, and the backtracking evaluator programs with backreferences or
+/// subroutine calls. `starts` restricts where a match may begin, as does a
+/// start anchor of the program; with `earliest` only the presence of a
+/// match is meaningful.
+fn search_leftmost_first(
+    code: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    starts: Option<&[usize]>,
+    earliest: bool,
+) -> Result<Option<Slots>, EvalError> {
+    let anchored;
+    let starts = match starts {
+        Some(starts) => Some(starts),
+        None => {
+            anchored =
+                start_anchor(code).map(|anchor| anchored_starts(anchor, chars, search_start));
+            anchored.as_deref()
+        }
+    };
+    if bounded_backtracker::is_supported(code, chars.len()) {
+        return bounded_backtracker::search(code, chars, search_start, starts);
+    }
+    if pike_vm::is_supported(code) {
+        return pike_vm::search(code, chars, search_start, starts, earliest);
+    }
+    match starts {
+        None => eval_captures(code, chars, search_start, MatchKind::LeftmostFirst),
+        Some(starts) => eval_from_starts(code, chars, search_start, starts),
+    }
+}
+
+/// Returns whether `code` needs the backtracking evaluator, as it has
+/// instructions the Pike VM cannot run.
+pub(crate) fn needs_backtracking(code: &[Instruction]) -> bool {
+    !pike_vm::is_supported(code)
+}
+
 /// Match an instruction sequence against a line.
 pub fn match_line(code: &[Instruction], line: &str) -> Result<bool, RegexError> {
-    Ok(eval(code, line)?)
+    let chars: Vec<char> = line.chars().collect();
+    Ok(search_leftmost_first(code, &chars, 0, None, true)?.is_some())
+}
+
+/// Find the leftmost match in `chars` that starts at or after `search_start`.
+///
+/// Returns the matched `(start, end)` span as character indices.
+pub(crate) fn find_line(
+    code: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    kind: MatchKind,
+) -> Result<Option<(usize, usize)>, RegexError> {
+    if kind == MatchKind::LeftmostFirst {
+        let slots = search_leftmost_first(code, chars, search_start, None, false)?;
+        return Ok(slots.and_then(|slots| slots[0]));
+    }
+    Ok(eval_find(code, chars, search_start, kind)?)
+}
+
+/// Find the leftmost-first match in `chars` that starts at `start`.
+///
+/// Returns the matched `(start, end)` span as character indices.
+pub(crate) fn find_line_from(
+    code: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    start: usize,
+) -> Result<Option<(usize, usize)>, RegexError> {
+    let slots = search_leftmost_first(code, chars, search_start, Some(&[start]), false)?;
+    Ok(slots.and_then(|slots| slots[0]))
+}
+
+/// Find the leftmost match in `chars` and return the spans of its groups.
+///
+/// Slot 0 is the whole match; spans are character indices. Leftmost-longest
+/// captures need every path explored, so they always use the evaluator.
+pub(crate) fn captures_line(
+    code: &[Instruction],
+    chars: &[char],
+    kind: MatchKind,
+) -> Result<Option<Slots>, RegexError> {
+    if kind == MatchKind::LeftmostFirst {
+        return Ok(search_leftmost_first(code, chars, 0, None, false)?);
+    }
+    Ok(eval_captures(code, chars, 0, kind)?)
+}
+
+/// Find the leftmost-first match in the byte `haystack` that starts at or
+/// after `search_start`.
+///
+/// Returns the matched `(start, end)` span as byte offsets.
+pub(crate) fn find_bytes(
+    program: &[ByteInstruction],
+    haystack: &[u8],
+    search_start: usize,
+) -> Result<Option<(usize, usize)>, RegexError> {
+    Ok(byte_pike_vm::find(program, haystack, search_start)?)
 }
 
 /// Match an instruction sequence from provided starting character indices.
@@ -91,15 +238,16 @@ pub(crate) fn match_line_from_starts(
     line: &str,
     starts: &[usize],
 ) -> Result<bool, RegexError> {
-    Ok(eval_from_starts(code, line, starts)?)
+    let chars: Vec<char> = line.chars().collect();
+    Ok(search_leftmost_first(code, &chars, 0, Some(starts), true)?.is_some())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::engine::{
-        CompileError, RegexError, compile_pattern_with_analysis,
-        compile_pattern_with_must_literals, instruction::Instruction, match_line,
-        match_line_from_starts,
+        CompileError, MatchKind, ParseOptions, RegexError, captures_line,
+        compile_pattern_with_analysis, compile_pattern_with_must_literals, find_line,
+        instruction::Instruction, match_line, match_line_from_starts,
     };
 
     #[test]
@@ -133,12 +281,60 @@ mod tests {
 
     #[test]
     fn test_compile_pattern_with_analysis() {
-        let (_code, analysis) = compile_pattern_with_analysis("(abc|def)").unwrap();
+        let (_code, analysis) =
+            compile_pattern_with_analysis("(abc|def)", ParseOptions::default()).unwrap();
         assert!(analysis.must_literals.is_empty());
         assert_eq!(analysis.needles, vec!["abc".to_string(), "def".to_string()]);
         assert!(!analysis.nullable);
     }
 
+    #[test]
+    fn test_find_line() {
+        let (code, _) = compile_pattern_with_must_literals("b+").unwrap();
+        let chars: Vec<char> = "abbcb".chars().collect();
+        let kind = MatchKind::LeftmostFirst;
+        assert_eq!(find_line(&code, &chars, 0, kind).unwrap(), Some((1, 3)));
+        assert_eq!(find_line(&code, &chars, 3, kind).unwrap(), Some((4, 5)));
+    }
+
+    #[test]
+    fn test_find_line_anchored() {
+        let kind = MatchKind::LeftmostFirst;
+        let chars: Vec<char> = "xa\naa\nba".chars().collect();
+        let (code, _) = compile_pattern_with_must_literals("^(a)\\1?").unwrap();
+        assert_eq!(find_line(&code, &chars, 0, kind).unwrap(), Some((3, 5)));
+        assert_eq!(find_line(&code, &chars, 4, kind).unwrap(), None);
+        let (code, _) = compile_pattern_with_must_literals("\\G(a)\\1").unwrap();
+        assert_eq!(find_line(&code, &chars, 3, kind).unwrap(), Some((3, 5)));
+        assert_eq!(find_line(&code, &chars, 1, kind).unwrap(), None);
+        let longest = MatchKind::LeftmostLongest;
+        assert_eq!(find_line(&code, &chars, 3, longest).unwrap(), Some((3, 5)));
+    }
+
+    #[test]
+    fn test_find_line_leftmost_longest() {
+        let (code, _) = compile_pattern_with_must_literals("a|ab|abc").unwrap();
+        let chars: Vec<char> = "xabcd".chars().collect();
+        let first = find_line(&code, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+        let longest = find_line(&code, &chars, 0, MatchKind::LeftmostLongest).unwrap();
+        assert_eq!(first, Some((1, 2)));
+        assert_eq!(longest, Some((1, 4)));
+    }
+
+    #[test]
+    fn test_captures_line() {
+        let (code, _) = compile_pattern_with_must_literals("(a|ab)(c|bcd)(d*)").unwrap();
+        let chars: Vec<char> = "abcd".chars().collect();
+        assert_eq!(
+            captures_line(&code, &chars, MatchKind::LeftmostFirst).unwrap(),
+            Some(vec![Some((0, 4)), Some((0, 1)), Some((1, 4)), Some((4, 4))])
+        );
+        assert_eq!(
+            captures_line(&code, &chars, MatchKind::LeftmostLongest).unwrap(),
+            Some(vec![Some((0, 4)), Some((0, 2)), Some((2, 3)), Some((3, 4))])
+        );
+    }
+
     #[test]
     fn test_match_line_from_starts() {
         let (code, _) = compile_pattern_with_must_literals("abc").unwrap();
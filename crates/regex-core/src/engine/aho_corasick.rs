@@ -0,0 +1,248 @@
+//! Aho-Corasick automaton finding where any of a set of literals occurs.
+//!
+//! The trie of the literals' UTF-8 bytes is completed into a DFA: a
+//! missing transition follows the failure link, the state of the longest
+//! proper suffix that is also in the trie. Each state lists the literals
+//! ending there, its failure chain included, so one pass over a line
+//! reports every occurrence, overlapping ones too.
+//!
+//! When the CPU allows it, searches over `&str` use the Teddy searcher
+//! instead, which skips over stretches without a literal much faster.
+
+use crate::engine::teddy::Teddy;
+
+/// Multi-literal searcher; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct AhoCorasick {
+    /// `next[state][byte]` is the state after reading `byte`.
+    next: Vec<[u32; 256]>,
+    /// Length in characters of each literal ending in a state.
+    outputs: Vec<Vec<usize>>,
+    /// Length in characters of the longest literal.
+    max_len: usize,
+    /// Vectorized searcher over the same literals, when available.
+    teddy: Option<Teddy>,
+}
+
+impl AhoCorasick {
+    /// Builds the automaton of `literals`, or returns `None` if all of
+    /// them are empty.
+    pub(crate) fn new(literals: &[String]) -> Option<Self> {
+        const MISSING: u32 = u32::MAX;
+
+        let mut next = vec![[MISSING; 256]];
+        let mut outputs = vec![Vec::new()];
+        for literal in literals.iter().filter(|literal| !literal.is_empty()) {
+            let mut state = 0;
+            for &byte in literal.as_bytes() {
+                if next[state][usize::from(byte)] == MISSING {
+                    next[state][usize::from(byte)] = next.len() as u32;
+                    next.push([MISSING; 256]);
+                    outputs.push(Vec::new());
+                }
+                state = next[state][usize::from(byte)] as usize;
+            }
+            outputs[state].push(literal.chars().count());
+        }
+        if next.len() == 1 {
+            return None;
+        }
+        let max_len = outputs.iter().flatten().copied().max().unwrap_or(0);
+
+        // States in breadth-first order, so a failure link always points to
+        // a state whose transitions are complete.
+        let mut fail = vec![0; next.len()];
+        let mut queue = std::collections::VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            let fallbacks = if state == 0 {
+                [0; 256]
+            } else {
+                next[fail[state]]
+            };
+            for (byte, fallback) in fallbacks.into_iter().enumerate() {
+                let child = next[state][byte];
+                if child == MISSING {
+                    next[state][byte] = fallback;
+                    continue;
+                }
+                let child = child as usize;
+                fail[child] = fallback as usize;
+                let inherited = outputs[fail[child]].clone();
+                outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Some(Self {
+            next,
+            outputs,
+            max_len,
+            teddy: Teddy::new(literals),
+        })
+    }
+
+    /// Returns whether a literal occurs in `text`.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        if let Some(teddy) = &self.teddy {
+            return teddy.find_at(text.as_bytes(), 0).is_some();
+        }
+        let mut state = 0;
+        text.as_bytes().iter().any(|&byte| {
+            state = self.next[state][usize::from(byte)] as usize;
+            !self.outputs[state].is_empty()
+        })
+    }
+
+    /// Returns the leftmost character index at or after `from` where a
+    /// literal starts in `chars`.
+    pub(crate) fn leftmost_start(&self, chars: &[char], from: usize) -> Option<usize> {
+        let mut state = 0;
+        let mut leftmost: Option<usize> = None;
+        let mut buf = [0; 4];
+        for (index, ch) in chars.iter().enumerate().skip(from) {
+            if leftmost.is_some_and(|leftmost| index >= leftmost + self.max_len) {
+                // Later matches cannot start before the one found.
+                break;
+            }
+            for &byte in ch.encode_utf8(&mut buf).as_bytes() {
+                state = self.next[state][usize::from(byte)] as usize;
+            }
+            for len in &self.outputs[state] {
+                let start = index + 1 - len;
+                leftmost = Some(leftmost.map_or(start, |leftmost| leftmost.min(start)));
+            }
+        }
+        leftmost
+    }
+
+    /// Returns the character indices in `text` where a literal starts, in
+    /// ascending order without duplicates.
+    pub(crate) fn starts(&self, text: &str) -> Vec<usize> {
+        if let Some(teddy) = &self.teddy {
+            return teddy_starts(teddy, text);
+        }
+        let mut starts = Vec::new();
+        let mut state = 0;
+        // Characters whose first byte has been read.
+        let mut chars = 0;
+        for &byte in text.as_bytes() {
+            if !is_continuation(byte) {
+                chars += 1;
+            }
+            state = self.next[state][usize::from(byte)] as usize;
+            // Literals are whole characters, so a match ends a character.
+            starts.extend(self.outputs[state].iter().map(|len| chars - len));
+        }
+        starts.sort_unstable();
+        starts.dedup();
+        starts
+    }
+}
+
+/// Returns the character indices where `teddy` finds a literal in `text`,
+/// in ascending order.
+fn teddy_starts(teddy: &Teddy, text: &str) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    let mut starts = Vec::new();
+    let mut chars = 0;
+    let mut counted = 0;
+    while let Some(pos) = teddy.find_at(bytes, counted) {
+        // Literals start at character boundaries.
+        chars += bytes[counted..pos]
+            .iter()
+            .filter(|&&byte| !is_continuation(byte))
+            .count();
+        starts.push(chars);
+        chars += 1;
+        counted = pos + 1;
+    }
+    starts
+}
+
+/// Returns whether `byte` continues a multi-byte UTF-8 sequence.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AhoCorasick;
+
+    fn starts(literals: &[&str], text: &str) -> Vec<usize> {
+        let literals: Vec<String> = literals.iter().map(|literal| literal.to_string()).collect();
+        AhoCorasick::new(&literals).unwrap().starts(text)
+    }
+
+    /// Starts found by trying every literal at every character boundary.
+    fn naive_starts(literals: &[&str], text: &str) -> Vec<usize> {
+        let mut starts: Vec<usize> = literals
+            .iter()
+            .filter(|literal| !literal.is_empty())
+            .flat_map(|&literal| {
+                (0..text.len())
+                    .filter(move |&i| text.is_char_boundary(i) && text[i..].starts_with(literal))
+                    .map(|i| text[..i].chars().count())
+            })
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+        starts
+    }
+
+    #[test]
+    fn test_new_rejects_empty_literals() {
+        assert!(AhoCorasick::new(&[]).is_none());
+        assert!(AhoCorasick::new(&[String::new()]).is_none());
+    }
+
+    #[test]
+    fn test_starts() {
+        assert_eq!(starts(&["he", "she", "hers"], "ushers"), vec![1, 2]);
+        assert_eq!(starts(&["aa"], "aaaa"), vec![0, 1, 2]);
+        assert_eq!(starts(&["日本", "本語"], "x日本語"), vec![1, 2]);
+        assert_eq!(starts(&["abc"], "ab"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_is_match_and_leftmost_start() {
+        let literals = ["bcd".to_string(), "abcde".to_string(), "é".to_string()];
+        let searcher = AhoCorasick::new(&literals).unwrap();
+        assert!(searcher.is_match("xabcy bcd"));
+        assert!(searcher.is_match("café"));
+        assert!(!searcher.is_match("abc"));
+
+        let chars: Vec<char> = "xabcdé abcde".chars().collect();
+        assert_eq!(searcher.leftmost_start(&chars, 0), Some(2));
+        assert_eq!(searcher.leftmost_start(&chars, 3), Some(5));
+        assert_eq!(searcher.leftmost_start(&chars, 6), Some(7));
+        assert_eq!(searcher.leftmost_start(&chars, 9), None);
+    }
+
+    #[test]
+    fn test_starts_agree_with_naive_search() {
+        let literal_sets: [&[&str]; 5] = [
+            &["abc", "bc", "c"],
+            &["a", "ab", "abab"],
+            &["é", "éé", "xé"],
+            &["needle", "needles", "eed"],
+            &["λό", "γος", ""],
+        ];
+        let texts = [
+            "",
+            "abcabc",
+            "abababab",
+            "xéééx",
+            "needles in needle",
+            "λόγος λόγοι",
+        ];
+        for literals in literal_sets {
+            for text in texts {
+                assert_eq!(
+                    starts(literals, text),
+                    naive_starts(literals, text),
+                    "{literals:?} in {text:?}"
+                );
+            }
+        }
+    }
+}
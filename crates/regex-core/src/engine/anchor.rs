@@ -0,0 +1,221 @@
+//! Detection of programs anchored at the start or end of a line, of the
+//! text, or of the search.
+//!
+//! When every path of a program asserts `^` before reading a character, a
+//! match can only start where a line starts, so searches try those
+//! positions instead of every one. End anchors are found the same way
+//! backwards from `Match`; `compile_reverse` turns them into start anchors
+//! of the reversed program.
+
+use crate::engine::{
+    ast::{CharClass, Predicate},
+    instruction::Instruction,
+};
+
+/// Positions where every match of a program starts or ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Anchor {
+    /// The start or end of the text (`StartOfText`, `EndOfText`).
+    Text,
+    /// The start or end of a line (`^`, `$`), which includes the text's.
+    Line,
+    /// Where the search started (`\G`).
+    Search,
+}
+
+impl Anchor {
+    /// Returns the anchor covering both `self` and `other`, if any.
+    fn union(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            _ if self == other => Some(self),
+            (Self::Text | Self::Line, Self::Text | Self::Line) => Some(Self::Line),
+            _ => None,
+        }
+    }
+}
+
+/// Returns where every match of `inst` starts, or `None` if some path
+/// reads a character before asserting a start anchor.
+pub(crate) fn start_anchor(inst: &[Instruction]) -> Option<Anchor> {
+    let mut anchor: Option<Anchor> = None;
+    let mut seen = vec![false; inst.len()];
+    let mut stack = vec![0];
+    while let Some(pc) = stack.pop() {
+        if std::mem::replace(seen.get_mut(pc)?, true) {
+            continue;
+        }
+        let found = match &inst[pc] {
+            Instruction::Assert(Predicate::StartOfText) => Anchor::Text,
+            Instruction::Assert(Predicate::StartOfLine) => Anchor::Line,
+            Instruction::Assert(Predicate::StartOfSearch) => Anchor::Search,
+            Instruction::Assert(_)
+            | Instruction::SaveStart(_)
+            | Instruction::SaveEnd(_)
+            | Instruction::ResetMatchStart => {
+                stack.push(pc + 1);
+                continue;
+            }
+            Instruction::Split(first, second) => {
+                stack.extend([*first, *second]);
+                continue;
+            }
+            Instruction::Jump(target) => {
+                stack.push(*target);
+                continue;
+            }
+            _ => return None,
+        };
+        anchor = Some(anchor.map_or(Some(found), |anchor| anchor.union(found))?);
+    }
+    anchor
+}
+
+/// Returns where every match of `inst` ends, `Anchor::Text` or
+/// `Anchor::Line`, or `None` if some path reaches `Match` after its last
+/// character without asserting an end anchor.
+pub(crate) fn end_anchor(inst: &[Instruction]) -> Option<Anchor> {
+    let mut anchor: Option<Anchor> = None;
+    let mut seen = vec![false; inst.len()];
+    // Paths towards `Match` resume after each character read.
+    let mut stack: Vec<usize> = inst
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| {
+            matches!(
+                instruction,
+                Instruction::CharClass(_)
+                    | Instruction::ByteClass(_)
+                    | Instruction::Backref(_)
+                    | Instruction::GraphemeCluster
+            )
+        })
+        .map(|(pc, _)| pc + 1)
+        .collect();
+    stack.push(0);
+    while let Some(pc) = stack.pop() {
+        if std::mem::replace(seen.get_mut(pc)?, true) {
+            continue;
+        }
+        let found = match &inst[pc] {
+            Instruction::Assert(Predicate::EndOfText) => Anchor::Text,
+            Instruction::Assert(Predicate::EndOfLine) => Anchor::Line,
+            Instruction::Assert(_)
+            | Instruction::SaveStart(_)
+            | Instruction::SaveEnd(_)
+            | Instruction::ResetMatchStart => {
+                stack.push(pc + 1);
+                continue;
+            }
+            Instruction::Split(first, second) => {
+                stack.extend([*first, *second]);
+                continue;
+            }
+            Instruction::Jump(target) => {
+                stack.push(*target);
+                continue;
+            }
+            Instruction::CharClass(_)
+            | Instruction::ByteClass(_)
+            | Instruction::Backref(_)
+            | Instruction::GraphemeCluster => continue,
+            Instruction::Match | Instruction::Call(_) | Instruction::Return => return None,
+        };
+        anchor = Some(anchor.map_or(Some(found), |anchor| anchor.union(found))?);
+    }
+    anchor
+}
+
+/// Returns the character indices at or after `search_start` where a match
+/// anchored at `anchor` may start, in ascending order.
+pub(crate) fn anchored_starts(anchor: Anchor, chars: &[char], search_start: usize) -> Vec<usize> {
+    match anchor {
+        Anchor::Text if search_start == 0 => vec![0],
+        Anchor::Text => Vec::new(),
+        Anchor::Search => vec![search_start],
+        Anchor::Line => (search_start..=chars.len())
+            .filter(|&index| index == 0 || chars[index - 1] == '\n')
+            .collect(),
+    }
+}
+
+/// Returns `inst` with every `\G` replaced by an instruction that never
+/// matches, for searches resuming past where `\G` holds, or `None` if
+/// `inst` has no `\G`.
+pub(crate) fn without_search_anchor(inst: &[Instruction]) -> Option<Vec<Instruction>> {
+    let is_search = |instruction: &Instruction| {
+        matches!(instruction, Instruction::Assert(Predicate::StartOfSearch))
+    };
+    if !inst.iter().any(is_search) {
+        return None;
+    }
+    let never = Instruction::CharClass(CharClass::new(Vec::new(), false));
+    Some(
+        inst.iter()
+            .map(|instruction| match is_search(instruction) {
+                true => never.clone(),
+                false => instruction.clone(),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Anchor, anchored_starts, end_anchor, start_anchor, without_search_anchor};
+    use crate::engine::{
+        compiler::compile,
+        evaluator::{MatchKind, eval_find},
+        parser::parse,
+    };
+
+    fn anchors(pattern: &str) -> (Option<Anchor>, Option<Anchor>) {
+        let inst = compile(&parse(pattern).unwrap()).unwrap();
+        (start_anchor(&inst), end_anchor(&inst))
+    }
+
+    #[test]
+    fn test_anchors() {
+        assert_eq!(anchors("^abc$"), (Some(Anchor::Line), Some(Anchor::Line)));
+        assert_eq!(anchors("(^a|^b)c*"), (Some(Anchor::Line), None));
+        assert_eq!(
+            anchors("\\Ga(b$|c$)"),
+            (Some(Anchor::Search), Some(Anchor::Line))
+        );
+        assert_eq!(anchors("(x)?^a"), (None, None));
+        assert_eq!(anchors("^a|b"), (None, None));
+        assert_eq!(anchors("^|\\Ga"), (None, None));
+        assert_eq!(anchors("a*$"), (None, Some(Anchor::Line)));
+        assert_eq!(anchors("a$b*"), (None, None));
+        assert_eq!(anchors("(a)\\1$"), (None, Some(Anchor::Line)));
+        assert_eq!(anchors("^a(?R)?$"), (Some(Anchor::Line), None));
+    }
+
+    #[test]
+    fn test_anchored_starts() {
+        let chars: Vec<char> = "ab\ncd\n".chars().collect();
+        assert_eq!(anchored_starts(Anchor::Line, &chars, 0), vec![0, 3, 6]);
+        assert_eq!(anchored_starts(Anchor::Line, &chars, 1), vec![3, 6]);
+        assert_eq!(anchored_starts(Anchor::Text, &chars, 0), vec![0]);
+        assert_eq!(
+            anchored_starts(Anchor::Text, &chars, 2),
+            Vec::<usize>::new()
+        );
+        assert_eq!(anchored_starts(Anchor::Search, &chars, 4), vec![4]);
+    }
+
+    #[test]
+    fn test_without_search_anchor() {
+        assert!(without_search_anchor(&compile(&parse("^a").unwrap()).unwrap()).is_none());
+        let inst = compile(&parse("\\Ga|b").unwrap()).unwrap();
+        let past = without_search_anchor(&inst).unwrap();
+        let chars: Vec<char> = "ab".chars().collect();
+        assert_eq!(
+            eval_find(&inst, &chars, 0, MatchKind::LeftmostFirst),
+            Ok(Some((0, 1)))
+        );
+        assert_eq!(
+            eval_find(&past, &chars, 0, MatchKind::LeftmostFirst),
+            Ok(Some((1, 2)))
+        );
+    }
+}
@@ -5,6 +5,24 @@ use std::{cmp::Ordering, collections::BTreeSet};
 /// Maximum number of must literals to retain.
 pub(crate) const MUST_LITERAL_LIMIT: usize = 16;
 
+/// Bytes of English text and source code, most frequent first. Bytes not
+/// listed, including every non-ASCII byte, count as rarer than all of them.
+const COMMON_BYTES: &[u8] = b" etaoinsrhldcumfpgwyb\n.,vk0-1=_2\"/:()'T3S4A5CIE6M7P8DR9BNLOHFWGx;UjVqKJYzQXZ[]{}<>*#@!?&%$+|\\^~`\t\r";
+
+/// How frequent each byte is, from 0 for the rarest up.
+const BYTE_FREQUENCY: [u8; 256] = {
+    let mut frequency = [0; 256];
+    let mut i = 0;
+    while i < COMMON_BYTES.len() {
+        frequency[COMMON_BYTES[i] as usize] = (COMMON_BYTES.len() - i) as u8;
+        i += 1;
+    }
+    frequency
+};
+
+/// Maximum number of strings in an exact literal language.
+pub(crate) const EXACT_LITERAL_LIMIT: usize = 64;
+
 /// Inclusive character range.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CharRange {
@@ -33,6 +51,43 @@ impl CharClass {
     }
 }
 
+/// Inclusive byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    /// Inclusive start byte.
+    pub start: u8,
+    /// Inclusive end byte.
+    pub end: u8,
+}
+
+/// Class of raw bytes, written with Unicode mode disabled (`(?-u)`).
+///
+/// `ranges` represents inclusive `[start, end]` spans.
+/// If `negated` is true, the class matches every other byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteClass {
+    /// Inclusive byte ranges that belong to this class.
+    pub ranges: Vec<ByteRange>,
+    /// Whether the class is negated (`[^...]`).
+    pub negated: bool,
+}
+
+impl ByteClass {
+    /// Creates a byte class from ranges and a negation flag.
+    pub fn new(ranges: Vec<ByteRange>, negated: bool) -> Self {
+        Self { ranges, negated }
+    }
+
+    /// Returns whether `byte` belongs to the class.
+    pub(crate) fn contains(&self, byte: u8) -> bool {
+        let is_in_range = self
+            .ranges
+            .iter()
+            .any(|range| range.start <= byte && byte <= range.end);
+        is_in_range != self.negated
+    }
+}
+
 /// Zero-width assertion kinds.
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -41,6 +96,10 @@ pub enum Predicate {
     StartOfLine,
     /// Line end assertion (`$`).
     EndOfLine,
+    /// Line start assertion in CRLF mode (`^`): `\r\n` is one terminator.
+    StartOfCrlfLine,
+    /// Line end assertion in CRLF mode (`$`): `\r\n` is one terminator.
+    EndOfCrlfLine,
     /// Text start assertion.
     StartOfText,
     /// Text end assertion.
@@ -49,12 +108,15 @@ pub enum Predicate {
     WordBoundary,
     /// Non-word-boundary assertion.
     NonWordBoundary,
+    /// Continuation anchor (`\G`): the position where the current search started.
+    StartOfSearch,
 }
 
 /// Regex abstract syntax tree.
 ///
 /// - Empty
 /// - CharClass(..., neg)
+/// - ByteClass(..., neg)
 /// - Assertion(Predicate)
 /// - Capture(..., index)
 /// - ZeroOrMore / OneOrMore / ZeroOrOne (greedy)
@@ -62,12 +124,17 @@ pub enum Predicate {
 /// - Concat
 /// - Alternate
 /// - Backreference
+/// - SubroutineCall
+/// - ResetMatchStart
+/// - GraphemeCluster
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Ast {
     /// Empty expression.
     Empty,
     /// Character class match node.
     CharClass(CharClass),
+    /// Raw byte class match node.
+    ByteClass(ByteClass),
     /// Zero-width assertion node.
     Assertion(Predicate),
     /// Capturing group node.
@@ -115,6 +182,14 @@ pub enum Ast {
     Alternate(Box<Ast>, Box<Ast>),
     /// Backreference node (`\1`, `\2`, ...).
     Backreference(usize),
+    /// Recursion or subroutine call node (`(?R)`, `(?1)`, `(?&name)`).
+    ///
+    /// Index `0` refers to the whole pattern.
+    SubroutineCall(usize),
+    /// Match-start reset node (`\K`).
+    ResetMatchStart,
+    /// Extended grapheme cluster node (`\X`).
+    GraphemeCluster,
 }
 
 /// Aggregate analysis results derived from one AST.
@@ -126,6 +201,13 @@ pub(crate) struct AstAnalysis {
     pub needles: Vec<String>,
     /// Whether this pattern can match the empty string.
     pub nullable: bool,
+    /// Every string the pattern matches, in leftmost-first priority order,
+    /// when that language is a small finite set of non-empty literals.
+    pub literals: Option<Vec<String>>,
+    /// Literals one of which starts every match, or empty if unknown.
+    pub prefixes: Vec<String>,
+    /// Literals one of which ends every match, or empty if unknown.
+    pub suffixes: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -135,13 +217,27 @@ struct AstAnalysisSet {
     nullable: bool,
 }
 
-/// Analyzes `ast` and returns deterministic `must_literals`, `needles`, and `nullable`.
+/// A literal starting a match, or ending it when built in reverse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Affix {
+    literal: String,
+    /// Whether the literal is the whole match, so that whatever follows it
+    /// in the pattern extends it.
+    exact: bool,
+}
+
+/// Analyzes `ast` and returns deterministic `must_literals`, `needles`,
+/// `nullable`, the exact `literals` of its language, and its required
+/// `prefixes` and `suffixes`.
 pub(crate) fn analyze_ast(ast: &Ast) -> AstAnalysis {
     let result = analyze_ast_set(ast);
     AstAnalysis {
         must_literals: literal_set_to_vec(result.must_literals),
         needles: literal_set_to_vec(result.needles),
         nullable: result.nullable,
+        literals: exact_literals(ast).filter(|literals| !literals.iter().any(String::is_empty)),
+        prefixes: affix_set(ast, false),
+        suffixes: affix_set(ast, true),
     }
 }
 
@@ -167,12 +263,15 @@ pub(crate) fn is_nullable(ast: &Ast) -> bool {
 
 fn analyze_ast_set(ast: &Ast) -> AstAnalysisSet {
     match ast {
-        Ast::Empty | Ast::Assertion(_) => AstAnalysisSet {
+        Ast::Empty | Ast::Assertion(_) | Ast::ResetMatchStart => AstAnalysisSet {
             must_literals: BTreeSet::new(),
             needles: BTreeSet::new(),
             nullable: true,
         },
-        Ast::Backreference(_) => AstAnalysisSet {
+        Ast::ByteClass(_)
+        | Ast::Backreference(_)
+        | Ast::SubroutineCall(_)
+        | Ast::GraphemeCluster => AstAnalysisSet {
             must_literals: BTreeSet::new(),
             needles: BTreeSet::new(),
             nullable: false,
@@ -205,6 +304,241 @@ fn analyze_ast_set(ast: &Ast) -> AstAnalysisSet {
     }
 }
 
+/// Returns the strings `ast` matches in leftmost-first priority order, or
+/// `None` if its language is not a finite set of at most
+/// `EXACT_LITERAL_LIMIT` strings built from literals alone.
+fn exact_literals(ast: &Ast) -> Option<Vec<String>> {
+    let literals = match ast {
+        Ast::Empty => vec![String::new()],
+        Ast::CharClass(class) => class_members(class)?,
+        Ast::Capture { expr, .. } => exact_literals(expr)?,
+        Ast::ZeroOrOne { expr, greedy } => {
+            let mut literals = exact_literals(expr)?;
+            if *greedy {
+                literals.push(String::new());
+            } else {
+                literals.insert(0, String::new());
+            }
+            literals
+        }
+        Ast::Concat(exprs) => {
+            let mut literals = vec![String::new()];
+            for expr in exprs {
+                let suffixes = exact_literals(expr)?;
+                if literals.len() * suffixes.len() > EXACT_LITERAL_LIMIT {
+                    return None;
+                }
+                literals = literals
+                    .iter()
+                    .flat_map(|prefix| {
+                        suffixes
+                            .iter()
+                            .map(move |suffix| format!("{prefix}{suffix}"))
+                    })
+                    .collect();
+            }
+            literals
+        }
+        Ast::Alternate(left, right) => {
+            let mut literals = exact_literals(left)?;
+            literals.extend(exact_literals(right)?);
+            literals
+        }
+        _ => return None,
+    };
+    (literals.len() <= EXACT_LITERAL_LIMIT).then_some(literals)
+}
+
+/// Returns the characters of a non-negated class with at most
+/// `EXACT_LITERAL_LIMIT` members.
+fn class_members(class: &CharClass) -> Option<Vec<String>> {
+    if class.negated {
+        return None;
+    }
+    let size: u32 = class
+        .ranges
+        .iter()
+        .map(|range| u32::from(range.end) - u32::from(range.start) + 1)
+        .sum();
+    if size as usize > EXACT_LITERAL_LIMIT {
+        return None;
+    }
+    Some(
+        class
+            .ranges
+            .iter()
+            .flat_map(|range| range.start..=range.end)
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Returns literals one of which starts every match of `ast`, or ends it
+/// with `reverse`, most selective first, without those that extend
+/// another. The set is empty when some match may start (or end) with
+/// anything, or when it would exceed `MUST_LITERAL_LIMIT` literals.
+fn affix_set(ast: &Ast, reverse: bool) -> Vec<String> {
+    let affixes = affixes(ast, reverse);
+    if affixes.iter().any(|affix| affix.literal.is_empty()) {
+        return Vec::new();
+    }
+    // A literal extending another one is implied by it.
+    let set: BTreeSet<String> = affixes
+        .iter()
+        .filter(|affix| {
+            !affixes.iter().any(|other| {
+                other.literal.len() < affix.literal.len()
+                    && affix.literal.starts_with(&other.literal)
+            })
+        })
+        .map(|affix| {
+            if reverse {
+                affix.literal.chars().rev().collect()
+            } else {
+                affix.literal.clone()
+            }
+        })
+        .collect();
+    let mut literals: Vec<String> = set.into_iter().collect();
+    literals.sort_by(|a, b| compare_literals(a, b));
+    literals
+}
+
+/// Returns the affixes of `ast`; with `reverse`, suffixes are built from
+/// the end of the pattern and spelled backwards.
+///
+/// An inexact empty affix means nothing is known. Bounded repeats are
+/// unrolled up to their minimum, and alternations take the union of both
+/// sides.
+fn affixes(ast: &Ast, reverse: bool) -> Vec<Affix> {
+    match ast {
+        Ast::Empty | Ast::Assertion(_) | Ast::ResetMatchStart => exact_empty_affix(),
+        Ast::CharClass(class) => match class_members(class) {
+            Some(members) if members.len() <= MUST_LITERAL_LIMIT => members
+                .into_iter()
+                .map(|literal| Affix {
+                    literal,
+                    exact: true,
+                })
+                .collect(),
+            _ => unknown_affix(),
+        },
+        Ast::ByteClass(_)
+        | Ast::Backreference(_)
+        | Ast::SubroutineCall(_)
+        | Ast::GraphemeCluster => unknown_affix(),
+        Ast::Capture { expr, .. } => affixes(expr, reverse),
+        Ast::ZeroOrOne { expr, .. } => union_affixes(affixes(expr, reverse), exact_empty_affix()),
+        Ast::ZeroOrMore { expr, .. } => {
+            union_affixes(inexact_affixes(affixes(expr, reverse)), exact_empty_affix())
+        }
+        Ast::OneOrMore { expr, .. } => inexact_affixes(affixes(expr, reverse)),
+        Ast::Repeat { expr, min, max, .. } => {
+            let child = affixes(expr, reverse);
+            if *min == 0 {
+                return union_affixes(inexact_affixes(child), exact_empty_affix());
+            }
+            let mut result = exact_empty_affix();
+            for _ in 0..*min {
+                if result.iter().all(|affix| !affix.exact) {
+                    break;
+                }
+                result = concat_affixes(result, &child);
+            }
+            if *max == Some(*min) {
+                result
+            } else {
+                inexact_affixes(result)
+            }
+        }
+        Ast::Concat(exprs) => {
+            let exprs: Vec<&Ast> = if reverse {
+                exprs.iter().rev().collect()
+            } else {
+                exprs.iter().collect()
+            };
+            let mut result = exact_empty_affix();
+            for expr in exprs {
+                if result.iter().all(|affix| !affix.exact) {
+                    break;
+                }
+                result = concat_affixes(result, &affixes(expr, reverse));
+            }
+            result
+        }
+        Ast::Alternate(left, right) => {
+            union_affixes(affixes(left, reverse), affixes(right, reverse))
+        }
+    }
+}
+
+fn exact_empty_affix() -> Vec<Affix> {
+    vec![Affix {
+        literal: String::new(),
+        exact: true,
+    }]
+}
+
+fn unknown_affix() -> Vec<Affix> {
+    vec![Affix {
+        literal: String::new(),
+        exact: false,
+    }]
+}
+
+fn inexact_affixes(affixes: Vec<Affix>) -> Vec<Affix> {
+    affixes
+        .into_iter()
+        .map(|affix| Affix {
+            exact: false,
+            ..affix
+        })
+        .collect()
+}
+
+/// Extends the exact affixes of `left` with each affix of `right`.
+///
+/// Past `MUST_LITERAL_LIMIT` affixes or `EXACT_LITERAL_LIMIT` characters,
+/// `left` is kept as it is but no longer exact.
+fn concat_affixes(left: Vec<Affix>, right: &[Affix]) -> Vec<Affix> {
+    let mut result = Vec::new();
+    for affix in &left {
+        if !affix.exact {
+            result.push(affix.clone());
+            continue;
+        }
+        for next in right {
+            let literal = format!("{}{}", affix.literal, next.literal);
+            if literal.chars().count() > EXACT_LITERAL_LIMIT {
+                return inexact_affixes(left);
+            }
+            result.push(Affix {
+                literal,
+                exact: next.exact,
+            });
+        }
+    }
+    result.dedup();
+    if result.len() > MUST_LITERAL_LIMIT {
+        return inexact_affixes(left);
+    }
+    result
+}
+
+/// Returns the affixes of either side, or nothing known past
+/// `MUST_LITERAL_LIMIT` affixes, since none may be dropped.
+fn union_affixes(mut left: Vec<Affix>, right: Vec<Affix>) -> Vec<Affix> {
+    for affix in right {
+        if !left.contains(&affix) {
+            left.push(affix);
+        }
+    }
+    if left.len() > MUST_LITERAL_LIMIT {
+        return unknown_affix();
+    }
+    left
+}
+
 fn analyze_char_class(class: &CharClass) -> AstAnalysisSet {
     let mut must_literals = BTreeSet::new();
     let mut needles = BTreeSet::new();
@@ -333,16 +667,30 @@ fn literal_set_to_vec(set: BTreeSet<String>) -> Vec<String> {
     literals
 }
 
+/// Orders literals from the most to the least selective: by their rarest
+/// byte first, so a scan for them stops at fewer false candidates, then
+/// longer first, then lexicographically.
 fn compare_literals(a: &str, b: &str) -> Ordering {
-    b.len()
-        .cmp(&a.len())
+    rarest_byte_frequency(a)
+        .cmp(&rarest_byte_frequency(b))
+        .then_with(|| b.len().cmp(&a.len()))
         .then_with(|| a.as_bytes().cmp(b.as_bytes()))
 }
 
+/// Returns the frequency of the rarest byte of `literal`.
+fn rarest_byte_frequency(literal: &str) -> u8 {
+    literal
+        .bytes()
+        .map(|byte| BYTE_FREQUENCY[usize::from(byte)])
+        .min()
+        .unwrap_or(u8::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        MUST_LITERAL_LIMIT, analyze_ast, extract_must_literals, extract_needles, is_nullable,
+        MUST_LITERAL_LIMIT, analyze_ast, compare_literals, extract_must_literals, extract_needles,
+        is_nullable,
     };
     use crate::engine::parser::parse;
 
@@ -364,18 +712,18 @@ mod tests {
     fn test_extract_must_literals_ab_star_c() {
         let ast = parse("ab*c").unwrap();
         let actual = extract_must_literals(&ast);
-        assert_eq!(actual, vec!["a".to_string(), "c".to_string()]);
+        assert_eq!(actual, vec!["c".to_string(), "a".to_string()]);
     }
 
     #[test]
     fn test_extract_must_literals_a_class_z() {
         let ast = parse("a[a-z]z").unwrap();
         let actual = extract_must_literals(&ast);
-        assert_eq!(actual, vec!["a".to_string(), "z".to_string()]);
+        assert_eq!(actual, vec!["z".to_string(), "a".to_string()]);
     }
 
     #[test]
-    fn test_extract_must_literals_limit_prefers_longer_then_lexicographic() {
+    fn test_extract_must_literals_limit_prefers_rare_bytes() {
         let pattern = [
             "ppp", "aaa", "qqq", "bbb", "ccc", "ddd", "eee", "fff", "ggg", "hhh", "iii", "jjj",
             "kkk", "lll", "mmm", "nnn", "ooo", "zzzz",
@@ -386,8 +734,8 @@ mod tests {
         let actual = extract_must_literals(&ast);
         assert_eq!(actual.len(), MUST_LITERAL_LIMIT);
         let expected = [
-            "zzzz", "aaa", "bbb", "ccc", "ddd", "eee", "fff", "ggg", "hhh", "iii", "jjj", "kkk",
-            "lll", "mmm", "nnn", "ooo",
+            "zzzz", "qqq", "jjj", "kkk", "bbb", "ggg", "ppp", "fff", "mmm", "ccc", "ddd", "lll",
+            "hhh", "nnn", "iii", "ooo",
         ]
         .into_iter()
         .map(str::to_string)
@@ -395,6 +743,20 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_compare_literals_prefers_rare_bytes_then_longer() {
+        let mut literals = vec!["the", "e", "ex", "Q", "text", "日本"];
+        literals.sort_by(|a, b| compare_literals(a, b));
+        assert_eq!(literals, vec!["日本", "Q", "text", "ex", "the", "e"]);
+
+        let ast = parse("the.*Quick.*e").unwrap();
+        let actual = extract_must_literals(&ast);
+        assert_eq!(
+            actual,
+            vec!["Quick".to_string(), "the".to_string(), "e".to_string()]
+        );
+    }
+
     #[test]
     fn test_analyze_ast_alternate_needles_and_nullable() {
         let ast = parse("(abc|def)").unwrap();
@@ -403,6 +765,75 @@ mod tests {
         assert!(!actual.nullable);
     }
 
+    #[test]
+    fn test_analyze_ast_exact_literals() {
+        let literals = |pattern: &str| analyze_ast(&parse(pattern).unwrap()).literals;
+        let strings = |literals: &[&str]| Some(literals.iter().map(|s| s.to_string()).collect());
+
+        assert_eq!(literals("abc"), strings(&["abc"]));
+        assert_eq!(literals("(abc|def)"), strings(&["abc", "def"]));
+        assert_eq!(literals("gr[ae]y"), strings(&["gray", "grey"]));
+        assert_eq!(
+            literals("(a|ab)(c|bcd)"),
+            strings(&["ac", "abcd", "abc", "abbcd"])
+        );
+        assert_eq!(literals("colou?r"), strings(&["colour", "color"]));
+
+        assert_eq!(literals("a?"), None);
+        assert_eq!(literals("ab*"), None);
+        assert_eq!(literals("^abc"), None);
+        assert_eq!(literals("ab\\Kc"), None);
+        assert_eq!(literals("[^a]b"), None);
+        assert_eq!(literals("[a-z]b").map(|literals| literals.len()), Some(26));
+        assert_eq!(literals("[a-z]{2}"), None);
+        assert_eq!(literals(&"[ab]".repeat(7)), None);
+    }
+
+    #[test]
+    fn test_analyze_ast_prefixes_and_suffixes() {
+        let affixes = |pattern: &str| {
+            let analysis = analyze_ast(&parse(pattern).unwrap());
+            (analysis.prefixes, analysis.suffixes)
+        };
+        let strings = |literals: &[&str]| literals.iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(affixes("^abc$"), (strings(&["abc"]), strings(&["abc"])));
+        assert_eq!(
+            affixes("[a-z0-9.]+@example\\.com"),
+            (strings(&[]), strings(&["@example.com"]))
+        );
+        assert_eq!(
+            affixes("(foo|bar)baz"),
+            (
+                strings(&["barbaz", "foobaz"]),
+                strings(&["barbaz", "foobaz"])
+            )
+        );
+        assert_eq!(affixes("ab+c"), (strings(&["ab"]), strings(&["bc"])));
+        assert_eq!(
+            affixes("x(ab){2}y"),
+            (strings(&["xababy"]), strings(&["xababy"]))
+        );
+        assert_eq!(
+            affixes("x(ab){1,3}y"),
+            (strings(&["xab"]), strings(&["aby"]))
+        );
+        assert_eq!(
+            affixes("x?[ab]*cde"),
+            (
+                strings(&["xcde", "xa", "xb", "b", "cde", "a"]),
+                strings(&["cde"])
+            )
+        );
+        assert_eq!(affixes("(foo|bar)[0-9]?end").1, strings(&["end"]));
+        assert_eq!(affixes("(foo|bar)[0-9]end").1.len(), 10);
+        assert_eq!(affixes("a?b"), (strings(&["ab", "b"]), strings(&["b"])));
+        assert_eq!(affixes(".*abc"), (strings(&[]), strings(&["abc"])));
+        assert_eq!(affixes("a|b*"), (strings(&[]), strings(&[])));
+        assert_eq!(affixes("[a-z]x"), (strings(&[]), strings(&["x"])));
+        assert_eq!(affixes("(a)\\1"), (strings(&["a"]), strings(&[])));
+    }
+
     #[test]
     fn test_analyze_ast_zero_or_more_nullable_and_needles() {
         let ast = parse("(abc)*").unwrap();
@@ -424,7 +855,7 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_needles_limit_prefers_longer_then_lexicographic() {
+    fn test_extract_needles_limit_prefers_rare_bytes() {
         let pattern = [
             "ppp", "aaa", "qqq", "bbb", "ccc", "ddd", "eee", "fff", "ggg", "hhh", "iii", "jjj",
             "kkk", "lll", "mmm", "nnn", "ooo", "zzzz",
@@ -435,8 +866,8 @@ mod tests {
         let actual = extract_needles(&ast);
         assert_eq!(actual.len(), MUST_LITERAL_LIMIT);
         let expected = [
-            "zzzz", "aaa", "bbb", "ccc", "ddd", "eee", "fff", "ggg", "hhh", "iii", "jjj", "kkk",
-            "lll", "mmm", "nnn", "ooo",
+            "zzzz", "qqq", "jjj", "kkk", "bbb", "ggg", "ppp", "fff", "mmm", "ccc", "ddd", "lll",
+            "hhh", "nnn", "iii", "ooo",
         ]
         .into_iter()
         .map(str::to_string)
@@ -451,4 +882,12 @@ mod tests {
         assert!(!actual.nullable);
         assert!(actual.needles.is_empty());
     }
+
+    #[test]
+    fn test_subroutine_call_analysis_is_conservative() {
+        let ast = parse("(a*)(?1)").unwrap();
+        let actual = analyze_ast(&ast);
+        assert!(!actual.nullable);
+        assert!(actual.must_literals.is_empty());
+    }
 }
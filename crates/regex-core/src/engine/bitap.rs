@@ -0,0 +1,246 @@
+//! Bit-parallel Shift-And engine answering whether a line contains a match.
+//!
+//! The positions of the Glushkov automaton are the character instructions
+//! of the program, so a pattern with at most 64 of them fits one `u64`
+//! with one bit per position. `first` holds the positions that can read the
+//! first character of a match, `last` those after which the program can
+//! reach `Match`, and `follow` the positions that can come right after a
+//! given one, loops included. Reading a character then takes one table
+//! lookup per byte of the state plus a mask of the positions accepting it:
+//!
+//! ```text
+//! active = (first | follow(active)) & mask(ch)
+//! ```
+//!
+//! Adding `first` at every step makes the search unanchored. Programs with
+//! assertions, backreferences, subroutine calls or `\X` are not supported.
+
+use crate::engine::{
+    evaluator::{eval_byte_class, eval_char_class},
+    instruction::Instruction,
+};
+
+/// Most positions a program may have.
+const MAX_POSITIONS: usize = 64;
+
+/// Shift-And automaton; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct Bitap {
+    /// Character instruction of each position.
+    classes: Vec<Instruction>,
+    /// Positions accepting each ASCII character.
+    ascii: [u64; 128],
+    first: u64,
+    last: u64,
+    /// Whether the program matches the empty string.
+    nullable: bool,
+    /// `follow[k][byte]` is the union of the follow sets of the positions
+    /// set in byte `k` of the state.
+    follow: Box<[[u64; 256]; 8]>,
+}
+
+impl Bitap {
+    /// Builds the automaton of `inst`, or returns `None` if the program is
+    /// unsupported or has more than 64 positions.
+    pub(crate) fn new(inst: &[Instruction]) -> Option<Self> {
+        let mut position_of = vec![None; inst.len()];
+        let mut classes = Vec::new();
+        for (pc, instruction) in inst.iter().enumerate() {
+            if matches!(
+                instruction,
+                Instruction::CharClass(_) | Instruction::ByteClass(_)
+            ) {
+                if classes.len() == MAX_POSITIONS {
+                    return None;
+                }
+                position_of[pc] = Some(classes.len());
+                classes.push(instruction.clone());
+            }
+        }
+
+        let (first, nullable) = closure(inst, &position_of, 0)?;
+        let mut last = 0;
+        let mut follow_sets = Vec::with_capacity(classes.len());
+        for (pc, position) in position_of.iter().enumerate() {
+            if let Some(position) = position {
+                let (follow, reaches_match) = closure(inst, &position_of, pc + 1)?;
+                if reaches_match {
+                    last |= 1 << position;
+                }
+                follow_sets.push(follow);
+            }
+        }
+
+        let mut follow = Box::new([[0; 256]; 8]);
+        for (k, table) in follow.iter_mut().enumerate() {
+            for (byte, entry) in table.iter_mut().enumerate() {
+                for bit in 0..8 {
+                    if byte & (1 << bit) != 0
+                        && let Some(set) = follow_sets.get(k * 8 + bit)
+                    {
+                        *entry |= set;
+                    }
+                }
+            }
+        }
+
+        let mut ascii = [0; 128];
+        for (ch, mask) in (0..128u8).map(char::from).zip(ascii.iter_mut()) {
+            *mask = mask_of(&classes, ch);
+        }
+
+        Some(Self {
+            classes,
+            ascii,
+            first,
+            last,
+            nullable,
+            follow,
+        })
+    }
+
+    /// Returns whether `text` contains a match.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        if self.nullable {
+            return true;
+        }
+        let mut active: u64 = 0;
+        for ch in text.chars() {
+            let mask = match self.ascii.get(ch as usize) {
+                Some(&mask) => mask,
+                None => mask_of(&self.classes, ch),
+            };
+            active = (self.first | self.follow(active)) & mask;
+            if active & self.last != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the positions that can follow any position in `active`.
+    fn follow(&self, active: u64) -> u64 {
+        active
+            .to_le_bytes()
+            .iter()
+            .zip(self.follow.iter())
+            .fold(0, |set, (&byte, table)| set | table[usize::from(byte)])
+    }
+}
+
+/// Returns the positions whose class contains `ch`.
+fn mask_of(classes: &[Instruction], ch: char) -> u64 {
+    classes
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| match instruction {
+            Instruction::CharClass(class) => eval_char_class(class, Some(ch)),
+            Instruction::ByteClass(class) => eval_byte_class(class, Some(ch)),
+            _ => false,
+        })
+        .fold(0, |mask, (position, _)| mask | 1 << position)
+}
+
+/// Follows the epsilon transitions from `pc`.
+///
+/// Returns the positions reached and whether `Match` is reached, or `None`
+/// if the program has an instruction the automaton cannot represent.
+fn closure(inst: &[Instruction], position_of: &[Option<usize>], pc: usize) -> Option<(u64, bool)> {
+    let mut positions = 0;
+    let mut reaches_match = false;
+    let mut visited = vec![false; inst.len()];
+    let mut stack = vec![pc];
+
+    while let Some(pc) = stack.pop() {
+        let seen = visited.get_mut(pc)?;
+        if *seen {
+            continue;
+        }
+        *seen = true;
+
+        match &inst[pc] {
+            Instruction::Jump(addr) => stack.push(*addr),
+            Instruction::Split(left, right) => stack.extend([*left, *right]),
+            Instruction::SaveStart(_) | Instruction::SaveEnd(_) | Instruction::ResetMatchStart => {
+                stack.push(pc + 1);
+            }
+            Instruction::CharClass(_) | Instruction::ByteClass(_) => {
+                positions |= 1 << position_of[pc]?;
+            }
+            Instruction::Match => reaches_match = true,
+            Instruction::Assert(_)
+            | Instruction::Backref(_)
+            | Instruction::Call(_)
+            | Instruction::Return
+            | Instruction::GraphemeCluster => return None,
+        }
+    }
+
+    Some((positions, reaches_match))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bitap;
+    use crate::engine::{compiler::compile, evaluator::eval, parser::parse};
+
+    fn build(pattern: &str) -> Option<Bitap> {
+        Bitap::new(&compile(&parse(pattern).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_supported_programs() {
+        assert!(build("err[0-9]").is_some());
+        assert!(build("fo.bar").is_some());
+        assert!(build("(ab|c)*d+").is_some());
+        assert!(build(&"a".repeat(64)).is_some());
+
+        assert!(build(&"a".repeat(65)).is_none());
+        assert!(build("a{65}").is_none());
+        assert!(build("^foo").is_none());
+        assert!(build("(a)\\1").is_none());
+        assert!(build("\\X").is_none());
+    }
+
+    #[test]
+    fn test_is_match_agrees_with_evaluator() {
+        let patterns = [
+            "err[0-9]",
+            "fo.bar",
+            "(a|b)*abb",
+            "x[^y]+z",
+            "(ab|c)*d+",
+            "a(b|c){2,3}d",
+            "[α-ω]+s",
+            "é|日本",
+            "ab\\Kc",
+            "(a|ab)(c|bcd)",
+            "a*",
+        ];
+        let inputs = [
+            "",
+            "err",
+            "err7",
+            "an err 42",
+            "foxbar",
+            "fo\u{e9}bar",
+            "foobaz",
+            "xabbx",
+            "xyz xaz",
+            "ababccd",
+            "abcbd",
+            "acccd",
+            "λόγοςs",
+            "x日本語z",
+            "abcd",
+        ];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let bitap = Bitap::new(&inst).unwrap();
+            for input in inputs {
+                let expected = eval(&inst, input).unwrap();
+                assert_eq!(bitap.is_match(input), expected, "{pattern} on {input:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! Bounded backtracker: depth-first search with a `(pc, char_index)` bitset.
+//!
+//! Without backreferences or subroutine calls, whether a thread at `pc`
+//! and `char_index` can reach `Match` does not depend on its captures, so
+//! each pair needs exploring at most once. That bounds the work by the
+//! bitset size, and captures are restored from an undo stack instead of
+//! cloning the state at every `Split`.
+//!
+//! The bitset holds one bit per pair, so this engine only runs when
+//! `program length × (input length + 1)` fits `MAX_VISITED_BITS`; larger
+//! searches use the Pike VM.
+
+use crate::engine::{
+    evaluator::{
+        EvalError, Slots, eval_assert, eval_byte_class, eval_char_class, max_capture_index,
+    },
+    grapheme::grapheme_len,
+    instruction::Instruction,
+    pike_vm::to_slots,
+};
+
+/// Largest visited bitset, in bits (32 KiB of memory).
+const MAX_VISITED_BITS: usize = 256 * 1024;
+
+/// Returns whether the bounded backtracker can run `inst` over an input of
+/// `input_len` characters.
+pub(crate) fn is_supported(inst: &[Instruction], input_len: usize) -> bool {
+    let fits = input_len
+        .checked_add(1)
+        .and_then(|positions| positions.checked_mul(inst.len()))
+        .is_some_and(|bits| bits <= MAX_VISITED_BITS);
+
+    fits && !inst.iter().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Backref(_) | Instruction::Call(_) | Instruction::Return
+        )
+    })
+}
+
+/// Pending work on the backtracking stack.
+enum Job {
+    /// Explore from `pc` at `char_index`.
+    Explore { pc: usize, char_index: usize },
+    /// Undo a capture write when backtracking past it.
+    Restore { slot: usize, value: Option<usize> },
+}
+
+/// Search state shared by every start position.
+struct Backtracker<'a> {
+    inst: &'a [Instruction],
+    chars: &'a [char],
+    search_start: usize,
+    /// One bit per `(pc, char_index)` pair already explored.
+    visited: Vec<u64>,
+    stack: Vec<Job>,
+    /// Capture positions: `2 * n` is the start of group `n`, `2 * n + 1` its end.
+    positions: Vec<Option<usize>>,
+}
+
+impl<'a> Backtracker<'a> {
+    fn new(inst: &'a [Instruction], chars: &'a [char], search_start: usize) -> Self {
+        let bits = inst.len() * (chars.len() + 1);
+        let slot_count = 2 * (max_capture_index(inst) + 1);
+        Self {
+            inst,
+            chars,
+            search_start,
+            visited: vec![0; bits.div_ceil(64)],
+            stack: Vec::new(),
+            positions: vec![None; slot_count],
+        }
+    }
+
+    /// Marks `(pc, char_index)` as explored and returns whether it was new.
+    fn visit(&mut self, pc: usize, char_index: usize) -> bool {
+        let bit = pc * (self.chars.len() + 1) + char_index;
+        let (word, mask) = (bit / 64, 1u64 << (bit % 64));
+        let is_new = self.visited[word] & mask == 0;
+        self.visited[word] |= mask;
+        is_new
+    }
+
+    /// Writes a capture position, remembering the old value for backtracking.
+    fn save(&mut self, slot: usize, char_index: usize) -> bool {
+        let Some(position) = self.positions.get_mut(slot) else {
+            return false;
+        };
+        self.stack.push(Job::Restore {
+            slot,
+            value: *position,
+        });
+        *position = Some(char_index);
+        true
+    }
+
+    /// Runs the search for a match starting at `start`.
+    fn run(&mut self, start: usize) -> Result<Option<Slots>, EvalError> {
+        self.positions.fill(None);
+        self.positions[0] = Some(start);
+        self.stack.clear();
+        self.stack.push(Job::Explore {
+            pc: 0,
+            char_index: start,
+        });
+
+        while let Some(job) = self.stack.pop() {
+            let (mut pc, mut char_index) = match job {
+                Job::Restore { slot, value } => {
+                    self.positions[slot] = value;
+                    continue;
+                }
+                Job::Explore { pc, char_index } => (pc, char_index),
+            };
+
+            loop {
+                if pc >= self.inst.len() {
+                    return Err(EvalError::InvalidPC);
+                }
+                if !self.visit(pc, char_index) {
+                    break;
+                }
+
+                match &self.inst[pc] {
+                    Instruction::CharClass(class) => {
+                        if !eval_char_class(class, self.chars.get(char_index).copied()) {
+                            break;
+                        }
+                        pc += 1;
+                        char_index += 1;
+                    }
+                    Instruction::ByteClass(class) => {
+                        if !eval_byte_class(class, self.chars.get(char_index).copied()) {
+                            break;
+                        }
+                        pc += 1;
+                        char_index += 1;
+                    }
+                    Instruction::Assert(predicate) => {
+                        if !eval_assert(*predicate, self.chars, char_index, self.search_start) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::SaveStart(index) => {
+                        if !self.save(2 * index, char_index) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::SaveEnd(index) => {
+                        if !self.save(2 * index + 1, char_index) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::ResetMatchStart => {
+                        self.save(0, char_index);
+                        pc += 1;
+                    }
+                    Instruction::Split(left, right) => {
+                        self.stack.push(Job::Explore {
+                            pc: *right,
+                            char_index,
+                        });
+                        pc = *left;
+                    }
+                    Instruction::Jump(addr) => pc = *addr,
+                    Instruction::GraphemeCluster => {
+                        let Some(len) = grapheme_len(self.chars, char_index) else {
+                            break;
+                        };
+                        pc += 1;
+                        char_index += len;
+                    }
+                    Instruction::Match => {
+                        self.positions[1] = Some(char_index);
+                        return Ok(Some(to_slots(&self.positions)));
+                    }
+                    Instruction::Backref(_) | Instruction::Call(_) | Instruction::Return => break,
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Finds the leftmost-first match that starts at or after `search_start`.
+///
+/// When `starts` is given, matches may only start at those character
+/// indices. Callers must check `is_supported` first.
+pub(crate) fn search(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    starts: Option<&[usize]>,
+) -> Result<Option<Slots>, EvalError> {
+    let mut backtracker = Backtracker::new(inst, chars, search_start);
+    let candidates: Box<dyn Iterator<Item = usize>> = match starts {
+        Some(starts) => Box::new(starts.iter().copied()),
+        None => Box::new(search_start..=chars.len()),
+    };
+
+    for start in candidates {
+        if start < search_start || start > chars.len() {
+            continue;
+        }
+        if let Some(slots) = backtracker.run(start)? {
+            return Ok(Some(slots));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_VISITED_BITS, is_supported, search};
+    use crate::engine::{compiler::compile, parser::parse, pike_vm};
+
+    fn captures(pattern: &str, input: &str) -> Option<Vec<Option<(usize, usize)>>> {
+        let inst = compile(&parse(pattern).unwrap()).unwrap();
+        let chars: Vec<char> = input.chars().collect();
+        search(&inst, &chars, 0, None).unwrap()
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let inst = compile(&parse("(a|b)*\\X").unwrap()).unwrap();
+        assert!(is_supported(&inst, 100));
+        assert!(!is_supported(&inst, MAX_VISITED_BITS));
+
+        let inst = compile(&parse("(a)\\1").unwrap()).unwrap();
+        assert!(!is_supported(&inst, 1));
+    }
+
+    #[test]
+    fn test_search_captures() {
+        assert_eq!(
+            captures("(a+)(b*)", "xaab"),
+            Some(vec![Some((1, 4)), Some((1, 3)), Some((3, 4))])
+        );
+        assert_eq!(
+            captures("(a|ab)(c|bcd)(d*)", "abcd"),
+            Some(vec![Some((0, 4)), Some((0, 1)), Some((1, 4)), Some((4, 4))])
+        );
+        assert_eq!(captures("ab\\Kc", "xabc"), Some(vec![Some((3, 4))]));
+        assert_eq!(
+            captures("e\\X", "xe\u{301}\u{301}"),
+            Some(vec![Some((1, 4))])
+        );
+        assert_eq!(captures("^b", "ab"), None);
+    }
+
+    #[test]
+    fn test_failed_branch_restores_captures() {
+        // Group 2 is set while trying `(a)b` and undone when `b` fails.
+        assert_eq!(
+            captures("((a)b|ac)", "ac"),
+            Some(vec![Some((0, 2)), Some((0, 2)), None])
+        );
+    }
+
+    #[test]
+    fn test_search_agrees_with_pike_vm() {
+        let patterns = [
+            "a*",
+            "(a|b)*b",
+            "x(y?)+z",
+            "(ab|a)(bc|c)?",
+            "[0-9]{2,3}-(x|y){1,2}",
+            "\\bfoo\\b",
+            "^$",
+        ];
+        let inputs = ["", "ab", "aabb", "xyyz", "abc", "12-xy 123-x", "a foo b"];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                for start in 0..=chars.len() {
+                    let expected = pike_vm::search(&inst, &chars, start, None, false).unwrap();
+                    let actual = search(&inst, &chars, start, None).unwrap();
+                    assert_eq!(actual, expected, "{pattern} on {input:?} at {start}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_with_starts() {
+        let inst = compile(&parse("abc").unwrap()).unwrap();
+        let chars: Vec<char> = "abcabc".chars().collect();
+        let span = |starts: &[usize]| {
+            search(&inst, &chars, 0, Some(starts))
+                .unwrap()
+                .map(|slots| slots[0])
+        };
+        assert_eq!(span(&[3]), Some(Some((3, 6))));
+        assert_eq!(span(&[1, 2]), None);
+    }
+}
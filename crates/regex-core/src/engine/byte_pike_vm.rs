@@ -0,0 +1,226 @@
+//! Pike VM over the UTF-8 byte program, for haystacks that may not be
+//! valid UTF-8.
+//!
+//! Character classes only match well-formed encodings, so invalid bytes
+//! are skipped by them; `(?-u)` byte classes match any byte. Threads carry
+//! the start of their match only: `\K` moves it, and the other capture
+//! slots are ignored.
+
+use crate::engine::{
+    ast::Predicate,
+    evaluator::EvalError,
+    lazy_dfa::eval_predicate,
+    utf8::{ByteInstruction, ByteKind},
+};
+
+/// Threads waiting at one haystack position, in priority order.
+struct ThreadList {
+    /// Program counter and match start of each thread.
+    threads: Vec<(usize, usize)>,
+    /// Whether an instruction already holds a thread at this position.
+    occupied: Vec<bool>,
+}
+
+impl ThreadList {
+    fn new(len: usize) -> Self {
+        Self {
+            threads: Vec::new(),
+            occupied: vec![false; len],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.threads.clear();
+        self.occupied.fill(false);
+    }
+
+    /// Adds the thread at `pc` and follows its epsilon transitions at
+    /// `pos`, keeping the priority order of `Split` branches.
+    fn add(
+        &mut self,
+        program: &[ByteInstruction],
+        haystack: &[u8],
+        pc: usize,
+        start: usize,
+        pos: usize,
+        search_start: usize,
+    ) -> Result<(), EvalError> {
+        let mut stack = vec![(pc, start)];
+
+        while let Some((mut pc, mut start)) = stack.pop() {
+            loop {
+                let occupied = self.occupied.get_mut(pc).ok_or(EvalError::InvalidPC)?;
+                if *occupied {
+                    break;
+                }
+                *occupied = true;
+
+                match &program[pc] {
+                    ByteInstruction::Jump(addr) => pc = *addr,
+                    ByteInstruction::Split(left, right) => {
+                        stack.push((*right, start));
+                        pc = *left;
+                    }
+                    ByteInstruction::Assert(predicate) => {
+                        if !eval_assert(*predicate, haystack, pos, search_start) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    ByteInstruction::Save(slot) => {
+                        if *slot == 0 {
+                            start = pos;
+                        }
+                        pc += 1;
+                    }
+                    ByteInstruction::ByteRange(..) | ByteInstruction::Match => {
+                        self.threads.push((pc, start));
+                        break;
+                    }
+                    ByteInstruction::Fail => break,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluates an assertion at byte position `pos`.
+fn eval_assert(predicate: Predicate, haystack: &[u8], pos: usize, search_start: usize) -> bool {
+    if predicate == Predicate::StartOfSearch {
+        return pos == search_start;
+    }
+    let prev = pos
+        .checked_sub(1)
+        .map(|index| ByteKind::of(haystack[index]));
+    eval_predicate(predicate, prev, haystack.get(pos).copied())
+}
+
+/// Finds the leftmost-first match that starts at or after `search_start`.
+///
+/// Returns the matched `(start, end)` span as byte offsets.
+pub(crate) fn find(
+    program: &[ByteInstruction],
+    haystack: &[u8],
+    search_start: usize,
+) -> Result<Option<(usize, usize)>, EvalError> {
+    let mut current = ThreadList::new(program.len());
+    let mut next = ThreadList::new(program.len());
+    let mut matched = None;
+
+    for pos in search_start..=haystack.len() {
+        if matched.is_none() {
+            current.add(program, haystack, 0, pos, pos, search_start)?;
+        }
+        if current.threads.is_empty() {
+            if matched.is_some() {
+                break;
+            }
+            current.clear();
+            continue;
+        }
+
+        let byte = haystack.get(pos).copied();
+        for (pc, start) in current.threads.drain(..) {
+            match program[pc] {
+                ByteInstruction::ByteRange(low, high)
+                    if byte.is_some_and(|byte| (low..=high).contains(&byte)) =>
+                {
+                    next.add(program, haystack, pc + 1, start, pos + 1, search_start)?;
+                }
+                ByteInstruction::Match => {
+                    matched = Some((start, pos));
+                    // Lower-priority threads can no longer win.
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        next.clear();
+    }
+
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find;
+    use crate::engine::{
+        compiler::compile,
+        evaluator::{MatchKind, eval_captures},
+        parser::{ParseOptions, parse, parse_with_options},
+        utf8::compile_utf8,
+    };
+
+    fn byte_find(pattern: &str, haystack: &[u8]) -> Option<(usize, usize)> {
+        let options = ParseOptions {
+            allow_invalid_utf8: true,
+            ..ParseOptions::default()
+        };
+        let inst = compile(&parse_with_options(pattern, options).unwrap()).unwrap();
+        find(&compile_utf8(&inst).unwrap(), haystack, 0).unwrap()
+    }
+
+    #[test]
+    fn test_find_agrees_with_evaluator() {
+        let patterns = [
+            "a*",
+            "(a|b)*b",
+            "x(y?)+z",
+            "(ab|a)(bc|c)?",
+            "[0-9]{2,3}-(x|y){1,2}",
+            "^$",
+            "(a*)*",
+            "ab\\Kc",
+            "\\G[a-z]",
+            "[α-ω]+|é",
+            ".$",
+        ];
+        let inputs = [
+            "",
+            "ab",
+            "aabb",
+            "xyyz",
+            "abc",
+            "12-xy 123-x",
+            "aé λόγος",
+            "日本",
+        ];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let program = compile_utf8(&inst).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                let offsets: Vec<usize> = input
+                    .char_indices()
+                    .map(|(offset, _)| offset)
+                    .chain([input.len()])
+                    .collect();
+                for (start, &byte_start) in offsets.iter().enumerate() {
+                    let expected = eval_captures(&inst, &chars, start, MatchKind::LeftmostFirst)
+                        .unwrap()
+                        .and_then(|slots| slots[0])
+                        .map(|(start, end)| (offsets[start], offsets[end]));
+                    let actual = find(&program, input.as_bytes(), byte_start).unwrap();
+                    assert_eq!(actual, expected, "{pattern} on {input:?} at {start}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_invalid_utf8() {
+        assert_eq!(
+            byte_find("(?-u)\\xFF+", b"a\xFF\xFEb\xFF\xFF"),
+            Some((1, 2))
+        );
+        assert_eq!(byte_find("b(?-u:.)", b"ab\xC3"), Some((1, 3)));
+        // Unicode classes skip bytes that are not part of a valid encoding.
+        assert_eq!(byte_find(".+", b"\xFFa\xC3\xA9\xE6b"), Some((1, 4)));
+        assert_eq!(byte_find("é", b"\xC3\xC3\xA9"), Some((1, 3)));
+        assert_eq!(byte_find("^.$", b"\x80"), None);
+    }
+}
@@ -1,8 +1,14 @@
 //! Compile an AST into an instruction sequence (`Instruction`).
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use thiserror::Error;
 
-use crate::engine::{ast::Ast, instruction::Instruction, safe_add};
+use crate::engine::{
+    ast::{Ast, Predicate},
+    instruction::Instruction,
+    safe_add,
+};
 
 /// Errors returned while compiling AST nodes into instructions.
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -13,16 +19,39 @@ pub enum CompileError {
     /// A backreference points to a capture group that does not exist.
     #[error("CompileError: InvalidBackreference({0})")]
     InvalidBackreference(usize),
+    /// A subroutine call points to a capture group that does not exist.
+    #[error("CompileError: InvalidSubroutineCall({0})")]
+    InvalidSubroutineCall(usize),
+    /// A subroutine call to this group can recur without reading a
+    /// character, as in `(?R)?a`, so it would recurse forever.
+    #[error("CompileError: LeftRecursion({0})")]
+    LeftRecursion(usize),
+    /// Determinizing the pattern needs more DFA states than the limit.
+    #[error("CompileError: DfaTooLarge({0})")]
+    DfaTooLarge(usize),
+    /// The pattern uses features a DFA cannot express, such as backreferences.
+    #[error("CompileError: DfaUnsupported")]
+    DfaUnsupported,
+    /// The pattern uses features that cannot run right-to-left, such as
+    /// backreferences.
+    #[error("CompileError: ReverseUnsupported")]
+    ReverseUnsupported,
 }
 
 /// Stateful Thompson-style compiler.
 ///
 /// `p_counter` tracks the next instruction address.
 /// `instructions` stores emitted bytecode-like instructions.
+/// `pending_calls` stores `Call` addresses and their target group until
+/// subroutine bodies are emitted; `subroutines` maps a group to its body.
+/// `reverse` emits the program of the reversed language.
 #[derive(Default, Debug)]
 struct Compiler {
     p_counter: usize,
     instructions: Vec<Instruction>,
+    pending_calls: Vec<(usize, usize)>,
+    subroutines: BTreeMap<usize, usize>,
+    reverse: bool,
 }
 
 impl Compiler {
@@ -68,6 +97,17 @@ impl Compiler {
         }
     }
 
+    /// Patches the target of a previously emitted `Call`.
+    fn patch_call(&mut self, call_index: usize, target: usize) -> Result<(), CompileError> {
+        match self.instructions.get_mut(call_index) {
+            Some(Instruction::Call(addr)) => {
+                *addr = target;
+                Ok(())
+            }
+            _ => Err(CompileError::PCOverFlow),
+        }
+    }
+
     /// Patches the jump target of a previously emitted `Jump`.
     fn patch_jump(&mut self, jump_index: usize, target: usize) -> Result<(), CompileError> {
         match self.instructions.get_mut(jump_index) {
@@ -81,12 +121,31 @@ impl Compiler {
 
     /// Emits instructions for one AST node.
     fn gen_expr(&mut self, ast: &Ast) -> Result<(), CompileError> {
+        if self.reverse {
+            match ast {
+                Ast::Assertion(predicate) => {
+                    let predicate =
+                        reverse_predicate(*predicate).ok_or(CompileError::ReverseUnsupported)?;
+                    self.push_instruction(Instruction::Assert(predicate))?;
+                    return Ok(());
+                }
+                Ast::Backreference(_)
+                | Ast::ResetMatchStart
+                | Ast::GraphemeCluster
+                | Ast::SubroutineCall(_) => return Err(CompileError::ReverseUnsupported),
+                _ => {}
+            }
+        }
         match ast {
             Ast::Empty => Ok(()),
             Ast::CharClass(class) => {
                 self.push_instruction(Instruction::CharClass(class.clone()))?;
                 Ok(())
             }
+            Ast::ByteClass(class) => {
+                self.push_instruction(Instruction::ByteClass(class.clone()))?;
+                Ok(())
+            }
             Ast::Assertion(predicate) => {
                 self.push_instruction(Instruction::Assert(*predicate))?;
                 Ok(())
@@ -107,6 +166,19 @@ impl Compiler {
                 self.push_instruction(Instruction::Backref(*index))?;
                 Ok(())
             }
+            Ast::ResetMatchStart => {
+                self.push_instruction(Instruction::ResetMatchStart)?;
+                Ok(())
+            }
+            Ast::GraphemeCluster => {
+                self.push_instruction(Instruction::GraphemeCluster)?;
+                Ok(())
+            }
+            Ast::SubroutineCall(group) => {
+                let call_index = self.push_instruction(Instruction::Call(0))?;
+                self.pending_calls.push((call_index, *group));
+                Ok(())
+            }
         }
     }
 
@@ -197,10 +269,17 @@ impl Compiler {
         }
     }
 
-    /// Emits concatenated expressions in order.
+    /// Emits concatenated expressions in order, or last to first when
+    /// compiling in reverse.
     fn gen_concat(&mut self, exprs: &[Ast]) -> Result<(), CompileError> {
-        for expr in exprs {
-            self.gen_expr(expr)?;
+        if self.reverse {
+            for expr in exprs.iter().rev() {
+                self.gen_expr(expr)?;
+            }
+        } else {
+            for expr in exprs {
+                self.gen_expr(expr)?;
+            }
         }
         Ok(())
     }
@@ -221,13 +300,75 @@ impl Compiler {
         self.patch_jump(jump_index, out)
     }
 
-    /// Finalizes the compiled program by appending `Match`.
-    fn finish(mut self) -> Result<Vec<Instruction>, CompileError> {
+    /// Emits one subroutine body per called group after the main program.
+    ///
+    /// Group `0` is the whole pattern. Bodies may call further groups, so
+    /// this runs until no call is left without a body, then patches calls.
+    fn gen_subroutines(&mut self, ast: &Ast) -> Result<(), CompileError> {
+        let mut next_call = 0;
+        while let Some(&(_, group)) = self.pending_calls.get(next_call) {
+            next_call += 1;
+            if self.subroutines.contains_key(&group) {
+                continue;
+            }
+
+            self.subroutines.insert(group, self.p_counter);
+            if group == 0 {
+                self.gen_expr(ast)?;
+            } else {
+                let expr =
+                    find_capture(ast, group).ok_or(CompileError::InvalidSubroutineCall(group))?;
+                self.gen_capture(expr, group)?;
+            }
+            self.push_instruction(Instruction::Return)?;
+        }
+
+        for (call_index, group) in std::mem::take(&mut self.pending_calls) {
+            let target = self.subroutines[&group];
+            self.patch_call(call_index, target)?;
+        }
+        Ok(())
+    }
+
+    /// Finalizes the compiled program by appending `Match` and subroutine bodies.
+    fn finish(mut self, ast: &Ast) -> Result<Vec<Instruction>, CompileError> {
         self.push_instruction(Instruction::Match)?;
+        self.gen_subroutines(ast)?;
         Ok(self.instructions)
     }
 }
 
+/// Returns the assertion checking at the same place in the reversed text,
+/// or `None` for `\G` and the CRLF-mode anchors, whose terminator `\r\n`
+/// reads `\n\r` backwards.
+fn reverse_predicate(predicate: Predicate) -> Option<Predicate> {
+    match predicate {
+        Predicate::StartOfLine => Some(Predicate::EndOfLine),
+        Predicate::EndOfLine => Some(Predicate::StartOfLine),
+        Predicate::StartOfText => Some(Predicate::EndOfText),
+        Predicate::EndOfText => Some(Predicate::StartOfText),
+        Predicate::WordBoundary | Predicate::NonWordBoundary => Some(predicate),
+        Predicate::StartOfCrlfLine | Predicate::EndOfCrlfLine | Predicate::StartOfSearch => None,
+    }
+}
+
+/// Returns the inner expression of capture group `index`.
+fn find_capture(ast: &Ast, index: usize) -> Option<&Ast> {
+    match ast {
+        Ast::Capture { expr, index: i } if *i == index => Some(expr),
+        Ast::Capture { expr, .. }
+        | Ast::ZeroOrMore { expr, .. }
+        | Ast::OneOrMore { expr, .. }
+        | Ast::ZeroOrOne { expr, .. }
+        | Ast::Repeat { expr, .. } => find_capture(expr, index),
+        Ast::Concat(exprs) => exprs.iter().find_map(|expr| find_capture(expr, index)),
+        Ast::Alternate(left, right) => {
+            find_capture(left, index).or_else(|| find_capture(right, index))
+        }
+        _ => None,
+    }
+}
+
 /// Returns the maximum capture index used in the AST.
 fn max_capture_index(ast: &Ast) -> usize {
     match ast {
@@ -242,7 +383,7 @@ fn max_capture_index(ast: &Ast) -> usize {
     }
 }
 
-/// Validates that every backreference points to an existing capture.
+/// Validates that every backreference and subroutine call points to an existing capture.
 fn validate_backreferences(ast: &Ast, max_capture: usize) -> Result<(), CompileError> {
     match ast {
         Ast::Backreference(index) => {
@@ -252,6 +393,13 @@ fn validate_backreferences(ast: &Ast, max_capture: usize) -> Result<(), CompileE
                 Ok(())
             }
         }
+        Ast::SubroutineCall(index) => {
+            if *index > max_capture {
+                Err(CompileError::InvalidSubroutineCall(*index))
+            } else {
+                Ok(())
+            }
+        }
         Ast::Capture { expr, .. }
         | Ast::ZeroOrMore { expr, .. }
         | Ast::OneOrMore { expr, .. }
@@ -271,21 +419,139 @@ fn validate_backreferences(ast: &Ast, max_capture: usize) -> Result<(), CompileE
     }
 }
 
+/// Adds to `calls` the groups `ast` may call before reading a character,
+/// and returns whether it can match without reading one. `nullable` tells
+/// which groups can; a backreference may be empty.
+fn leading_calls(ast: &Ast, nullable: &[bool], calls: &mut BTreeSet<usize>) -> bool {
+    match ast {
+        Ast::Empty | Ast::Assertion(_) | Ast::ResetMatchStart | Ast::Backreference(_) => true,
+        Ast::CharClass(_) | Ast::ByteClass(_) | Ast::GraphemeCluster => false,
+        Ast::SubroutineCall(group) => {
+            calls.insert(*group);
+            nullable.get(*group).copied().unwrap_or(false)
+        }
+        Ast::Capture { expr, .. } | Ast::OneOrMore { expr, .. } => {
+            leading_calls(expr, nullable, calls)
+        }
+        Ast::ZeroOrMore { expr, .. } | Ast::ZeroOrOne { expr, .. } => {
+            leading_calls(expr, nullable, calls);
+            true
+        }
+        Ast::Repeat { expr, min, .. } => leading_calls(expr, nullable, calls) || *min == 0,
+        Ast::Concat(exprs) => exprs
+            .iter()
+            .all(|expr| leading_calls(expr, nullable, calls)),
+        Ast::Alternate(left, right) => {
+            let left = leading_calls(left, nullable, calls);
+            leading_calls(right, nullable, calls) || left
+        }
+    }
+}
+
+/// Rejects subroutine calls that can recur without reading a character,
+/// which would otherwise fail every search at run time.
+fn validate_recursion(ast: &Ast, max_capture: usize) -> Result<(), CompileError> {
+    let bodies: Vec<Option<&Ast>> = (0..=max_capture)
+        .map(|group| match group {
+            0 => Some(ast),
+            _ => find_capture(ast, group),
+        })
+        .collect();
+
+    // Which groups can match without reading a character, to a fixed point.
+    let mut nullable = vec![false; bodies.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (group, body) in bodies.iter().enumerate() {
+            if let Some(body) = body
+                && !nullable[group]
+                && leading_calls(body, &nullable, &mut BTreeSet::new())
+            {
+                nullable[group] = true;
+                changed = true;
+            }
+        }
+    }
+
+    let leading: Vec<BTreeSet<usize>> = bodies
+        .iter()
+        .map(|body| {
+            let mut calls = BTreeSet::new();
+            if let Some(body) = body {
+                leading_calls(body, &nullable, &mut calls);
+            }
+            calls
+        })
+        .collect();
+    for group in 0..leading.len() {
+        let mut seen = BTreeSet::new();
+        let mut stack: Vec<usize> = leading[group].iter().copied().collect();
+        while let Some(callee) = stack.pop() {
+            if callee == group {
+                return Err(CompileError::LeftRecursion(group));
+            }
+            if seen.insert(callee)
+                && let Some(calls) = leading.get(callee)
+            {
+                stack.extend(calls);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Compiles an AST into an executable instruction sequence.
 pub fn compile(ast: &Ast) -> Result<Vec<Instruction>, CompileError> {
     let max_capture = max_capture_index(ast);
     validate_backreferences(ast, max_capture)?;
+    validate_recursion(ast, max_capture)?;
 
     let mut compiler = Compiler::default();
     compiler.gen_expr(ast)?;
-    compiler.finish()
+    compiler.finish(ast)
+}
+
+/// Compiles an AST like `compile`, with a leading `\G` so that every match
+/// starts where the search starts.
+///
+/// Unlike a `\G` written in the pattern, the assertion is not repeated by
+/// a recursive `(?R)`.
+pub fn compile_anchored(ast: &Ast) -> Result<Vec<Instruction>, CompileError> {
+    let max_capture = max_capture_index(ast);
+    validate_backreferences(ast, max_capture)?;
+    validate_recursion(ast, max_capture)?;
+
+    let mut compiler = Compiler::default();
+    compiler.push_instruction(Instruction::Assert(Predicate::StartOfSearch))?;
+    compiler.gen_expr(ast)?;
+    compiler.finish(ast)
+}
+
+/// Compiles an AST into the program of its reversed language, which
+/// matches a string read right to left wherever `ast` matches it read left
+/// to right.
+///
+/// Run over the reversed text, the program finds match starts from an end
+/// position. Line and text anchors swap ends; captures record spans of the
+/// reversed text.
+pub fn compile_reverse(ast: &Ast) -> Result<Vec<Instruction>, CompileError> {
+    let mut compiler = Compiler {
+        reverse: true,
+        ..Compiler::default()
+    };
+    compiler.gen_expr(ast)?;
+    compiler.finish(ast)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use crate::engine::{
         ast::{CharClass, CharRange, Predicate},
-        compiler::{CompileError, compile},
+        compiler::{CompileError, compile, compile_anchored, compile_reverse},
+        evaluator::{eval_assert, eval_char_class},
         instruction::Instruction,
         parser::parse,
     };
@@ -361,10 +627,213 @@ mod tests {
         assert_eq!(actual, expect);
     }
 
+    #[test]
+    fn test_compile_recursion() {
+        let ast = parse("a(?R)?b").unwrap();
+        let actual = compile(&ast).unwrap();
+        let expect = vec![
+            literal('a'),
+            Instruction::Split(2, 3),
+            Instruction::Call(5),
+            literal('b'),
+            Instruction::Match,
+            literal('a'),
+            Instruction::Split(7, 8),
+            Instruction::Call(5),
+            literal('b'),
+            Instruction::Return,
+        ];
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_compile_subroutine_call() {
+        let ast = parse("(a)(?1)").unwrap();
+        let actual = compile(&ast).unwrap();
+        let expect = vec![
+            Instruction::SaveStart(1),
+            literal('a'),
+            Instruction::SaveEnd(1),
+            Instruction::Call(5),
+            Instruction::Match,
+            Instruction::SaveStart(1),
+            literal('a'),
+            Instruction::SaveEnd(1),
+            Instruction::Return,
+        ];
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_compile_invalid_subroutine_call() {
+        let ast = parse("(a)(?2)").unwrap();
+        let actual = compile(&ast);
+        assert_eq!(actual, Err(CompileError::InvalidSubroutineCall(2)));
+    }
+
+    #[test]
+    fn test_compile_left_recursion() {
+        for (pattern, group) in [
+            ("(?R)?a", 0),
+            ("((?1))", 1),
+            ("(a|(?2)b)(c?(?1))", 1),
+            ("()\\1(?R)", 0),
+            (" *^|\\N?\\V+((?1))", 1),
+        ] {
+            let ast = parse(pattern).unwrap();
+            assert_eq!(
+                compile(&ast),
+                Err(CompileError::LeftRecursion(group)),
+                "{pattern}"
+            );
+            assert_eq!(
+                compile_anchored(&ast),
+                Err(CompileError::LeftRecursion(group))
+            );
+        }
+        for pattern in ["a(?R)?b", "(a|\\((?1)*\\))", "(a)(?1)", "x?(a(?R)?)"] {
+            assert!(compile(&parse(pattern).unwrap()).is_ok(), "{pattern}");
+        }
+    }
+
     #[test]
     fn test_compile_invalid_backreference() {
         let ast = parse("(a)\\2").unwrap();
         let actual = compile(&ast);
         assert_eq!(actual, Err(CompileError::InvalidBackreference(2)));
     }
+
+    #[test]
+    fn test_compile_anchored() {
+        let ast = parse("a(?R)?").unwrap();
+        let actual = compile_anchored(&ast).unwrap();
+        let expect = vec![
+            Instruction::Assert(Predicate::StartOfSearch),
+            literal('a'),
+            Instruction::Split(3, 4),
+            Instruction::Call(5),
+            Instruction::Match,
+            literal('a'),
+            Instruction::Split(7, 8),
+            Instruction::Call(5),
+            Instruction::Return,
+        ];
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_compile_reverse() {
+        let ast = parse("^a(bc|d)*$").unwrap();
+        let actual = compile_reverse(&ast).unwrap();
+        let expect = vec![
+            Instruction::Assert(Predicate::StartOfLine),
+            Instruction::Split(2, 10),
+            Instruction::SaveStart(1),
+            Instruction::Split(4, 7),
+            literal('c'),
+            literal('b'),
+            Instruction::Jump(8),
+            literal('d'),
+            Instruction::SaveEnd(1),
+            Instruction::Jump(1),
+            literal('a'),
+            Instruction::Assert(Predicate::EndOfLine),
+            Instruction::Match,
+        ];
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_compile_reverse_unsupported() {
+        for pattern in ["(a)\\1", "a\\Kb", "a(?R)?b", "\\X", "\\Ga"] {
+            let ast = parse(pattern).unwrap();
+            assert_eq!(
+                compile_reverse(&ast),
+                Err(CompileError::ReverseUnsupported),
+                "{pattern}"
+            );
+        }
+    }
+
+    /// Returns every end of a match of `code` starting at `start`.
+    fn match_ends(code: &[Instruction], chars: &[char], start: usize) -> BTreeSet<usize> {
+        let mut ends = BTreeSet::new();
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![(0, start)];
+        while let Some((pc, pos)) = stack.pop() {
+            if !seen.insert((pc, pos)) {
+                continue;
+            }
+            match &code[pc] {
+                Instruction::CharClass(class) => {
+                    if eval_char_class(class, chars.get(pos).copied()) {
+                        stack.push((pc + 1, pos + 1));
+                    }
+                }
+                Instruction::Assert(predicate) => {
+                    if eval_assert(*predicate, chars, pos, 0) {
+                        stack.push((pc + 1, pos));
+                    }
+                }
+                Instruction::SaveStart(_) | Instruction::SaveEnd(_) => stack.push((pc + 1, pos)),
+                Instruction::Split(first, second) => stack.extend([(*first, pos), (*second, pos)]),
+                Instruction::Jump(target) => stack.push((*target, pos)),
+                Instruction::Match => {
+                    ends.insert(pos);
+                }
+                instruction => panic!("unexpected {instruction:?}"),
+            }
+        }
+        ends
+    }
+
+    /// Every span the forward program matches in a text, the reverse
+    /// program matches at the mirrored span of the reversed text.
+    #[test]
+    fn test_compile_reverse_agrees_with_forward() {
+        let patterns = [
+            "abc",
+            "a(b|cd)*e",
+            "x?[a-c]{1,3}y+",
+            "(ab|a)(c|bcd)",
+            "^ab|cd$",
+            "^$",
+            "日本(語)?",
+            "[^x]a|a*",
+        ];
+        let inputs = [
+            "",
+            "abc abcabc",
+            "abdcde acdbe ae",
+            "xaby xccyyy y",
+            "abcd\nab cd\n",
+            "日本語 日本",
+            "\naa\nxa",
+        ];
+        for pattern in patterns {
+            let ast = parse(pattern).unwrap();
+            let forward = compile(&ast).unwrap();
+            let reverse = compile_reverse(&ast).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                let reversed: Vec<char> = chars.iter().rev().copied().collect();
+                let len = chars.len();
+                let forward_spans: BTreeSet<(usize, usize)> = (0..=len)
+                    .flat_map(|start| {
+                        match_ends(&forward, &chars, start)
+                            .into_iter()
+                            .map(move |end| (start, end))
+                    })
+                    .collect();
+                let reverse_spans: BTreeSet<(usize, usize)> = (0..=len)
+                    .flat_map(|start| {
+                        match_ends(&reverse, &reversed, start)
+                            .into_iter()
+                            .map(move |end| (len - end, len - start))
+                    })
+                    .collect();
+                assert_eq!(forward_spans, reverse_spans, "{pattern} on {input:?}");
+            }
+        }
+    }
 }
@@ -0,0 +1,321 @@
+//! Fully determinized, minimized DFA answering whether a line contains a
+//! match.
+//!
+//! The states are those of the lazy DFA, built ahead of time by exploring
+//! every transition from the start state over the UTF-8 byte program.
+//! Bytes are first mapped to equivalence classes: two bytes share a class
+//! when every byte range of the program and every assertion treats them
+//! alike, so the transition table has one column per class rather than per
+//! byte. The table is then minimized with Hopcroft's partition refinement.
+//!
+//! Determinization can blow up exponentially, so construction stops with
+//! `CompileError::DfaTooLarge` once the state limit is exceeded.
+
+use std::collections::HashMap;
+
+use crate::engine::{
+    RegexError,
+    compiler::CompileError,
+    instruction::Instruction,
+    lazy_dfa::{self, StateKey},
+    utf8::{ByteClasses, ByteInstruction, compile_utf8},
+};
+
+/// Default number of states a DFA may have before construction fails.
+pub(crate) const DEFAULT_STATE_LIMIT: usize = 10_000;
+
+/// Transition table before and after minimization.
+struct Table {
+    /// `transitions[state * stride + class]` is the next state.
+    transitions: Vec<usize>,
+    stride: usize,
+    /// Whether a match ends at the end of the text in each state.
+    matches_at_end: Vec<bool>,
+    start: usize,
+    /// Absorbing state entered once a match has ended; after minimization
+    /// it also absorbs states from which every continuation matches.
+    matched: usize,
+}
+
+impl Table {
+    fn state_count(&self) -> usize {
+        self.matches_at_end.len()
+    }
+}
+
+/// Ahead-of-time DFA; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct Dfa {
+    classes: ByteClasses,
+    transitions: Vec<usize>,
+    matches_at_end: Vec<bool>,
+    start: usize,
+    matched: usize,
+}
+
+impl Dfa {
+    /// Builds the minimized DFA of `inst` with at most `state_limit` states
+    /// before minimization.
+    pub(crate) fn new(inst: &[Instruction], state_limit: usize) -> Result<Self, RegexError> {
+        let program = compile_utf8(inst).ok_or(CompileError::DfaUnsupported)?;
+        let classes = ByteClasses::new(&program);
+        let table = minimize(&determinize(&program, &classes, state_limit)?);
+
+        Ok(Self {
+            classes,
+            transitions: table.transitions,
+            matches_at_end: table.matches_at_end,
+            start: table.start,
+            matched: table.matched,
+        })
+    }
+
+    /// Returns whether `haystack` contains a match.
+    pub(crate) fn is_match(&self, haystack: &[u8]) -> bool {
+        let stride = self.classes.len();
+        let mut state = self.start;
+        for &byte in haystack {
+            state = self.transitions[state * stride + self.classes.get(byte)];
+            if state == self.matched {
+                return true;
+            }
+        }
+        self.matches_at_end[state]
+    }
+}
+
+/// Builds the transition table of every state reachable from the start.
+fn determinize(
+    program: &[ByteInstruction],
+    classes: &ByteClasses,
+    state_limit: usize,
+) -> Result<Table, RegexError> {
+    let stride = classes.len();
+    // State 0 is the absorbing match state; it has no key.
+    let mut transitions = vec![0; stride];
+    let mut matches_at_end = vec![true];
+    let mut keys: Vec<StateKey> = vec![lazy_dfa::start_key()];
+    let mut ids: HashMap<StateKey, usize> = HashMap::from([(keys[0].clone(), 1)]);
+
+    let mut index = 0;
+    while let Some(key) = keys.get(index).cloned() {
+        index += 1;
+        matches_at_end.push(lazy_dfa::matches_at_end(program, &key)?);
+        for &byte in classes.representatives() {
+            let next = match lazy_dfa::step(program, &key, byte)? {
+                None => 0,
+                Some(next_key) => match ids.get(&next_key) {
+                    Some(&id) => id,
+                    None => {
+                        if keys.len() + 1 >= state_limit {
+                            return Err(CompileError::DfaTooLarge(state_limit).into());
+                        }
+                        keys.push(next_key.clone());
+                        ids.insert(next_key, keys.len());
+                        keys.len()
+                    }
+                },
+            };
+            transitions.push(next);
+        }
+    }
+
+    Ok(Table {
+        transitions,
+        stride,
+        matches_at_end,
+        start: 1,
+        matched: 0,
+    })
+}
+
+/// Merges equivalent states with Hopcroft's algorithm.
+fn minimize(table: &Table) -> Table {
+    let state_count = table.state_count();
+    let stride = table.stride;
+
+    // predecessors[class][state] lists the states entering `state` on `class`.
+    let mut predecessors = vec![vec![Vec::new(); state_count]; stride];
+    for state in 0..state_count {
+        for (class, class_predecessors) in predecessors.iter_mut().enumerate() {
+            let next = table.transitions[state * stride + class];
+            class_predecessors[next].push(state);
+        }
+    }
+
+    let mut blocks: Vec<Vec<usize>> = vec![Vec::new(); 2];
+    for state in 0..state_count {
+        blocks[usize::from(table.matches_at_end[state])].push(state);
+    }
+    blocks.retain(|block| !block.is_empty());
+    let mut block_of = vec![0; state_count];
+    for (id, block) in blocks.iter().enumerate() {
+        for &state in block {
+            block_of[state] = id;
+        }
+    }
+
+    let mut worklist: Vec<usize> = (0..blocks.len()).collect();
+    let mut in_worklist = vec![true; blocks.len()];
+    let mut marked = vec![false; state_count];
+
+    while let Some(splitter) = worklist.pop() {
+        in_worklist[splitter] = false;
+        let splitter_states = blocks[splitter].clone();
+
+        for class_predecessors in &predecessors {
+            // Group the predecessors of the splitter by their block.
+            let mut touched: Vec<usize> = Vec::new();
+            let mut inside: HashMap<usize, Vec<usize>> = HashMap::new();
+            for &state in &splitter_states {
+                for &predecessor in &class_predecessors[state] {
+                    if marked[predecessor] {
+                        continue;
+                    }
+                    marked[predecessor] = true;
+                    let block = block_of[predecessor];
+                    inside
+                        .entry(block)
+                        .or_insert_with(|| {
+                            touched.push(block);
+                            Vec::new()
+                        })
+                        .push(predecessor);
+                }
+            }
+
+            for block in touched {
+                let members = inside.remove(&block).unwrap_or_default();
+                for &state in &members {
+                    marked[state] = false;
+                }
+                if members.len() == blocks[block].len() {
+                    continue;
+                }
+
+                let new_block = blocks.len();
+                for &state in &members {
+                    block_of[state] = new_block;
+                }
+                blocks[block].retain(|&state| block_of[state] == block);
+                let smaller = if members.len() < blocks[block].len() {
+                    new_block
+                } else {
+                    block
+                };
+                blocks.push(members);
+                in_worklist.push(false);
+
+                if in_worklist[block] {
+                    worklist.push(new_block);
+                    in_worklist[new_block] = true;
+                } else {
+                    worklist.push(smaller);
+                    in_worklist[smaller] = true;
+                }
+            }
+        }
+    }
+
+    let mut transitions = Vec::with_capacity(blocks.len() * stride);
+    let mut matches_at_end = Vec::with_capacity(blocks.len());
+    for block in &blocks {
+        let state = block[0];
+        let row = &table.transitions[state * stride..(state + 1) * stride];
+        transitions.extend(row.iter().map(|&next| block_of[next]));
+        matches_at_end.push(table.matches_at_end[state]);
+    }
+
+    Table {
+        transitions,
+        stride,
+        matches_at_end,
+        start: block_of[table.start],
+        matched: block_of[table.matched],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dfa;
+    use crate::engine::{
+        RegexError,
+        compiler::{CompileError, compile},
+        evaluator::eval,
+        parser::parse,
+    };
+
+    fn build(pattern: &str) -> Result<Dfa, RegexError> {
+        Dfa::new(&compile(&parse(pattern).unwrap()).unwrap(), 1_000)
+    }
+
+    #[test]
+    fn test_is_match_agrees_with_evaluator() {
+        let patterns = [
+            "abc",
+            "a*",
+            "(a|b)*abb",
+            "^foo",
+            "bar$",
+            "^$",
+            "x[^y]+z",
+            "(a|b){2,3}c",
+            "\\Aab",
+            "[α-ω]+s",
+            "^.$",
+            "é|日本",
+        ];
+        let inputs = [
+            "",
+            "abc",
+            "xabbx",
+            "foo bar",
+            "a\nfoo",
+            "bar\nx",
+            "\n",
+            "foobar",
+            "xyz xaz",
+            "abab",
+            "aabc",
+            "ab\u{e9}",
+            "λόγος",
+            "日",
+            "x日本語z",
+            "a\u{10348}c",
+        ];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let dfa = Dfa::new(&inst, 1_000).unwrap();
+            for input in inputs {
+                let expected = eval(&inst, input).unwrap();
+                assert_eq!(
+                    dfa.is_match(input.as_bytes()),
+                    expected,
+                    "{pattern} on {input:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimization_merges_equivalent_states() {
+        let states = |pattern: &str| build(pattern).unwrap().matches_at_end.len();
+        assert_eq!(states("(a|b)*abb"), states("(b|a)*abb"));
+        assert_eq!(states("(a|b)*abb"), states("(a*b*)*abb"));
+        assert_eq!(states("(a|a)(b|b)"), states("ab"));
+        // Start, after `a`, after `ab`, and the match state.
+        assert_eq!(states("abb"), 4);
+    }
+
+    #[test]
+    fn test_state_limit() {
+        assert_eq!(
+            build("a(a|b){12}c").unwrap_err(),
+            RegexError::Compile(CompileError::DfaTooLarge(1_000))
+        );
+        assert_eq!(
+            build("(a)\\1").unwrap_err(),
+            RegexError::Compile(CompileError::DfaUnsupported)
+        );
+    }
+}
@@ -5,11 +5,16 @@ use std::collections::HashSet;
 use thiserror::Error;
 
 use crate::engine::{
-    ast::{CharClass, Predicate},
+    anchor::{anchored_starts, start_anchor},
+    ast::{ByteClass, CharClass, Predicate},
+    grapheme::grapheme_len,
     instruction::Instruction,
     safe_add,
 };
 
+/// Maximum nesting depth of subroutine calls (`(?R)`, `(?1)`, ...).
+const MAX_CALL_DEPTH: usize = 1000;
+
 /// Errors returned while evaluating instructions.
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum EvalError {
@@ -22,6 +27,33 @@ pub enum EvalError {
     /// Instruction pointer points outside the instruction array.
     #[error("EvalError: InvalidPC")]
     InvalidPC,
+    /// Subroutine calls nested deeper than `MAX_CALL_DEPTH`.
+    #[error("EvalError: CallDepthExceeded")]
+    CallDepthExceeded,
+}
+
+/// Which match wins among the matches that start at the same position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum MatchKind {
+    /// The first match in backtracking priority order (Perl semantics).
+    #[default]
+    LeftmostFirst,
+    /// The longest match, with captures chosen by POSIX subexpression rules.
+    LeftmostLongest,
+}
+
+/// Spans of one match as character indices; index 0 is the whole match
+/// and index `n` is capture group `n` (`None` if it did not participate).
+pub(crate) type Slots = Vec<Option<(usize, usize)>>;
+
+/// Caller context saved by `Call` and restored by `Return`.
+///
+/// Captures set inside a subroutine are discarded on return.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct CallFrame {
+    return_pc: usize,
+    capture_start: Vec<Option<usize>>,
+    capture_end: Vec<Option<usize>>,
 }
 
 /// Runtime state for one NFA execution branch.
@@ -29,8 +61,10 @@ pub enum EvalError {
 struct State {
     pc: usize,
     char_index: usize,
+    match_start: usize,
     capture_start: Vec<Option<usize>>,
     capture_end: Vec<Option<usize>>,
+    call_stack: Vec<CallFrame>,
 }
 
 impl State {
@@ -39,9 +73,29 @@ impl State {
         Self {
             pc: 0,
             char_index: start,
+            match_start: start,
             capture_start: vec![None; capture_slots],
             capture_end: vec![None; capture_slots],
+            call_stack: Vec::new(),
+        }
+    }
+
+    /// Collects the match span and capture spans of a state at `Match`.
+    fn slots(&self) -> Slots {
+        let mut slots: Slots = self
+            .capture_start
+            .iter()
+            .zip(&self.capture_end)
+            .map(|(start, end)| match (start, end) {
+                (Some(start), Some(end)) if start <= end => Some((*start, *end)),
+                _ => None,
+            })
+            .collect();
+        if slots.is_empty() {
+            slots.push(None);
         }
+        slots[0] = Some((self.match_start, self.char_index));
+        slots
     }
 }
 
@@ -50,8 +104,10 @@ impl State {
 struct StateKey {
     pc: usize,
     char_index: usize,
+    match_start: usize,
     capture_start: Vec<Option<usize>>,
     capture_end: Vec<Option<usize>>,
+    call_stack: Vec<CallFrame>,
 }
 
 impl StateKey {
@@ -60,12 +116,54 @@ impl StateKey {
         Self {
             pc: state.pc,
             char_index: state.char_index,
+            match_start: state.match_start,
             capture_start: state.capture_start.clone(),
             capture_end: state.capture_end.clone(),
+            call_stack: state.call_stack.clone(),
+        }
+    }
+
+    /// Leaves out what cannot decide whether the state reaches `Match`:
+    /// where the match started and, unless `captures`, the capture
+    /// positions, including those saved by calls.
+    fn reduce(mut self, captures: bool) -> Self {
+        self.match_start = 0;
+        if !captures {
+            self.capture_start.clear();
+            self.capture_end.clear();
+            for frame in &mut self.call_stack {
+                frame.capture_start.clear();
+                frame.capture_end.clear();
+            }
         }
+        self
     }
 }
 
+/// Returns whether `candidate` beats `best` under POSIX rules.
+///
+/// Spans are compared in group order, whole match first: a participating
+/// group beats a missing one, then the leftmost start wins, then the
+/// longest end.
+fn is_posix_preferred(candidate: &Slots, best: &Slots) -> bool {
+    for (candidate, best) in candidate.iter().zip(best) {
+        match (candidate, best) {
+            (Some((c_start, c_end)), Some((b_start, b_end))) => {
+                if c_start != b_start {
+                    return c_start < b_start;
+                }
+                if c_end != b_end {
+                    return c_end > b_end;
+                }
+            }
+            (Some(_), None) => return true,
+            (None, Some(_)) => return false,
+            (None, None) => {}
+        }
+    }
+    false
+}
+
 /// Increments the program counter with overflow checks.
 fn increment_pc(pc: &mut usize) -> Result<(), EvalError> {
     safe_add(pc, &1, || EvalError::PCOverFlow)
@@ -77,7 +175,7 @@ fn increment_char_index(char_index: &mut usize, size: usize) -> Result<(), EvalE
 }
 
 /// Evaluates one character-class instruction against the current character.
-fn eval_char_class(class: &CharClass, current: Option<char>) -> bool {
+pub(crate) fn eval_char_class(class: &CharClass, current: Option<char>) -> bool {
     let Some(current_char) = current else {
         return false;
     };
@@ -94,8 +192,22 @@ fn eval_char_class(class: &CharClass, current: Option<char>) -> bool {
     }
 }
 
+/// Evaluates a raw byte class against the current character.
+///
+/// Decoded characters hold no raw bytes, so only ASCII characters can match.
+pub(crate) fn eval_byte_class(class: &ByteClass, current: Option<char>) -> bool {
+    current.is_some_and(|c| c.is_ascii() && class.contains(c as u8))
+}
+
 /// Evaluates one zero-width assertion at the current position.
-fn eval_assert(predicate: Predicate, chars: &[char], char_index: usize) -> bool {
+///
+/// `search_start` is the index where the current search began (`\G`).
+pub(crate) fn eval_assert(
+    predicate: Predicate,
+    chars: &[char],
+    char_index: usize,
+    search_start: usize,
+) -> bool {
     if char_index > chars.len() {
         return false;
     }
@@ -105,10 +217,34 @@ fn eval_assert(predicate: Predicate, chars: &[char], char_index: usize) -> bool
             char_index == 0 || chars.get(char_index.saturating_sub(1)) == Some(&'\n')
         }
         Predicate::EndOfLine => char_index == chars.len() || chars.get(char_index) == Some(&'\n'),
+        Predicate::StartOfCrlfLine => is_start_of_crlf_line(chars, char_index),
+        Predicate::EndOfCrlfLine => is_end_of_crlf_line(chars, char_index),
         Predicate::StartOfText => char_index == 0,
         Predicate::EndOfText => char_index == chars.len(),
         Predicate::WordBoundary => is_word_boundary(chars, char_index),
         Predicate::NonWordBoundary => !is_word_boundary(chars, char_index),
+        Predicate::StartOfSearch => char_index == search_start,
+    }
+}
+
+/// Returns whether `char_index` starts a line, never splitting `\r\n`.
+fn is_start_of_crlf_line(chars: &[char], char_index: usize) -> bool {
+    if char_index == 0 {
+        return true;
+    }
+    match chars[char_index - 1] {
+        '\n' => true,
+        '\r' => chars.get(char_index) != Some(&'\n'),
+        _ => false,
+    }
+}
+
+/// Returns whether `char_index` ends a line, never splitting `\r\n`.
+fn is_end_of_crlf_line(chars: &[char], char_index: usize) -> bool {
+    match chars.get(char_index) {
+        None | Some('\r') => true,
+        Some('\n') => char_index == 0 || chars[char_index - 1] != '\r',
+        Some(_) => false,
     }
 }
 
@@ -128,7 +264,7 @@ fn is_word_boundary(chars: &[char], char_index: usize) -> bool {
 }
 
 /// Defines word characters for `WordBoundary`.
-fn is_word_char(c: char) -> bool {
+pub(crate) fn is_word_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_'
 }
 
@@ -163,8 +299,34 @@ fn eval_backref(index: usize, state: &mut State, chars: &[char]) -> Result<bool,
     Ok(true)
 }
 
+/// Enters a subroutine at `addr`, saving the return address and captures.
+fn eval_call(addr: usize, state: &mut State) -> Result<(), EvalError> {
+    if state.call_stack.len() >= MAX_CALL_DEPTH {
+        return Err(EvalError::CallDepthExceeded);
+    }
+
+    let mut return_pc = state.pc;
+    increment_pc(&mut return_pc)?;
+    state.call_stack.push(CallFrame {
+        return_pc,
+        capture_start: state.capture_start.clone(),
+        capture_end: state.capture_end.clone(),
+    });
+    state.pc = addr;
+    Ok(())
+}
+
+/// Leaves the innermost subroutine and restores the caller's captures.
+fn eval_return(state: &mut State) -> Result<(), EvalError> {
+    let frame = state.call_stack.pop().ok_or(EvalError::InvalidPC)?;
+    state.pc = frame.return_pc;
+    state.capture_start = frame.capture_start;
+    state.capture_end = frame.capture_end;
+    Ok(())
+}
+
 /// Returns the largest capture index referenced by instructions.
-fn max_capture_index(inst: &[Instruction]) -> usize {
+pub(crate) fn max_capture_index(inst: &[Instruction]) -> usize {
     let mut max_index = 0;
     for instruction in inst {
         match instruction {
@@ -179,115 +341,310 @@ fn max_capture_index(inst: &[Instruction]) -> usize {
     max_index
 }
 
-/// Runs the NFA from a fixed starting character index.
-fn eval_from_start_inner(
-    inst: &[Instruction],
-    chars: &[char],
-    start: usize,
+/// Backtracking search over the start positions of one search.
+///
+/// Explored states are shared across start positions: a state explored
+/// from an earlier start without reaching `Match` fails from later ones
+/// too, as where the match started never decides whether it succeeds, nor
+/// do the captures unless a backreference reads them. Each state is thus
+/// explored once per search rather than once per start, as if the program
+/// were prefixed with a lazy `(?s:.)*?`.
+struct Search<'a> {
+    inst: &'a [Instruction],
+    chars: &'a [char],
+    search_start: usize,
+    kind: MatchKind,
     capture_slots: usize,
-) -> Result<bool, EvalError> {
-    let mut stack = vec![State::new(start, capture_slots)];
-    let mut visited = HashSet::new();
-
-    while let Some(mut state) = stack.pop() {
-        loop {
-            let key = StateKey::from_state(&state);
-            if !visited.insert(key) {
-                break;
+    /// Whether states differing only in captures must be told apart.
+    has_backref: bool,
+    /// Reduced keys of the states explored so far.
+    explored: HashSet<StateKey>,
+    /// With `MatchKind::LeftmostLongest`, the keys of the states explored
+    /// from the current start.
+    visited: HashSet<StateKey>,
+    /// With `MatchKind::LeftmostLongest`, whether states differing only in
+    /// captures are told apart, as POSIX captures need. Otherwise only the
+    /// end of the longest match is meaningful.
+    posix: bool,
+    /// With `posix`, the index no state goes past, where the match ends.
+    end_limit: Option<usize>,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        inst: &'a [Instruction],
+        chars: &'a [char],
+        search_start: usize,
+        kind: MatchKind,
+    ) -> Result<Self, EvalError> {
+        let capture_slots = max_capture_index(inst)
+            .checked_add(1)
+            .ok_or(EvalError::PCOverFlow)?;
+        Ok(Self {
+            inst,
+            chars,
+            search_start,
+            kind,
+            capture_slots,
+            has_backref: inst
+                .iter()
+                .any(|instruction| matches!(instruction, Instruction::Backref(_))),
+            explored: HashSet::new(),
+            visited: HashSet::new(),
+            posix: false,
+            end_limit: None,
+        })
+    }
+
+    /// Returns whether `state` is new, and records it.
+    fn visit(&mut self, state: &State) -> bool {
+        let key = StateKey::from_state(state);
+        match self.kind {
+            MatchKind::LeftmostFirst => self.explored.insert(key.reduce(self.has_backref)),
+            MatchKind::LeftmostLongest if self.posix => {
+                self.end_limit.is_none_or(|end| state.char_index <= end) && self.visited.insert(key)
             }
+            MatchKind::LeftmostLongest => {
+                let key = key.reduce(self.has_backref);
+                !self.explored.contains(&key) && self.visited.insert(key)
+            }
+        }
+    }
 
-            let instruction = match inst.get(state.pc) {
-                Some(instruction) => instruction,
-                None => return Err(EvalError::InvalidPC),
-            };
+    /// Runs the NFA from a fixed starting character index.
+    ///
+    /// Returns the match slots, where the start of slot 0 honors `\K`. With
+    /// `MatchKind::LeftmostLongest` every state is explored and the
+    /// preferred match is kept.
+    fn run(&mut self, start: usize) -> Result<Option<Slots>, EvalError> {
+        let mut stack = vec![State::new(start, self.capture_slots)];
+        let mut best: Option<Slots> = None;
+
+        while let Some(mut state) = stack.pop() {
+            loop {
+                if !self.visit(&state) {
+                    break;
+                }
 
-            match instruction {
-                Instruction::CharClass(class) => {
-                    if !eval_char_class(class, chars.get(state.char_index).copied()) {
-                        break;
+                let instruction = match self.inst.get(state.pc) {
+                    Some(instruction) => instruction,
+                    None => return Err(EvalError::InvalidPC),
+                };
+
+                match instruction {
+                    Instruction::CharClass(class) => {
+                        if !eval_char_class(class, self.chars.get(state.char_index).copied()) {
+                            break;
+                        }
+                        increment_pc(&mut state.pc)?;
+                        increment_char_index(&mut state.char_index, 1)?;
                     }
-                    increment_pc(&mut state.pc)?;
-                    increment_char_index(&mut state.char_index, 1)?;
-                }
-                Instruction::Assert(predicate) => {
-                    if !eval_assert(*predicate, chars, state.char_index) {
-                        break;
+                    Instruction::ByteClass(class) => {
+                        if !eval_byte_class(class, self.chars.get(state.char_index).copied()) {
+                            break;
+                        }
+                        increment_pc(&mut state.pc)?;
+                        increment_char_index(&mut state.char_index, 1)?;
                     }
-                    increment_pc(&mut state.pc)?;
-                }
-                Instruction::SaveStart(index) => {
-                    if let Some(slot) = state.capture_start.get_mut(*index) {
-                        *slot = Some(state.char_index);
-                    } else {
-                        break;
+                    Instruction::Assert(predicate) => {
+                        if !eval_assert(*predicate, self.chars, state.char_index, self.search_start)
+                        {
+                            break;
+                        }
+                        increment_pc(&mut state.pc)?;
                     }
-                    increment_pc(&mut state.pc)?;
-                }
-                Instruction::SaveEnd(index) => {
-                    if let Some(slot) = state.capture_end.get_mut(*index) {
-                        *slot = Some(state.char_index);
-                    } else {
-                        break;
+                    Instruction::SaveStart(index) => {
+                        if let Some(slot) = state.capture_start.get_mut(*index) {
+                            *slot = Some(state.char_index);
+                        } else {
+                            break;
+                        }
+                        increment_pc(&mut state.pc)?;
                     }
-                    increment_pc(&mut state.pc)?;
-                }
-                Instruction::Backref(index) => {
-                    if !eval_backref(*index, &mut state, chars)? {
+                    Instruction::SaveEnd(index) => {
+                        if let Some(slot) = state.capture_end.get_mut(*index) {
+                            *slot = Some(state.char_index);
+                        } else {
+                            break;
+                        }
+                        increment_pc(&mut state.pc)?;
+                    }
+                    Instruction::Backref(index) => {
+                        if !eval_backref(*index, &mut state, self.chars)? {
+                            break;
+                        }
+                    }
+                    Instruction::Split(left, right) => {
+                        let mut right_state = state.clone();
+                        right_state.pc = *right;
+                        stack.push(right_state);
+                        state.pc = *left;
+                    }
+                    Instruction::Jump(addr) => state.pc = *addr,
+                    Instruction::Call(addr) => eval_call(*addr, &mut state)?,
+                    Instruction::Return => eval_return(&mut state)?,
+                    Instruction::GraphemeCluster => {
+                        let Some(len) = grapheme_len(self.chars, state.char_index) else {
+                            break;
+                        };
+                        increment_pc(&mut state.pc)?;
+                        increment_char_index(&mut state.char_index, len)?;
+                    }
+                    Instruction::ResetMatchStart => {
+                        state.match_start = state.char_index;
+                        increment_pc(&mut state.pc)?;
+                    }
+                    Instruction::Match => {
+                        let slots = state.slots();
+                        if self.kind == MatchKind::LeftmostFirst {
+                            return Ok(Some(slots));
+                        }
+                        if best
+                            .as_ref()
+                            .is_none_or(|best| is_posix_preferred(&slots, best))
+                        {
+                            best = Some(slots);
+                        }
                         break;
                     }
                 }
-                Instruction::Split(left, right) => {
-                    let mut right_state = state.clone();
-                    right_state.pc = *right;
-                    stack.push(right_state);
-                    state.pc = *left;
-                }
-                Instruction::Jump(addr) => state.pc = *addr,
-                Instruction::Match => return Ok(true),
             }
         }
-    }
 
-    Ok(false)
+        if best.is_none() {
+            let has_backref = self.has_backref;
+            self.explored
+                .extend(self.visited.drain().map(|key| key.reduce(has_backref)));
+        }
+        self.visited.clear();
+        Ok(best)
+    }
 }
 
-/// Evaluates whether `input` matches from any start index in `starts`.
+/// Finds the first match, in `starts` order, that begins at one of `starts`.
+///
+/// `search_start` is where `\G` holds.
 pub(crate) fn eval_from_starts(
     inst: &[Instruction],
-    input: &str,
+    chars: &[char],
+    search_start: usize,
     starts: &[usize],
-) -> Result<bool, EvalError> {
-    let chars: Vec<char> = input.chars().collect();
-    let capture_slots = max_capture_index(inst)
-        .checked_add(1)
-        .ok_or(EvalError::PCOverFlow)?;
-
+) -> Result<Option<Slots>, EvalError> {
+    let mut search = Search::new(inst, chars, search_start, MatchKind::LeftmostFirst)?;
     for start in starts {
         if *start > chars.len() {
             continue;
         }
-        if eval_from_start_inner(inst, &chars, *start, capture_slots)? {
-            return Ok(true);
+        let slots = search.run(*start)?;
+        if slots.is_some() {
+            return Ok(slots);
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
 
 /// Evaluates whether `input` matches at any starting position.
+#[allow(dead_code)]
 pub fn eval(inst: &[Instruction], input: &str) -> Result<bool, EvalError> {
     let chars: Vec<char> = input.chars().collect();
-    let capture_slots = max_capture_index(inst)
-        .checked_add(1)
-        .ok_or(EvalError::PCOverFlow)?;
+    Ok(eval_find(inst, &chars, 0, MatchKind::LeftmostFirst)?.is_some())
+}
 
-    for start in 0..=chars.len() {
-        if eval_from_start_inner(inst, &chars, start, capture_slots)? {
-            return Ok(true);
+/// Finds the leftmost match that starts at or after `search_start`.
+///
+/// Returns the matched `(start, end)` span as character indices.
+pub(crate) fn eval_find(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    kind: MatchKind,
+) -> Result<Option<(usize, usize)>, EvalError> {
+    let Some((start, slots)) = eval_leftmost(inst, chars, search_start, kind)? else {
+        return Ok(None);
+    };
+    // Only `\K` lets the POSIX captures report another span.
+    if kind == MatchKind::LeftmostFirst || !has_reset_match_start(inst) {
+        return Ok(slots[0]);
+    }
+    let slots = posix_captures(inst, chars, search_start, start, &slots)?;
+    Ok(slots.and_then(|slots| slots[0]))
+}
+
+/// Finds the leftmost match that starts at or after `search_start`
+/// and returns its slots.
+///
+/// A program anchored at its start is only run where it can match.
+pub(crate) fn eval_captures(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    kind: MatchKind,
+) -> Result<Option<Slots>, EvalError> {
+    match eval_leftmost(inst, chars, search_start, kind)? {
+        Some((start, slots)) if kind == MatchKind::LeftmostLongest => {
+            posix_captures(inst, chars, search_start, start, &slots)
         }
+        found => Ok(found.map(|(_, slots)| slots)),
+    }
+}
+
+/// Finds the leftmost match that starts at or after `search_start`, and
+/// returns where it starts along with its slots.
+///
+/// With `MatchKind::LeftmostLongest`, states are told apart only by what
+/// decides whether they reach `Match`, which keeps the search polynomial:
+/// the slots end where the longest match does, but their captures need
+/// not follow POSIX rules.
+fn eval_leftmost(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    kind: MatchKind,
+) -> Result<Option<(usize, Slots)>, EvalError> {
+    let mut search = Search::new(inst, chars, search_start, kind)?;
+
+    let anchored = start_anchor(inst).map(|anchor| anchored_starts(anchor, chars, search_start));
+    let starts: Box<dyn Iterator<Item = usize>> = match &anchored {
+        Some(starts) => Box::new(starts.iter().copied()),
+        None => Box::new(search_start..=chars.len()),
+    };
+
+    for start in starts {
+        if let Some(slots) = search.run(start)? {
+            return Ok(Some((start, slots)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the POSIX captures of the leftmost-longest match found from
+/// `start` by `eval_leftmost`, whose slots are `found`.
+///
+/// Every state reachable from `start` is explored, told apart by its
+/// captures, without going past the end of the match. With `\K` the
+/// preferred match may end earlier, as an earlier reported start wins
+/// first, so no end limit applies.
+fn posix_captures(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    start: usize,
+    found: &Slots,
+) -> Result<Option<Slots>, EvalError> {
+    let mut search = Search::new(inst, chars, search_start, MatchKind::LeftmostLongest)?;
+    search.posix = true;
+    if !has_reset_match_start(inst) {
+        search.end_limit = found[0].map(|(_, end)| end);
     }
+    search.run(start)
+}
 
-    Ok(false)
+/// Returns whether `inst` contains `\K`.
+fn has_reset_match_start(inst: &[Instruction]) -> bool {
+    inst.iter()
+        .any(|instruction| matches!(instruction, Instruction::ResetMatchStart))
 }
 
 #[cfg(test)]
@@ -295,7 +652,9 @@ mod tests {
     use crate::engine::{
         ast::{CharClass, CharRange, Predicate},
         compiler::compile,
-        evaluator::{EvalError, eval, eval_from_starts},
+        evaluator::{
+            EvalError, MatchKind, Search, eval, eval_captures, eval_find, eval_from_starts,
+        },
         instruction::Instruction,
         parser::parse,
     };
@@ -358,6 +717,108 @@ mod tests {
         assert!(!eval(&inst, "_a").unwrap());
     }
 
+    #[test]
+    fn test_eval_recursion_matches_balanced_parentheses() {
+        let ast = parse("^(\\((?1)*\\))$").unwrap();
+        let inst = compile(&ast).unwrap();
+
+        assert!(eval(&inst, "()").unwrap());
+        assert!(eval(&inst, "(()(()))").unwrap());
+        assert!(!eval(&inst, "(()").unwrap());
+        assert!(!eval(&inst, "())(").unwrap());
+    }
+
+    #[test]
+    fn test_eval_subroutine_call_restores_captures() {
+        let ast = parse("(a|b)(?1)\\1").unwrap();
+        let inst = compile(&ast).unwrap();
+
+        assert!(eval(&inst, "aba").unwrap());
+        assert!(!eval(&inst, "abb").unwrap());
+    }
+
+    #[test]
+    fn test_eval_call_depth_exceeded() {
+        let ast = parse("a(?R)?").unwrap();
+        let inst = compile(&ast).unwrap();
+
+        assert!(eval(&inst, &"a".repeat(100)).unwrap());
+        assert_eq!(
+            eval(&inst, &"a".repeat(2000)),
+            Err(EvalError::CallDepthExceeded)
+        );
+    }
+
+    #[test]
+    fn test_eval_find_reset_match_start() {
+        let ast = parse("ab\\Kcd").unwrap();
+        let inst = compile(&ast).unwrap();
+        let chars: Vec<char> = "xabcd".chars().collect();
+
+        assert_eq!(
+            eval_find(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap(),
+            Some((3, 5))
+        );
+    }
+
+    #[test]
+    fn test_eval_captures_leftmost_longest() {
+        let ast = parse("(a*)(a|b)*").unwrap();
+        let inst = compile(&ast).unwrap();
+        let chars: Vec<char> = "aab".chars().collect();
+
+        let first = eval_captures(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+        assert_eq!(first, Some(vec![Some((0, 3)), Some((0, 2)), Some((2, 3))]));
+
+        let ast = parse("(a|ab)(b*)").unwrap();
+        let inst = compile(&ast).unwrap();
+        let chars: Vec<char> = "abb".chars().collect();
+
+        let first = eval_captures(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+        assert_eq!(first, Some(vec![Some((0, 3)), Some((0, 1)), Some((1, 3))]));
+        let longest = eval_captures(&inst, &chars, 0, MatchKind::LeftmostLongest).unwrap();
+        assert_eq!(
+            longest,
+            Some(vec![Some((0, 3)), Some((0, 2)), Some((2, 3))])
+        );
+    }
+
+    #[test]
+    fn test_eval_find_start_of_search() {
+        let ast = parse("\\Ga").unwrap();
+        let inst = compile(&ast).unwrap();
+        let chars: Vec<char> = "aba".chars().collect();
+
+        assert_eq!(
+            eval_find(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap(),
+            Some((0, 1))
+        );
+        assert_eq!(
+            eval_find(&inst, &chars, 1, MatchKind::LeftmostFirst).unwrap(),
+            None
+        );
+        assert_eq!(
+            eval_find(&inst, &chars, 2, MatchKind::LeftmostFirst).unwrap(),
+            Some((2, 3))
+        );
+    }
+
+    #[test]
+    fn test_eval_crlf_line_predicates() {
+        let inst = vec![
+            Instruction::Assert(Predicate::EndOfCrlfLine),
+            Instruction::Assert(Predicate::StartOfCrlfLine),
+            Instruction::Match,
+        ];
+        // `$^` only holds where one line ends and another begins with no text between.
+        assert!(eval(&inst, "").unwrap());
+        assert!(eval(&inst, "a\n\nb").unwrap());
+        assert!(eval(&inst, "a\r\rb").unwrap());
+        assert!(eval(&inst, "a\r\n\r\nb").unwrap());
+        assert!(!eval(&inst, "a\r\nb").unwrap());
+        assert!(!eval(&inst, "ab").unwrap());
+    }
+
     #[test]
     fn test_eval_invalid_pc() {
         let inst = vec![Instruction::Jump(10)];
@@ -370,7 +831,75 @@ mod tests {
         let ast = parse("abc").unwrap();
         let inst = compile(&ast).unwrap();
 
-        assert!(!eval_from_starts(&inst, "xabc", &[0]).unwrap());
-        assert!(eval_from_starts(&inst, "xabc", &[1]).unwrap());
+        let chars: Vec<char> = "xabc".chars().collect();
+
+        assert_eq!(eval_from_starts(&inst, &chars, 0, &[0]).unwrap(), None);
+        assert_eq!(
+            eval_from_starts(&inst, &chars, 0, &[1]).unwrap(),
+            Some(vec![Some((1, 4))])
+        );
+    }
+
+    #[test]
+    fn test_search_explores_each_state_once() {
+        let inst = compile(&parse("(a|b)*(ab)*c").unwrap()).unwrap();
+        let chars: Vec<char> = "ab".repeat(200).chars().collect();
+
+        let mut search = Search::new(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+        for start in 0..=chars.len() {
+            assert_eq!(search.run(start).unwrap(), None);
+        }
+        assert!(search.explored.len() <= inst.len() * (chars.len() + 1));
+    }
+
+    #[test]
+    fn test_leftmost_longest_search_explores_each_state_once() {
+        let chars: Vec<char> = "a".repeat(50).chars().collect();
+        for pattern in ["(a|aa)*c", "(a*)*(a*)*c"] {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let mut search = Search::new(&inst, &chars, 0, MatchKind::LeftmostLongest).unwrap();
+            for start in 0..=chars.len() {
+                assert_eq!(search.run(start).unwrap(), None, "{pattern}");
+            }
+            assert!(search.explored.len() <= inst.len() * (chars.len() + 1));
+            assert_eq!(
+                eval_find(&inst, &chars, 0, MatchKind::LeftmostLongest).unwrap(),
+                None
+            );
+        }
+
+        let inst = compile(&parse("((a|aa)*)b").unwrap()).unwrap();
+        let chars: Vec<char> = "aaaaab".chars().collect();
+        assert_eq!(
+            eval_captures(&inst, &chars, 0, MatchKind::LeftmostLongest).unwrap(),
+            Some(vec![Some((0, 6)), Some((0, 5)), Some((3, 5))])
+        );
+    }
+
+    #[test]
+    fn test_leftmost_longest_reset_match_start_prefers_earlier_start() {
+        let inst = compile(&parse("a\\Kbc|ab").unwrap()).unwrap();
+        let chars: Vec<char> = "abc".chars().collect();
+        assert_eq!(
+            eval_find(&inst, &chars, 0, MatchKind::LeftmostLongest).unwrap(),
+            Some((0, 2))
+        );
+    }
+
+    #[test]
+    fn test_search_shared_across_starts_keeps_captures() {
+        let chars: Vec<char> = "xaxab ab".chars().collect();
+        for kind in [MatchKind::LeftmostFirst, MatchKind::LeftmostLongest] {
+            let inst = compile(&parse("(a)?x?a\\Kb").unwrap()).unwrap();
+            assert_eq!(
+                eval_captures(&inst, &chars, 0, kind).unwrap(),
+                Some(vec![Some((4, 5)), Some((1, 2))])
+            );
+            let inst = compile(&parse("([ab])\\1?b").unwrap()).unwrap();
+            assert_eq!(
+                eval_captures(&inst, &chars, 0, kind).unwrap(),
+                Some(vec![Some((3, 5)), Some((3, 4))])
+            );
+        }
     }
 }
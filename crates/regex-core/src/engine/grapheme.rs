@@ -0,0 +1,260 @@
+//! Extended grapheme cluster segmentation (UAX #29) used by `\X`.
+
+mod tables;
+
+use std::cmp::Ordering;
+
+use tables::{GRAPHEME_BREAK_TABLE, INDIC_CONJUNCT_BREAK_TABLE};
+
+/// First precomposed Hangul syllable (`가`).
+const HANGUL_SYLLABLE_START: u32 = 0xAC00;
+/// Last precomposed Hangul syllable (`힣`).
+const HANGUL_SYLLABLE_END: u32 = 0xD7A3;
+/// Number of trailing consonants (plus "none") per Hangul `LV` syllable.
+const HANGUL_T_COUNT: u32 = 28;
+
+/// `Grapheme_Cluster_Break` property values, plus `Extended_Pictographic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GraphemeBreak {
+    Other,
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    ExtendedPictographic,
+}
+
+/// `Indic_Conjunct_Break` property values other than `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IndicConjunctBreak {
+    Consonant,
+    Extend,
+    Linker,
+}
+
+/// Position in an Indic conjunct: after `Consonant [Extend Linker]*`, and
+/// `Linked` once that run contains a linker, so that GB9c keeps a
+/// following consonant in the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conjunct {
+    None,
+    Consonant,
+    Linked,
+}
+
+impl Conjunct {
+    /// Returns the state after a character with property `incb`.
+    fn next(self, incb: Option<IndicConjunctBreak>) -> Self {
+        match (self, incb) {
+            (_, Some(IndicConjunctBreak::Consonant)) => Self::Consonant,
+            (Self::Consonant | Self::Linked, Some(IndicConjunctBreak::Linker)) => Self::Linked,
+            (state, Some(IndicConjunctBreak::Extend)) => state,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Looks up the property of `c` in a table of sorted ranges.
+fn lookup<T: Copy>(table: &[(char, char, T)], c: char) -> Option<T> {
+    let found = table.binary_search_by(|&(start, end, _)| {
+        if end < c {
+            Ordering::Less
+        } else if start > c {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    });
+    found.ok().map(|index| table[index].2)
+}
+
+/// Looks up the grapheme break property of `c`.
+fn grapheme_break(c: char) -> GraphemeBreak {
+    let cp = c as u32;
+    if (HANGUL_SYLLABLE_START..=HANGUL_SYLLABLE_END).contains(&cp) {
+        return if (cp - HANGUL_SYLLABLE_START).is_multiple_of(HANGUL_T_COUNT) {
+            GraphemeBreak::Lv
+        } else {
+            GraphemeBreak::Lvt
+        };
+    }
+    lookup(GRAPHEME_BREAK_TABLE, c).unwrap_or(GraphemeBreak::Other)
+}
+
+/// Looks up the `Indic_Conjunct_Break` property of `c`, or `None` for the
+/// value `None`.
+fn indic_conjunct_break(c: char) -> Option<IndicConjunctBreak> {
+    lookup(INDIC_CONJUNCT_BREAK_TABLE, c)
+}
+
+/// Returns whether a cluster boundary lies between `prev` and `next`.
+///
+/// `pictographic_zwj` tells whether `prev` is a ZWJ that follows
+/// `Extended_Pictographic Extend*`; `odd_regional` tells whether an odd
+/// number of regional indicators precedes `next`; `linked_consonant` tells
+/// whether `next` is a consonant joined by a linker to the one before it.
+fn is_break(
+    prev: GraphemeBreak,
+    next: GraphemeBreak,
+    pictographic_zwj: bool,
+    odd_regional: bool,
+    linked_consonant: bool,
+) -> bool {
+    use GraphemeBreak::{
+        Control, Cr, Extend, ExtendedPictographic, L, Lf, Lv, Lvt, Prepend, RegionalIndicator,
+        SpacingMark, T, V, Zwj,
+    };
+
+    match (prev, next) {
+        // GB3
+        (Cr, Lf) => false,
+        // GB4, GB5
+        (Cr | Lf | Control, _) | (_, Cr | Lf | Control) => true,
+        // GB6, GB7, GB8
+        (L, L | V | Lv | Lvt) | (Lv | V, V | T) | (Lvt | T, T) => false,
+        // GB9, GB9a
+        (_, Extend | Zwj | SpacingMark) => false,
+        // GB9b
+        (Prepend, _) => false,
+        // GB9c
+        _ if linked_consonant => false,
+        // GB11
+        (Zwj, ExtendedPictographic) => !pictographic_zwj,
+        // GB12, GB13
+        (RegionalIndicator, RegionalIndicator) => !odd_regional,
+        // GB999
+        _ => true,
+    }
+}
+
+/// Returns the number of characters in the extended grapheme cluster
+/// that starts at `start`, or `None` when `start` is at the end of `chars`.
+pub(crate) fn grapheme_len(chars: &[char], start: usize) -> Option<usize> {
+    let first = *chars.get(start)?;
+    let mut prev = grapheme_break(first);
+    let mut in_pictographic = prev == GraphemeBreak::ExtendedPictographic;
+    let mut pictographic_zwj = false;
+    let mut regional_count = usize::from(prev == GraphemeBreak::RegionalIndicator);
+    let mut conjunct = Conjunct::None.next(indic_conjunct_break(first));
+
+    let mut len = 1;
+    for &c in &chars[start + 1..] {
+        let next = grapheme_break(c);
+        let incb = indic_conjunct_break(c);
+        let linked_consonant =
+            conjunct == Conjunct::Linked && incb == Some(IndicConjunctBreak::Consonant);
+        if is_break(
+            prev,
+            next,
+            pictographic_zwj,
+            regional_count % 2 == 1,
+            linked_consonant,
+        ) {
+            break;
+        }
+
+        conjunct = conjunct.next(incb);
+
+        pictographic_zwj = next == GraphemeBreak::Zwj && in_pictographic;
+        in_pictographic = match next {
+            GraphemeBreak::ExtendedPictographic => true,
+            GraphemeBreak::Extend => in_pictographic,
+            _ => false,
+        };
+        regional_count = if next == GraphemeBreak::RegionalIndicator {
+            regional_count + 1
+        } else {
+            0
+        };
+        prev = next;
+        len += 1;
+    }
+
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GraphemeBreak, grapheme_break, grapheme_len};
+
+    fn first_len(s: &str) -> Option<usize> {
+        let chars: Vec<char> = s.chars().collect();
+        grapheme_len(&chars, 0)
+    }
+
+    #[test]
+    fn test_grapheme_break_lookup() {
+        assert_eq!(grapheme_break('a'), GraphemeBreak::Other);
+        assert_eq!(grapheme_break('\r'), GraphemeBreak::Cr);
+        assert_eq!(grapheme_break('\u{301}'), GraphemeBreak::Extend);
+        assert_eq!(grapheme_break('\u{AC00}'), GraphemeBreak::Lv);
+        assert_eq!(grapheme_break('\u{AC01}'), GraphemeBreak::Lvt);
+        assert_eq!(
+            grapheme_break('\u{1F600}'),
+            GraphemeBreak::ExtendedPictographic
+        );
+    }
+
+    #[test]
+    fn test_grapheme_len_basic() {
+        assert_eq!(first_len(""), None);
+        assert_eq!(first_len("ab"), Some(1));
+        assert_eq!(first_len("\r\n"), Some(2));
+        assert_eq!(first_len("\n\r"), Some(1));
+        assert_eq!(first_len("e\u{301}x"), Some(2));
+    }
+
+    #[test]
+    fn test_grapheme_len_hangul() {
+        assert_eq!(first_len("\u{1100}\u{1161}\u{11A8}"), Some(3));
+        assert_eq!(first_len("\u{AC00}\u{11A8}"), Some(2));
+        assert_eq!(first_len("\u{AC01}\u{1161}"), Some(1));
+    }
+
+    #[test]
+    fn test_grapheme_len_emoji() {
+        // Thumbs up with a skin tone modifier.
+        assert_eq!(first_len("\u{1F44D}\u{1F3FD}"), Some(2));
+        // Family: man, ZWJ, woman, ZWJ, girl.
+        assert_eq!(
+            first_len("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"),
+            Some(5)
+        );
+        // A ZWJ not preceded by a pictograph does not join.
+        assert_eq!(first_len("a\u{200D}\u{1F467}"), Some(2));
+    }
+
+    #[test]
+    fn test_grapheme_len_indic_conjuncts() {
+        // Devanagari KA, VIRAMA, SSA (क्ष).
+        assert_eq!(first_len("\u{915}\u{94D}\u{937}"), Some(3));
+        // KA, NUKTA, VIRAMA, ZWJ, SSA: extenders around the linker.
+        assert_eq!(first_len("\u{915}\u{93C}\u{94D}\u{200D}\u{937}x"), Some(5));
+        // Three consonants joined by two viramas.
+        assert_eq!(first_len("\u{938}\u{94D}\u{924}\u{94D}\u{930}"), Some(5));
+        // Without a linker the consonants are separate clusters.
+        assert_eq!(first_len("\u{915}\u{93F}\u{937}"), Some(2));
+        // A virama after a non-consonant does not link.
+        assert_eq!(first_len("a\u{94D}\u{937}"), Some(2));
+        // Bengali KA, VIRAMA, SSA.
+        assert_eq!(first_len("\u{995}\u{9CD}\u{9B7}"), Some(3));
+    }
+
+    #[test]
+    fn test_grapheme_len_regional_indicators() {
+        // Flags of Japan and the United States.
+        let flags = "\u{1F1EF}\u{1F1F5}\u{1F1FA}\u{1F1F8}";
+        let chars: Vec<char> = flags.chars().collect();
+        assert_eq!(grapheme_len(&chars, 0), Some(2));
+        assert_eq!(grapheme_len(&chars, 2), Some(2));
+    }
+}
@@ -0,0 +1,1190 @@
+//! Grapheme cluster break property tables.
+//!
+//! Generated from Unicode 16.0.0 `GraphemeBreakProperty.txt`, the
+//! `Extended_Pictographic` property of `emoji-data.txt` and the
+//! `Indic_Conjunct_Break` property of `DerivedCoreProperties.txt`. Hangul
+//! `LV`/`LVT` syllables are computed arithmetically and are not listed here.
+
+use crate::engine::grapheme::{GraphemeBreak, IndicConjunctBreak};
+
+/// Sorted, non-overlapping `(start, end, property)` ranges.
+/// Characters not listed have the `Other` property.
+pub(crate) const GRAPHEME_BREAK_TABLE: &[(char, char, GraphemeBreak)] = &[
+    ('\u{0}', '\u{9}', GraphemeBreak::Control),
+    ('\u{a}', '\u{a}', GraphemeBreak::Lf),
+    ('\u{b}', '\u{c}', GraphemeBreak::Control),
+    ('\u{d}', '\u{d}', GraphemeBreak::Cr),
+    ('\u{e}', '\u{1f}', GraphemeBreak::Control),
+    ('\u{7f}', '\u{9f}', GraphemeBreak::Control),
+    ('\u{a9}', '\u{a9}', GraphemeBreak::ExtendedPictographic),
+    ('\u{ad}', '\u{ad}', GraphemeBreak::Control),
+    ('\u{ae}', '\u{ae}', GraphemeBreak::ExtendedPictographic),
+    ('\u{300}', '\u{36f}', GraphemeBreak::Extend),
+    ('\u{483}', '\u{489}', GraphemeBreak::Extend),
+    ('\u{591}', '\u{5bd}', GraphemeBreak::Extend),
+    ('\u{5bf}', '\u{5bf}', GraphemeBreak::Extend),
+    ('\u{5c1}', '\u{5c2}', GraphemeBreak::Extend),
+    ('\u{5c4}', '\u{5c5}', GraphemeBreak::Extend),
+    ('\u{5c7}', '\u{5c7}', GraphemeBreak::Extend),
+    ('\u{600}', '\u{605}', GraphemeBreak::Prepend),
+    ('\u{610}', '\u{61a}', GraphemeBreak::Extend),
+    ('\u{61c}', '\u{61c}', GraphemeBreak::Control),
+    ('\u{64b}', '\u{65f}', GraphemeBreak::Extend),
+    ('\u{670}', '\u{670}', GraphemeBreak::Extend),
+    ('\u{6d6}', '\u{6dc}', GraphemeBreak::Extend),
+    ('\u{6dd}', '\u{6dd}', GraphemeBreak::Prepend),
+    ('\u{6df}', '\u{6e4}', GraphemeBreak::Extend),
+    ('\u{6e7}', '\u{6e8}', GraphemeBreak::Extend),
+    ('\u{6ea}', '\u{6ed}', GraphemeBreak::Extend),
+    ('\u{70f}', '\u{70f}', GraphemeBreak::Prepend),
+    ('\u{711}', '\u{711}', GraphemeBreak::Extend),
+    ('\u{730}', '\u{74a}', GraphemeBreak::Extend),
+    ('\u{7a6}', '\u{7b0}', GraphemeBreak::Extend),
+    ('\u{7eb}', '\u{7f3}', GraphemeBreak::Extend),
+    ('\u{7fd}', '\u{7fd}', GraphemeBreak::Extend),
+    ('\u{816}', '\u{819}', GraphemeBreak::Extend),
+    ('\u{81b}', '\u{823}', GraphemeBreak::Extend),
+    ('\u{825}', '\u{827}', GraphemeBreak::Extend),
+    ('\u{829}', '\u{82d}', GraphemeBreak::Extend),
+    ('\u{859}', '\u{85b}', GraphemeBreak::Extend),
+    ('\u{890}', '\u{891}', GraphemeBreak::Prepend),
+    ('\u{897}', '\u{89f}', GraphemeBreak::Extend),
+    ('\u{8ca}', '\u{8e1}', GraphemeBreak::Extend),
+    ('\u{8e2}', '\u{8e2}', GraphemeBreak::Prepend),
+    ('\u{8e3}', '\u{902}', GraphemeBreak::Extend),
+    ('\u{903}', '\u{903}', GraphemeBreak::SpacingMark),
+    ('\u{93a}', '\u{93a}', GraphemeBreak::Extend),
+    ('\u{93b}', '\u{93b}', GraphemeBreak::SpacingMark),
+    ('\u{93c}', '\u{93c}', GraphemeBreak::Extend),
+    ('\u{93e}', '\u{940}', GraphemeBreak::SpacingMark),
+    ('\u{941}', '\u{948}', GraphemeBreak::Extend),
+    ('\u{949}', '\u{94c}', GraphemeBreak::SpacingMark),
+    ('\u{94d}', '\u{94d}', GraphemeBreak::Extend),
+    ('\u{94e}', '\u{94f}', GraphemeBreak::SpacingMark),
+    ('\u{951}', '\u{957}', GraphemeBreak::Extend),
+    ('\u{962}', '\u{963}', GraphemeBreak::Extend),
+    ('\u{981}', '\u{981}', GraphemeBreak::Extend),
+    ('\u{982}', '\u{983}', GraphemeBreak::SpacingMark),
+    ('\u{9bc}', '\u{9bc}', GraphemeBreak::Extend),
+    ('\u{9be}', '\u{9be}', GraphemeBreak::Extend),
+    ('\u{9bf}', '\u{9c0}', GraphemeBreak::SpacingMark),
+    ('\u{9c1}', '\u{9c4}', GraphemeBreak::Extend),
+    ('\u{9c7}', '\u{9c8}', GraphemeBreak::SpacingMark),
+    ('\u{9cb}', '\u{9cc}', GraphemeBreak::SpacingMark),
+    ('\u{9cd}', '\u{9cd}', GraphemeBreak::Extend),
+    ('\u{9d7}', '\u{9d7}', GraphemeBreak::Extend),
+    ('\u{9e2}', '\u{9e3}', GraphemeBreak::Extend),
+    ('\u{9fe}', '\u{9fe}', GraphemeBreak::Extend),
+    ('\u{a01}', '\u{a02}', GraphemeBreak::Extend),
+    ('\u{a03}', '\u{a03}', GraphemeBreak::SpacingMark),
+    ('\u{a3c}', '\u{a3c}', GraphemeBreak::Extend),
+    ('\u{a3e}', '\u{a40}', GraphemeBreak::SpacingMark),
+    ('\u{a41}', '\u{a42}', GraphemeBreak::Extend),
+    ('\u{a47}', '\u{a48}', GraphemeBreak::Extend),
+    ('\u{a4b}', '\u{a4d}', GraphemeBreak::Extend),
+    ('\u{a51}', '\u{a51}', GraphemeBreak::Extend),
+    ('\u{a70}', '\u{a71}', GraphemeBreak::Extend),
+    ('\u{a75}', '\u{a75}', GraphemeBreak::Extend),
+    ('\u{a81}', '\u{a82}', GraphemeBreak::Extend),
+    ('\u{a83}', '\u{a83}', GraphemeBreak::SpacingMark),
+    ('\u{abc}', '\u{abc}', GraphemeBreak::Extend),
+    ('\u{abe}', '\u{ac0}', GraphemeBreak::SpacingMark),
+    ('\u{ac1}', '\u{ac5}', GraphemeBreak::Extend),
+    ('\u{ac7}', '\u{ac8}', GraphemeBreak::Extend),
+    ('\u{ac9}', '\u{ac9}', GraphemeBreak::SpacingMark),
+    ('\u{acb}', '\u{acc}', GraphemeBreak::SpacingMark),
+    ('\u{acd}', '\u{acd}', GraphemeBreak::Extend),
+    ('\u{ae2}', '\u{ae3}', GraphemeBreak::Extend),
+    ('\u{afa}', '\u{aff}', GraphemeBreak::Extend),
+    ('\u{b01}', '\u{b01}', GraphemeBreak::Extend),
+    ('\u{b02}', '\u{b03}', GraphemeBreak::SpacingMark),
+    ('\u{b3c}', '\u{b3c}', GraphemeBreak::Extend),
+    ('\u{b3e}', '\u{b3f}', GraphemeBreak::Extend),
+    ('\u{b40}', '\u{b40}', GraphemeBreak::SpacingMark),
+    ('\u{b41}', '\u{b44}', GraphemeBreak::Extend),
+    ('\u{b47}', '\u{b48}', GraphemeBreak::SpacingMark),
+    ('\u{b4b}', '\u{b4c}', GraphemeBreak::SpacingMark),
+    ('\u{b4d}', '\u{b4d}', GraphemeBreak::Extend),
+    ('\u{b55}', '\u{b57}', GraphemeBreak::Extend),
+    ('\u{b62}', '\u{b63}', GraphemeBreak::Extend),
+    ('\u{b82}', '\u{b82}', GraphemeBreak::Extend),
+    ('\u{bbe}', '\u{bbe}', GraphemeBreak::Extend),
+    ('\u{bbf}', '\u{bbf}', GraphemeBreak::SpacingMark),
+    ('\u{bc0}', '\u{bc0}', GraphemeBreak::Extend),
+    ('\u{bc1}', '\u{bc2}', GraphemeBreak::SpacingMark),
+    ('\u{bc6}', '\u{bc8}', GraphemeBreak::SpacingMark),
+    ('\u{bca}', '\u{bcc}', GraphemeBreak::SpacingMark),
+    ('\u{bcd}', '\u{bcd}', GraphemeBreak::Extend),
+    ('\u{bd7}', '\u{bd7}', GraphemeBreak::Extend),
+    ('\u{c00}', '\u{c00}', GraphemeBreak::Extend),
+    ('\u{c01}', '\u{c03}', GraphemeBreak::SpacingMark),
+    ('\u{c04}', '\u{c04}', GraphemeBreak::Extend),
+    ('\u{c3c}', '\u{c3c}', GraphemeBreak::Extend),
+    ('\u{c3e}', '\u{c40}', GraphemeBreak::Extend),
+    ('\u{c41}', '\u{c44}', GraphemeBreak::SpacingMark),
+    ('\u{c46}', '\u{c48}', GraphemeBreak::Extend),
+    ('\u{c4a}', '\u{c4d}', GraphemeBreak::Extend),
+    ('\u{c55}', '\u{c56}', GraphemeBreak::Extend),
+    ('\u{c62}', '\u{c63}', GraphemeBreak::Extend),
+    ('\u{c81}', '\u{c81}', GraphemeBreak::Extend),
+    ('\u{c82}', '\u{c83}', GraphemeBreak::SpacingMark),
+    ('\u{cbc}', '\u{cbc}', GraphemeBreak::Extend),
+    ('\u{cbe}', '\u{cbe}', GraphemeBreak::SpacingMark),
+    ('\u{cbf}', '\u{cc0}', GraphemeBreak::Extend),
+    ('\u{cc1}', '\u{cc1}', GraphemeBreak::SpacingMark),
+    ('\u{cc2}', '\u{cc2}', GraphemeBreak::Extend),
+    ('\u{cc3}', '\u{cc4}', GraphemeBreak::SpacingMark),
+    ('\u{cc6}', '\u{cc8}', GraphemeBreak::Extend),
+    ('\u{cca}', '\u{ccd}', GraphemeBreak::Extend),
+    ('\u{cd5}', '\u{cd6}', GraphemeBreak::Extend),
+    ('\u{ce2}', '\u{ce3}', GraphemeBreak::Extend),
+    ('\u{cf3}', '\u{cf3}', GraphemeBreak::SpacingMark),
+    ('\u{d00}', '\u{d01}', GraphemeBreak::Extend),
+    ('\u{d02}', '\u{d03}', GraphemeBreak::SpacingMark),
+    ('\u{d3b}', '\u{d3c}', GraphemeBreak::Extend),
+    ('\u{d3e}', '\u{d3e}', GraphemeBreak::Extend),
+    ('\u{d3f}', '\u{d40}', GraphemeBreak::SpacingMark),
+    ('\u{d41}', '\u{d44}', GraphemeBreak::Extend),
+    ('\u{d46}', '\u{d48}', GraphemeBreak::SpacingMark),
+    ('\u{d4a}', '\u{d4c}', GraphemeBreak::SpacingMark),
+    ('\u{d4d}', '\u{d4d}', GraphemeBreak::Extend),
+    ('\u{d4e}', '\u{d4e}', GraphemeBreak::Prepend),
+    ('\u{d57}', '\u{d57}', GraphemeBreak::Extend),
+    ('\u{d62}', '\u{d63}', GraphemeBreak::Extend),
+    ('\u{d81}', '\u{d81}', GraphemeBreak::Extend),
+    ('\u{d82}', '\u{d83}', GraphemeBreak::SpacingMark),
+    ('\u{dca}', '\u{dca}', GraphemeBreak::Extend),
+    ('\u{dcf}', '\u{dcf}', GraphemeBreak::Extend),
+    ('\u{dd0}', '\u{dd1}', GraphemeBreak::SpacingMark),
+    ('\u{dd2}', '\u{dd4}', GraphemeBreak::Extend),
+    ('\u{dd6}', '\u{dd6}', GraphemeBreak::Extend),
+    ('\u{dd8}', '\u{dde}', GraphemeBreak::SpacingMark),
+    ('\u{ddf}', '\u{ddf}', GraphemeBreak::Extend),
+    ('\u{df2}', '\u{df3}', GraphemeBreak::SpacingMark),
+    ('\u{e31}', '\u{e31}', GraphemeBreak::Extend),
+    ('\u{e33}', '\u{e33}', GraphemeBreak::SpacingMark),
+    ('\u{e34}', '\u{e3a}', GraphemeBreak::Extend),
+    ('\u{e47}', '\u{e4e}', GraphemeBreak::Extend),
+    ('\u{eb1}', '\u{eb1}', GraphemeBreak::Extend),
+    ('\u{eb3}', '\u{eb3}', GraphemeBreak::SpacingMark),
+    ('\u{eb4}', '\u{ebc}', GraphemeBreak::Extend),
+    ('\u{ec8}', '\u{ece}', GraphemeBreak::Extend),
+    ('\u{f18}', '\u{f19}', GraphemeBreak::Extend),
+    ('\u{f35}', '\u{f35}', GraphemeBreak::Extend),
+    ('\u{f37}', '\u{f37}', GraphemeBreak::Extend),
+    ('\u{f39}', '\u{f39}', GraphemeBreak::Extend),
+    ('\u{f3e}', '\u{f3f}', GraphemeBreak::SpacingMark),
+    ('\u{f71}', '\u{f7e}', GraphemeBreak::Extend),
+    ('\u{f7f}', '\u{f7f}', GraphemeBreak::SpacingMark),
+    ('\u{f80}', '\u{f84}', GraphemeBreak::Extend),
+    ('\u{f86}', '\u{f87}', GraphemeBreak::Extend),
+    ('\u{f8d}', '\u{f97}', GraphemeBreak::Extend),
+    ('\u{f99}', '\u{fbc}', GraphemeBreak::Extend),
+    ('\u{fc6}', '\u{fc6}', GraphemeBreak::Extend),
+    ('\u{102d}', '\u{1030}', GraphemeBreak::Extend),
+    ('\u{1031}', '\u{1031}', GraphemeBreak::SpacingMark),
+    ('\u{1032}', '\u{1037}', GraphemeBreak::Extend),
+    ('\u{1039}', '\u{103a}', GraphemeBreak::Extend),
+    ('\u{103b}', '\u{103c}', GraphemeBreak::SpacingMark),
+    ('\u{103d}', '\u{103e}', GraphemeBreak::Extend),
+    ('\u{1056}', '\u{1057}', GraphemeBreak::SpacingMark),
+    ('\u{1058}', '\u{1059}', GraphemeBreak::Extend),
+    ('\u{105e}', '\u{1060}', GraphemeBreak::Extend),
+    ('\u{1071}', '\u{1074}', GraphemeBreak::Extend),
+    ('\u{1082}', '\u{1082}', GraphemeBreak::Extend),
+    ('\u{1084}', '\u{1084}', GraphemeBreak::SpacingMark),
+    ('\u{1085}', '\u{1086}', GraphemeBreak::Extend),
+    ('\u{108d}', '\u{108d}', GraphemeBreak::Extend),
+    ('\u{109d}', '\u{109d}', GraphemeBreak::Extend),
+    ('\u{1100}', '\u{115f}', GraphemeBreak::L),
+    ('\u{1160}', '\u{11a7}', GraphemeBreak::V),
+    ('\u{11a8}', '\u{11ff}', GraphemeBreak::T),
+    ('\u{135d}', '\u{135f}', GraphemeBreak::Extend),
+    ('\u{1712}', '\u{1715}', GraphemeBreak::Extend),
+    ('\u{1732}', '\u{1734}', GraphemeBreak::Extend),
+    ('\u{1752}', '\u{1753}', GraphemeBreak::Extend),
+    ('\u{1772}', '\u{1773}', GraphemeBreak::Extend),
+    ('\u{17b4}', '\u{17b5}', GraphemeBreak::Extend),
+    ('\u{17b6}', '\u{17b6}', GraphemeBreak::SpacingMark),
+    ('\u{17b7}', '\u{17bd}', GraphemeBreak::Extend),
+    ('\u{17be}', '\u{17c5}', GraphemeBreak::SpacingMark),
+    ('\u{17c6}', '\u{17c6}', GraphemeBreak::Extend),
+    ('\u{17c7}', '\u{17c8}', GraphemeBreak::SpacingMark),
+    ('\u{17c9}', '\u{17d3}', GraphemeBreak::Extend),
+    ('\u{17dd}', '\u{17dd}', GraphemeBreak::Extend),
+    ('\u{180b}', '\u{180d}', GraphemeBreak::Extend),
+    ('\u{180e}', '\u{180e}', GraphemeBreak::Control),
+    ('\u{180f}', '\u{180f}', GraphemeBreak::Extend),
+    ('\u{1885}', '\u{1886}', GraphemeBreak::Extend),
+    ('\u{18a9}', '\u{18a9}', GraphemeBreak::Extend),
+    ('\u{1920}', '\u{1922}', GraphemeBreak::Extend),
+    ('\u{1923}', '\u{1926}', GraphemeBreak::SpacingMark),
+    ('\u{1927}', '\u{1928}', GraphemeBreak::Extend),
+    ('\u{1929}', '\u{192b}', GraphemeBreak::SpacingMark),
+    ('\u{1930}', '\u{1931}', GraphemeBreak::SpacingMark),
+    ('\u{1932}', '\u{1932}', GraphemeBreak::Extend),
+    ('\u{1933}', '\u{1938}', GraphemeBreak::SpacingMark),
+    ('\u{1939}', '\u{193b}', GraphemeBreak::Extend),
+    ('\u{1a17}', '\u{1a18}', GraphemeBreak::Extend),
+    ('\u{1a19}', '\u{1a1a}', GraphemeBreak::SpacingMark),
+    ('\u{1a1b}', '\u{1a1b}', GraphemeBreak::Extend),
+    ('\u{1a55}', '\u{1a55}', GraphemeBreak::SpacingMark),
+    ('\u{1a56}', '\u{1a56}', GraphemeBreak::Extend),
+    ('\u{1a57}', '\u{1a57}', GraphemeBreak::SpacingMark),
+    ('\u{1a58}', '\u{1a5e}', GraphemeBreak::Extend),
+    ('\u{1a60}', '\u{1a60}', GraphemeBreak::Extend),
+    ('\u{1a62}', '\u{1a62}', GraphemeBreak::Extend),
+    ('\u{1a65}', '\u{1a6c}', GraphemeBreak::Extend),
+    ('\u{1a6d}', '\u{1a72}', GraphemeBreak::SpacingMark),
+    ('\u{1a73}', '\u{1a7c}', GraphemeBreak::Extend),
+    ('\u{1a7f}', '\u{1a7f}', GraphemeBreak::Extend),
+    ('\u{1ab0}', '\u{1ace}', GraphemeBreak::Extend),
+    ('\u{1b00}', '\u{1b03}', GraphemeBreak::Extend),
+    ('\u{1b04}', '\u{1b04}', GraphemeBreak::SpacingMark),
+    ('\u{1b34}', '\u{1b3d}', GraphemeBreak::Extend),
+    ('\u{1b3e}', '\u{1b41}', GraphemeBreak::SpacingMark),
+    ('\u{1b42}', '\u{1b44}', GraphemeBreak::Extend),
+    ('\u{1b6b}', '\u{1b73}', GraphemeBreak::Extend),
+    ('\u{1b80}', '\u{1b81}', GraphemeBreak::Extend),
+    ('\u{1b82}', '\u{1b82}', GraphemeBreak::SpacingMark),
+    ('\u{1ba1}', '\u{1ba1}', GraphemeBreak::SpacingMark),
+    ('\u{1ba2}', '\u{1ba5}', GraphemeBreak::Extend),
+    ('\u{1ba6}', '\u{1ba7}', GraphemeBreak::SpacingMark),
+    ('\u{1ba8}', '\u{1bad}', GraphemeBreak::Extend),
+    ('\u{1be6}', '\u{1be6}', GraphemeBreak::Extend),
+    ('\u{1be7}', '\u{1be7}', GraphemeBreak::SpacingMark),
+    ('\u{1be8}', '\u{1be9}', GraphemeBreak::Extend),
+    ('\u{1bea}', '\u{1bec}', GraphemeBreak::SpacingMark),
+    ('\u{1bed}', '\u{1bed}', GraphemeBreak::Extend),
+    ('\u{1bee}', '\u{1bee}', GraphemeBreak::SpacingMark),
+    ('\u{1bef}', '\u{1bf3}', GraphemeBreak::Extend),
+    ('\u{1c24}', '\u{1c2b}', GraphemeBreak::SpacingMark),
+    ('\u{1c2c}', '\u{1c33}', GraphemeBreak::Extend),
+    ('\u{1c34}', '\u{1c35}', GraphemeBreak::SpacingMark),
+    ('\u{1c36}', '\u{1c37}', GraphemeBreak::Extend),
+    ('\u{1cd0}', '\u{1cd2}', GraphemeBreak::Extend),
+    ('\u{1cd4}', '\u{1ce0}', GraphemeBreak::Extend),
+    ('\u{1ce1}', '\u{1ce1}', GraphemeBreak::SpacingMark),
+    ('\u{1ce2}', '\u{1ce8}', GraphemeBreak::Extend),
+    ('\u{1ced}', '\u{1ced}', GraphemeBreak::Extend),
+    ('\u{1cf4}', '\u{1cf4}', GraphemeBreak::Extend),
+    ('\u{1cf7}', '\u{1cf7}', GraphemeBreak::SpacingMark),
+    ('\u{1cf8}', '\u{1cf9}', GraphemeBreak::Extend),
+    ('\u{1dc0}', '\u{1dff}', GraphemeBreak::Extend),
+    ('\u{200b}', '\u{200b}', GraphemeBreak::Control),
+    ('\u{200c}', '\u{200c}', GraphemeBreak::Extend),
+    ('\u{200d}', '\u{200d}', GraphemeBreak::Zwj),
+    ('\u{200e}', '\u{200f}', GraphemeBreak::Control),
+    ('\u{2028}', '\u{202e}', GraphemeBreak::Control),
+    ('\u{203c}', '\u{203c}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2049}', '\u{2049}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2060}', '\u{206f}', GraphemeBreak::Control),
+    ('\u{20d0}', '\u{20f0}', GraphemeBreak::Extend),
+    ('\u{2122}', '\u{2122}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2139}', '\u{2139}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2194}', '\u{2199}', GraphemeBreak::ExtendedPictographic),
+    ('\u{21a9}', '\u{21aa}', GraphemeBreak::ExtendedPictographic),
+    ('\u{231a}', '\u{231b}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2328}', '\u{2328}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2388}', '\u{2388}', GraphemeBreak::ExtendedPictographic),
+    ('\u{23cf}', '\u{23cf}', GraphemeBreak::ExtendedPictographic),
+    ('\u{23e9}', '\u{23f3}', GraphemeBreak::ExtendedPictographic),
+    ('\u{23f8}', '\u{23fa}', GraphemeBreak::ExtendedPictographic),
+    ('\u{24c2}', '\u{24c2}', GraphemeBreak::ExtendedPictographic),
+    ('\u{25aa}', '\u{25ab}', GraphemeBreak::ExtendedPictographic),
+    ('\u{25b6}', '\u{25b6}', GraphemeBreak::ExtendedPictographic),
+    ('\u{25c0}', '\u{25c0}', GraphemeBreak::ExtendedPictographic),
+    ('\u{25fb}', '\u{25fe}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2600}', '\u{2605}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2607}', '\u{2612}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2614}', '\u{2685}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2690}', '\u{2705}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2708}', '\u{2712}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2714}', '\u{2714}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2716}', '\u{2716}', GraphemeBreak::ExtendedPictographic),
+    ('\u{271d}', '\u{271d}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2721}', '\u{2721}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2728}', '\u{2728}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2733}', '\u{2734}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2744}', '\u{2744}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2747}', '\u{2747}', GraphemeBreak::ExtendedPictographic),
+    ('\u{274c}', '\u{274c}', GraphemeBreak::ExtendedPictographic),
+    ('\u{274e}', '\u{274e}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2753}', '\u{2755}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2757}', '\u{2757}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2763}', '\u{2767}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2795}', '\u{2797}', GraphemeBreak::ExtendedPictographic),
+    ('\u{27a1}', '\u{27a1}', GraphemeBreak::ExtendedPictographic),
+    ('\u{27b0}', '\u{27b0}', GraphemeBreak::ExtendedPictographic),
+    ('\u{27bf}', '\u{27bf}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2934}', '\u{2935}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2b05}', '\u{2b07}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2b1b}', '\u{2b1c}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2b50}', '\u{2b50}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2b55}', '\u{2b55}', GraphemeBreak::ExtendedPictographic),
+    ('\u{2cef}', '\u{2cf1}', GraphemeBreak::Extend),
+    ('\u{2d7f}', '\u{2d7f}', GraphemeBreak::Extend),
+    ('\u{2de0}', '\u{2dff}', GraphemeBreak::Extend),
+    ('\u{302a}', '\u{302f}', GraphemeBreak::Extend),
+    ('\u{3030}', '\u{3030}', GraphemeBreak::ExtendedPictographic),
+    ('\u{303d}', '\u{303d}', GraphemeBreak::ExtendedPictographic),
+    ('\u{3099}', '\u{309a}', GraphemeBreak::Extend),
+    ('\u{3297}', '\u{3297}', GraphemeBreak::ExtendedPictographic),
+    ('\u{3299}', '\u{3299}', GraphemeBreak::ExtendedPictographic),
+    ('\u{a66f}', '\u{a672}', GraphemeBreak::Extend),
+    ('\u{a674}', '\u{a67d}', GraphemeBreak::Extend),
+    ('\u{a69e}', '\u{a69f}', GraphemeBreak::Extend),
+    ('\u{a6f0}', '\u{a6f1}', GraphemeBreak::Extend),
+    ('\u{a802}', '\u{a802}', GraphemeBreak::Extend),
+    ('\u{a806}', '\u{a806}', GraphemeBreak::Extend),
+    ('\u{a80b}', '\u{a80b}', GraphemeBreak::Extend),
+    ('\u{a823}', '\u{a824}', GraphemeBreak::SpacingMark),
+    ('\u{a825}', '\u{a826}', GraphemeBreak::Extend),
+    ('\u{a827}', '\u{a827}', GraphemeBreak::SpacingMark),
+    ('\u{a82c}', '\u{a82c}', GraphemeBreak::Extend),
+    ('\u{a880}', '\u{a881}', GraphemeBreak::SpacingMark),
+    ('\u{a8b4}', '\u{a8c3}', GraphemeBreak::SpacingMark),
+    ('\u{a8c4}', '\u{a8c5}', GraphemeBreak::Extend),
+    ('\u{a8e0}', '\u{a8f1}', GraphemeBreak::Extend),
+    ('\u{a8ff}', '\u{a8ff}', GraphemeBreak::Extend),
+    ('\u{a926}', '\u{a92d}', GraphemeBreak::Extend),
+    ('\u{a947}', '\u{a951}', GraphemeBreak::Extend),
+    ('\u{a952}', '\u{a952}', GraphemeBreak::SpacingMark),
+    ('\u{a953}', '\u{a953}', GraphemeBreak::Extend),
+    ('\u{a960}', '\u{a97c}', GraphemeBreak::L),
+    ('\u{a980}', '\u{a982}', GraphemeBreak::Extend),
+    ('\u{a983}', '\u{a983}', GraphemeBreak::SpacingMark),
+    ('\u{a9b3}', '\u{a9b3}', GraphemeBreak::Extend),
+    ('\u{a9b4}', '\u{a9b5}', GraphemeBreak::SpacingMark),
+    ('\u{a9b6}', '\u{a9b9}', GraphemeBreak::Extend),
+    ('\u{a9ba}', '\u{a9bb}', GraphemeBreak::SpacingMark),
+    ('\u{a9bc}', '\u{a9bd}', GraphemeBreak::Extend),
+    ('\u{a9be}', '\u{a9bf}', GraphemeBreak::SpacingMark),
+    ('\u{a9c0}', '\u{a9c0}', GraphemeBreak::Extend),
+    ('\u{a9e5}', '\u{a9e5}', GraphemeBreak::Extend),
+    ('\u{aa29}', '\u{aa2e}', GraphemeBreak::Extend),
+    ('\u{aa2f}', '\u{aa30}', GraphemeBreak::SpacingMark),
+    ('\u{aa31}', '\u{aa32}', GraphemeBreak::Extend),
+    ('\u{aa33}', '\u{aa34}', GraphemeBreak::SpacingMark),
+    ('\u{aa35}', '\u{aa36}', GraphemeBreak::Extend),
+    ('\u{aa43}', '\u{aa43}', GraphemeBreak::Extend),
+    ('\u{aa4c}', '\u{aa4c}', GraphemeBreak::Extend),
+    ('\u{aa4d}', '\u{aa4d}', GraphemeBreak::SpacingMark),
+    ('\u{aa7c}', '\u{aa7c}', GraphemeBreak::Extend),
+    ('\u{aab0}', '\u{aab0}', GraphemeBreak::Extend),
+    ('\u{aab2}', '\u{aab4}', GraphemeBreak::Extend),
+    ('\u{aab7}', '\u{aab8}', GraphemeBreak::Extend),
+    ('\u{aabe}', '\u{aabf}', GraphemeBreak::Extend),
+    ('\u{aac1}', '\u{aac1}', GraphemeBreak::Extend),
+    ('\u{aaeb}', '\u{aaeb}', GraphemeBreak::SpacingMark),
+    ('\u{aaec}', '\u{aaed}', GraphemeBreak::Extend),
+    ('\u{aaee}', '\u{aaef}', GraphemeBreak::SpacingMark),
+    ('\u{aaf5}', '\u{aaf5}', GraphemeBreak::SpacingMark),
+    ('\u{aaf6}', '\u{aaf6}', GraphemeBreak::Extend),
+    ('\u{abe3}', '\u{abe4}', GraphemeBreak::SpacingMark),
+    ('\u{abe5}', '\u{abe5}', GraphemeBreak::Extend),
+    ('\u{abe6}', '\u{abe7}', GraphemeBreak::SpacingMark),
+    ('\u{abe8}', '\u{abe8}', GraphemeBreak::Extend),
+    ('\u{abe9}', '\u{abea}', GraphemeBreak::SpacingMark),
+    ('\u{abec}', '\u{abec}', GraphemeBreak::SpacingMark),
+    ('\u{abed}', '\u{abed}', GraphemeBreak::Extend),
+    ('\u{d7b0}', '\u{d7c6}', GraphemeBreak::V),
+    ('\u{d7cb}', '\u{d7fb}', GraphemeBreak::T),
+    ('\u{fb1e}', '\u{fb1e}', GraphemeBreak::Extend),
+    ('\u{fe00}', '\u{fe0f}', GraphemeBreak::Extend),
+    ('\u{fe20}', '\u{fe2f}', GraphemeBreak::Extend),
+    ('\u{feff}', '\u{feff}', GraphemeBreak::Control),
+    ('\u{ff9e}', '\u{ff9f}', GraphemeBreak::Extend),
+    ('\u{fff0}', '\u{fffb}', GraphemeBreak::Control),
+    ('\u{101fd}', '\u{101fd}', GraphemeBreak::Extend),
+    ('\u{102e0}', '\u{102e0}', GraphemeBreak::Extend),
+    ('\u{10376}', '\u{1037a}', GraphemeBreak::Extend),
+    ('\u{10a01}', '\u{10a03}', GraphemeBreak::Extend),
+    ('\u{10a05}', '\u{10a06}', GraphemeBreak::Extend),
+    ('\u{10a0c}', '\u{10a0f}', GraphemeBreak::Extend),
+    ('\u{10a38}', '\u{10a3a}', GraphemeBreak::Extend),
+    ('\u{10a3f}', '\u{10a3f}', GraphemeBreak::Extend),
+    ('\u{10ae5}', '\u{10ae6}', GraphemeBreak::Extend),
+    ('\u{10d24}', '\u{10d27}', GraphemeBreak::Extend),
+    ('\u{10d69}', '\u{10d6d}', GraphemeBreak::Extend),
+    ('\u{10eab}', '\u{10eac}', GraphemeBreak::Extend),
+    ('\u{10efc}', '\u{10eff}', GraphemeBreak::Extend),
+    ('\u{10f46}', '\u{10f50}', GraphemeBreak::Extend),
+    ('\u{10f82}', '\u{10f85}', GraphemeBreak::Extend),
+    ('\u{11000}', '\u{11000}', GraphemeBreak::SpacingMark),
+    ('\u{11001}', '\u{11001}', GraphemeBreak::Extend),
+    ('\u{11002}', '\u{11002}', GraphemeBreak::SpacingMark),
+    ('\u{11038}', '\u{11046}', GraphemeBreak::Extend),
+    ('\u{11070}', '\u{11070}', GraphemeBreak::Extend),
+    ('\u{11073}', '\u{11074}', GraphemeBreak::Extend),
+    ('\u{1107f}', '\u{11081}', GraphemeBreak::Extend),
+    ('\u{11082}', '\u{11082}', GraphemeBreak::SpacingMark),
+    ('\u{110b0}', '\u{110b2}', GraphemeBreak::SpacingMark),
+    ('\u{110b3}', '\u{110b6}', GraphemeBreak::Extend),
+    ('\u{110b7}', '\u{110b8}', GraphemeBreak::SpacingMark),
+    ('\u{110b9}', '\u{110ba}', GraphemeBreak::Extend),
+    ('\u{110bd}', '\u{110bd}', GraphemeBreak::Prepend),
+    ('\u{110c2}', '\u{110c2}', GraphemeBreak::Extend),
+    ('\u{110cd}', '\u{110cd}', GraphemeBreak::Prepend),
+    ('\u{11100}', '\u{11102}', GraphemeBreak::Extend),
+    ('\u{11127}', '\u{1112b}', GraphemeBreak::Extend),
+    ('\u{1112c}', '\u{1112c}', GraphemeBreak::SpacingMark),
+    ('\u{1112d}', '\u{11134}', GraphemeBreak::Extend),
+    ('\u{11145}', '\u{11146}', GraphemeBreak::SpacingMark),
+    ('\u{11173}', '\u{11173}', GraphemeBreak::Extend),
+    ('\u{11180}', '\u{11181}', GraphemeBreak::Extend),
+    ('\u{11182}', '\u{11182}', GraphemeBreak::SpacingMark),
+    ('\u{111b3}', '\u{111b5}', GraphemeBreak::SpacingMark),
+    ('\u{111b6}', '\u{111be}', GraphemeBreak::Extend),
+    ('\u{111bf}', '\u{111bf}', GraphemeBreak::SpacingMark),
+    ('\u{111c0}', '\u{111c0}', GraphemeBreak::Extend),
+    ('\u{111c2}', '\u{111c3}', GraphemeBreak::Prepend),
+    ('\u{111c9}', '\u{111cc}', GraphemeBreak::Extend),
+    ('\u{111ce}', '\u{111ce}', GraphemeBreak::SpacingMark),
+    ('\u{111cf}', '\u{111cf}', GraphemeBreak::Extend),
+    ('\u{1122c}', '\u{1122e}', GraphemeBreak::SpacingMark),
+    ('\u{1122f}', '\u{11231}', GraphemeBreak::Extend),
+    ('\u{11232}', '\u{11233}', GraphemeBreak::SpacingMark),
+    ('\u{11234}', '\u{11237}', GraphemeBreak::Extend),
+    ('\u{1123e}', '\u{1123e}', GraphemeBreak::Extend),
+    ('\u{11241}', '\u{11241}', GraphemeBreak::Extend),
+    ('\u{112df}', '\u{112df}', GraphemeBreak::Extend),
+    ('\u{112e0}', '\u{112e2}', GraphemeBreak::SpacingMark),
+    ('\u{112e3}', '\u{112ea}', GraphemeBreak::Extend),
+    ('\u{11300}', '\u{11301}', GraphemeBreak::Extend),
+    ('\u{11302}', '\u{11303}', GraphemeBreak::SpacingMark),
+    ('\u{1133b}', '\u{1133c}', GraphemeBreak::Extend),
+    ('\u{1133e}', '\u{1133e}', GraphemeBreak::Extend),
+    ('\u{1133f}', '\u{1133f}', GraphemeBreak::SpacingMark),
+    ('\u{11340}', '\u{11340}', GraphemeBreak::Extend),
+    ('\u{11341}', '\u{11344}', GraphemeBreak::SpacingMark),
+    ('\u{11347}', '\u{11348}', GraphemeBreak::SpacingMark),
+    ('\u{1134b}', '\u{1134c}', GraphemeBreak::SpacingMark),
+    ('\u{1134d}', '\u{1134d}', GraphemeBreak::Extend),
+    ('\u{11357}', '\u{11357}', GraphemeBreak::Extend),
+    ('\u{11362}', '\u{11363}', GraphemeBreak::SpacingMark),
+    ('\u{11366}', '\u{1136c}', GraphemeBreak::Extend),
+    ('\u{11370}', '\u{11374}', GraphemeBreak::Extend),
+    ('\u{113b8}', '\u{113b8}', GraphemeBreak::Extend),
+    ('\u{113b9}', '\u{113ba}', GraphemeBreak::SpacingMark),
+    ('\u{113bb}', '\u{113c0}', GraphemeBreak::Extend),
+    ('\u{113c2}', '\u{113c2}', GraphemeBreak::Extend),
+    ('\u{113c5}', '\u{113c5}', GraphemeBreak::Extend),
+    ('\u{113c7}', '\u{113c9}', GraphemeBreak::Extend),
+    ('\u{113ca}', '\u{113ca}', GraphemeBreak::SpacingMark),
+    ('\u{113cc}', '\u{113cd}', GraphemeBreak::SpacingMark),
+    ('\u{113ce}', '\u{113d0}', GraphemeBreak::Extend),
+    ('\u{113d1}', '\u{113d1}', GraphemeBreak::Prepend),
+    ('\u{113d2}', '\u{113d2}', GraphemeBreak::Extend),
+    ('\u{113e1}', '\u{113e2}', GraphemeBreak::Extend),
+    ('\u{11435}', '\u{11437}', GraphemeBreak::SpacingMark),
+    ('\u{11438}', '\u{1143f}', GraphemeBreak::Extend),
+    ('\u{11440}', '\u{11441}', GraphemeBreak::SpacingMark),
+    ('\u{11442}', '\u{11444}', GraphemeBreak::Extend),
+    ('\u{11445}', '\u{11445}', GraphemeBreak::SpacingMark),
+    ('\u{11446}', '\u{11446}', GraphemeBreak::Extend),
+    ('\u{1145e}', '\u{1145e}', GraphemeBreak::Extend),
+    ('\u{114b0}', '\u{114b0}', GraphemeBreak::Extend),
+    ('\u{114b1}', '\u{114b2}', GraphemeBreak::SpacingMark),
+    ('\u{114b3}', '\u{114b8}', GraphemeBreak::Extend),
+    ('\u{114b9}', '\u{114b9}', GraphemeBreak::SpacingMark),
+    ('\u{114ba}', '\u{114ba}', GraphemeBreak::Extend),
+    ('\u{114bb}', '\u{114bc}', GraphemeBreak::SpacingMark),
+    ('\u{114bd}', '\u{114bd}', GraphemeBreak::Extend),
+    ('\u{114be}', '\u{114be}', GraphemeBreak::SpacingMark),
+    ('\u{114bf}', '\u{114c0}', GraphemeBreak::Extend),
+    ('\u{114c1}', '\u{114c1}', GraphemeBreak::SpacingMark),
+    ('\u{114c2}', '\u{114c3}', GraphemeBreak::Extend),
+    ('\u{115af}', '\u{115af}', GraphemeBreak::Extend),
+    ('\u{115b0}', '\u{115b1}', GraphemeBreak::SpacingMark),
+    ('\u{115b2}', '\u{115b5}', GraphemeBreak::Extend),
+    ('\u{115b8}', '\u{115bb}', GraphemeBreak::SpacingMark),
+    ('\u{115bc}', '\u{115bd}', GraphemeBreak::Extend),
+    ('\u{115be}', '\u{115be}', GraphemeBreak::SpacingMark),
+    ('\u{115bf}', '\u{115c0}', GraphemeBreak::Extend),
+    ('\u{115dc}', '\u{115dd}', GraphemeBreak::Extend),
+    ('\u{11630}', '\u{11632}', GraphemeBreak::SpacingMark),
+    ('\u{11633}', '\u{1163a}', GraphemeBreak::Extend),
+    ('\u{1163b}', '\u{1163c}', GraphemeBreak::SpacingMark),
+    ('\u{1163d}', '\u{1163d}', GraphemeBreak::Extend),
+    ('\u{1163e}', '\u{1163e}', GraphemeBreak::SpacingMark),
+    ('\u{1163f}', '\u{11640}', GraphemeBreak::Extend),
+    ('\u{116ab}', '\u{116ab}', GraphemeBreak::Extend),
+    ('\u{116ac}', '\u{116ac}', GraphemeBreak::SpacingMark),
+    ('\u{116ad}', '\u{116ad}', GraphemeBreak::Extend),
+    ('\u{116ae}', '\u{116af}', GraphemeBreak::SpacingMark),
+    ('\u{116b0}', '\u{116b7}', GraphemeBreak::Extend),
+    ('\u{1171d}', '\u{1171d}', GraphemeBreak::Extend),
+    ('\u{1171e}', '\u{1171e}', GraphemeBreak::SpacingMark),
+    ('\u{1171f}', '\u{1171f}', GraphemeBreak::Extend),
+    ('\u{11722}', '\u{11725}', GraphemeBreak::Extend),
+    ('\u{11726}', '\u{11726}', GraphemeBreak::SpacingMark),
+    ('\u{11727}', '\u{1172b}', GraphemeBreak::Extend),
+    ('\u{1182c}', '\u{1182e}', GraphemeBreak::SpacingMark),
+    ('\u{1182f}', '\u{11837}', GraphemeBreak::Extend),
+    ('\u{11838}', '\u{11838}', GraphemeBreak::SpacingMark),
+    ('\u{11839}', '\u{1183a}', GraphemeBreak::Extend),
+    ('\u{11930}', '\u{11930}', GraphemeBreak::Extend),
+    ('\u{11931}', '\u{11935}', GraphemeBreak::SpacingMark),
+    ('\u{11937}', '\u{11938}', GraphemeBreak::SpacingMark),
+    ('\u{1193b}', '\u{1193e}', GraphemeBreak::Extend),
+    ('\u{1193f}', '\u{1193f}', GraphemeBreak::Prepend),
+    ('\u{11940}', '\u{11940}', GraphemeBreak::SpacingMark),
+    ('\u{11941}', '\u{11941}', GraphemeBreak::Prepend),
+    ('\u{11942}', '\u{11942}', GraphemeBreak::SpacingMark),
+    ('\u{11943}', '\u{11943}', GraphemeBreak::Extend),
+    ('\u{119d1}', '\u{119d3}', GraphemeBreak::SpacingMark),
+    ('\u{119d4}', '\u{119d7}', GraphemeBreak::Extend),
+    ('\u{119da}', '\u{119db}', GraphemeBreak::Extend),
+    ('\u{119dc}', '\u{119df}', GraphemeBreak::SpacingMark),
+    ('\u{119e0}', '\u{119e0}', GraphemeBreak::Extend),
+    ('\u{119e4}', '\u{119e4}', GraphemeBreak::SpacingMark),
+    ('\u{11a01}', '\u{11a0a}', GraphemeBreak::Extend),
+    ('\u{11a33}', '\u{11a38}', GraphemeBreak::Extend),
+    ('\u{11a39}', '\u{11a39}', GraphemeBreak::SpacingMark),
+    ('\u{11a3a}', '\u{11a3a}', GraphemeBreak::Prepend),
+    ('\u{11a3b}', '\u{11a3e}', GraphemeBreak::Extend),
+    ('\u{11a47}', '\u{11a47}', GraphemeBreak::Extend),
+    ('\u{11a51}', '\u{11a56}', GraphemeBreak::Extend),
+    ('\u{11a57}', '\u{11a58}', GraphemeBreak::SpacingMark),
+    ('\u{11a59}', '\u{11a5b}', GraphemeBreak::Extend),
+    ('\u{11a84}', '\u{11a89}', GraphemeBreak::Prepend),
+    ('\u{11a8a}', '\u{11a96}', GraphemeBreak::Extend),
+    ('\u{11a97}', '\u{11a97}', GraphemeBreak::SpacingMark),
+    ('\u{11a98}', '\u{11a99}', GraphemeBreak::Extend),
+    ('\u{11c2f}', '\u{11c2f}', GraphemeBreak::SpacingMark),
+    ('\u{11c30}', '\u{11c36}', GraphemeBreak::Extend),
+    ('\u{11c38}', '\u{11c3d}', GraphemeBreak::Extend),
+    ('\u{11c3e}', '\u{11c3e}', GraphemeBreak::SpacingMark),
+    ('\u{11c3f}', '\u{11c3f}', GraphemeBreak::Extend),
+    ('\u{11c92}', '\u{11ca7}', GraphemeBreak::Extend),
+    ('\u{11ca9}', '\u{11ca9}', GraphemeBreak::SpacingMark),
+    ('\u{11caa}', '\u{11cb0}', GraphemeBreak::Extend),
+    ('\u{11cb1}', '\u{11cb1}', GraphemeBreak::SpacingMark),
+    ('\u{11cb2}', '\u{11cb3}', GraphemeBreak::Extend),
+    ('\u{11cb4}', '\u{11cb4}', GraphemeBreak::SpacingMark),
+    ('\u{11cb5}', '\u{11cb6}', GraphemeBreak::Extend),
+    ('\u{11d31}', '\u{11d36}', GraphemeBreak::Extend),
+    ('\u{11d3a}', '\u{11d3a}', GraphemeBreak::Extend),
+    ('\u{11d3c}', '\u{11d3d}', GraphemeBreak::Extend),
+    ('\u{11d3f}', '\u{11d45}', GraphemeBreak::Extend),
+    ('\u{11d46}', '\u{11d46}', GraphemeBreak::Prepend),
+    ('\u{11d47}', '\u{11d47}', GraphemeBreak::Extend),
+    ('\u{11d8a}', '\u{11d8e}', GraphemeBreak::SpacingMark),
+    ('\u{11d90}', '\u{11d91}', GraphemeBreak::Extend),
+    ('\u{11d93}', '\u{11d94}', GraphemeBreak::SpacingMark),
+    ('\u{11d95}', '\u{11d95}', GraphemeBreak::Extend),
+    ('\u{11d96}', '\u{11d96}', GraphemeBreak::SpacingMark),
+    ('\u{11d97}', '\u{11d97}', GraphemeBreak::Extend),
+    ('\u{11ef3}', '\u{11ef4}', GraphemeBreak::Extend),
+    ('\u{11ef5}', '\u{11ef6}', GraphemeBreak::SpacingMark),
+    ('\u{11f00}', '\u{11f01}', GraphemeBreak::Extend),
+    ('\u{11f02}', '\u{11f02}', GraphemeBreak::Prepend),
+    ('\u{11f03}', '\u{11f03}', GraphemeBreak::SpacingMark),
+    ('\u{11f34}', '\u{11f35}', GraphemeBreak::SpacingMark),
+    ('\u{11f36}', '\u{11f3a}', GraphemeBreak::Extend),
+    ('\u{11f3e}', '\u{11f3f}', GraphemeBreak::SpacingMark),
+    ('\u{11f40}', '\u{11f42}', GraphemeBreak::Extend),
+    ('\u{11f5a}', '\u{11f5a}', GraphemeBreak::Extend),
+    ('\u{13430}', '\u{1343f}', GraphemeBreak::Control),
+    ('\u{13440}', '\u{13440}', GraphemeBreak::Extend),
+    ('\u{13447}', '\u{13455}', GraphemeBreak::Extend),
+    ('\u{1611e}', '\u{16129}', GraphemeBreak::Extend),
+    ('\u{1612a}', '\u{1612c}', GraphemeBreak::SpacingMark),
+    ('\u{1612d}', '\u{1612f}', GraphemeBreak::Extend),
+    ('\u{16af0}', '\u{16af4}', GraphemeBreak::Extend),
+    ('\u{16b30}', '\u{16b36}', GraphemeBreak::Extend),
+    ('\u{16d63}', '\u{16d63}', GraphemeBreak::V),
+    ('\u{16d67}', '\u{16d6a}', GraphemeBreak::V),
+    ('\u{16f4f}', '\u{16f4f}', GraphemeBreak::Extend),
+    ('\u{16f51}', '\u{16f87}', GraphemeBreak::SpacingMark),
+    ('\u{16f8f}', '\u{16f92}', GraphemeBreak::Extend),
+    ('\u{16fe4}', '\u{16fe4}', GraphemeBreak::Extend),
+    ('\u{16ff0}', '\u{16ff1}', GraphemeBreak::Extend),
+    ('\u{1bc9d}', '\u{1bc9e}', GraphemeBreak::Extend),
+    ('\u{1bca0}', '\u{1bca3}', GraphemeBreak::Control),
+    ('\u{1cf00}', '\u{1cf2d}', GraphemeBreak::Extend),
+    ('\u{1cf30}', '\u{1cf46}', GraphemeBreak::Extend),
+    ('\u{1d165}', '\u{1d169}', GraphemeBreak::Extend),
+    ('\u{1d16d}', '\u{1d172}', GraphemeBreak::Extend),
+    ('\u{1d173}', '\u{1d17a}', GraphemeBreak::Control),
+    ('\u{1d17b}', '\u{1d182}', GraphemeBreak::Extend),
+    ('\u{1d185}', '\u{1d18b}', GraphemeBreak::Extend),
+    ('\u{1d1aa}', '\u{1d1ad}', GraphemeBreak::Extend),
+    ('\u{1d242}', '\u{1d244}', GraphemeBreak::Extend),
+    ('\u{1da00}', '\u{1da36}', GraphemeBreak::Extend),
+    ('\u{1da3b}', '\u{1da6c}', GraphemeBreak::Extend),
+    ('\u{1da75}', '\u{1da75}', GraphemeBreak::Extend),
+    ('\u{1da84}', '\u{1da84}', GraphemeBreak::Extend),
+    ('\u{1da9b}', '\u{1da9f}', GraphemeBreak::Extend),
+    ('\u{1daa1}', '\u{1daaf}', GraphemeBreak::Extend),
+    ('\u{1e000}', '\u{1e006}', GraphemeBreak::Extend),
+    ('\u{1e008}', '\u{1e018}', GraphemeBreak::Extend),
+    ('\u{1e01b}', '\u{1e021}', GraphemeBreak::Extend),
+    ('\u{1e023}', '\u{1e024}', GraphemeBreak::Extend),
+    ('\u{1e026}', '\u{1e02a}', GraphemeBreak::Extend),
+    ('\u{1e08f}', '\u{1e08f}', GraphemeBreak::Extend),
+    ('\u{1e130}', '\u{1e136}', GraphemeBreak::Extend),
+    ('\u{1e2ae}', '\u{1e2ae}', GraphemeBreak::Extend),
+    ('\u{1e2ec}', '\u{1e2ef}', GraphemeBreak::Extend),
+    ('\u{1e4ec}', '\u{1e4ef}', GraphemeBreak::Extend),
+    ('\u{1e5ee}', '\u{1e5ef}', GraphemeBreak::Extend),
+    ('\u{1e8d0}', '\u{1e8d6}', GraphemeBreak::Extend),
+    ('\u{1e944}', '\u{1e94a}', GraphemeBreak::Extend),
+    (
+        '\u{1f000}',
+        '\u{1f0ff}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f10d}',
+        '\u{1f10f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f12f}',
+        '\u{1f12f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f16c}',
+        '\u{1f171}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f17e}',
+        '\u{1f17f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f18e}',
+        '\u{1f18e}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f191}',
+        '\u{1f19a}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f1ad}',
+        '\u{1f1e5}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    ('\u{1f1e6}', '\u{1f1ff}', GraphemeBreak::RegionalIndicator),
+    (
+        '\u{1f201}',
+        '\u{1f20f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f21a}',
+        '\u{1f21a}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f22f}',
+        '\u{1f22f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f232}',
+        '\u{1f23a}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f23c}',
+        '\u{1f23f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f249}',
+        '\u{1f3fa}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    ('\u{1f3fb}', '\u{1f3ff}', GraphemeBreak::Extend),
+    (
+        '\u{1f400}',
+        '\u{1f53d}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f546}',
+        '\u{1f64f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f680}',
+        '\u{1f6ff}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f774}',
+        '\u{1f77f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f7d5}',
+        '\u{1f7ff}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f80c}',
+        '\u{1f80f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f848}',
+        '\u{1f84f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f85a}',
+        '\u{1f85f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f888}',
+        '\u{1f88f}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f8ae}',
+        '\u{1f8ff}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f90c}',
+        '\u{1f93a}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f93c}',
+        '\u{1f945}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1f947}',
+        '\u{1faff}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    (
+        '\u{1fc00}',
+        '\u{1fffd}',
+        GraphemeBreak::ExtendedPictographic,
+    ),
+    ('\u{e0000}', '\u{e001f}', GraphemeBreak::Control),
+    ('\u{e0020}', '\u{e007f}', GraphemeBreak::Extend),
+    ('\u{e0080}', '\u{e00ff}', GraphemeBreak::Control),
+    ('\u{e0100}', '\u{e01ef}', GraphemeBreak::Extend),
+    ('\u{e01f0}', '\u{e0fff}', GraphemeBreak::Control),
+];
+
+/// Sorted, non-overlapping `(start, end, property)` ranges of the
+/// `Indic_Conjunct_Break` property. Characters not listed have the value
+/// `None`.
+pub(crate) const INDIC_CONJUNCT_BREAK_TABLE: &[(char, char, IndicConjunctBreak)] = &[
+    ('\u{300}', '\u{36f}', IndicConjunctBreak::Extend),
+    ('\u{483}', '\u{489}', IndicConjunctBreak::Extend),
+    ('\u{591}', '\u{5bd}', IndicConjunctBreak::Extend),
+    ('\u{5bf}', '\u{5bf}', IndicConjunctBreak::Extend),
+    ('\u{5c1}', '\u{5c2}', IndicConjunctBreak::Extend),
+    ('\u{5c4}', '\u{5c5}', IndicConjunctBreak::Extend),
+    ('\u{5c7}', '\u{5c7}', IndicConjunctBreak::Extend),
+    ('\u{610}', '\u{61a}', IndicConjunctBreak::Extend),
+    ('\u{64b}', '\u{65f}', IndicConjunctBreak::Extend),
+    ('\u{670}', '\u{670}', IndicConjunctBreak::Extend),
+    ('\u{6d6}', '\u{6dc}', IndicConjunctBreak::Extend),
+    ('\u{6df}', '\u{6e4}', IndicConjunctBreak::Extend),
+    ('\u{6e7}', '\u{6e8}', IndicConjunctBreak::Extend),
+    ('\u{6ea}', '\u{6ed}', IndicConjunctBreak::Extend),
+    ('\u{711}', '\u{711}', IndicConjunctBreak::Extend),
+    ('\u{730}', '\u{74a}', IndicConjunctBreak::Extend),
+    ('\u{7a6}', '\u{7b0}', IndicConjunctBreak::Extend),
+    ('\u{7eb}', '\u{7f3}', IndicConjunctBreak::Extend),
+    ('\u{7fd}', '\u{7fd}', IndicConjunctBreak::Extend),
+    ('\u{816}', '\u{819}', IndicConjunctBreak::Extend),
+    ('\u{81b}', '\u{823}', IndicConjunctBreak::Extend),
+    ('\u{825}', '\u{827}', IndicConjunctBreak::Extend),
+    ('\u{829}', '\u{82d}', IndicConjunctBreak::Extend),
+    ('\u{859}', '\u{85b}', IndicConjunctBreak::Extend),
+    ('\u{897}', '\u{89f}', IndicConjunctBreak::Extend),
+    ('\u{8ca}', '\u{8e1}', IndicConjunctBreak::Extend),
+    ('\u{8e3}', '\u{902}', IndicConjunctBreak::Extend),
+    ('\u{915}', '\u{939}', IndicConjunctBreak::Consonant),
+    ('\u{93a}', '\u{93a}', IndicConjunctBreak::Extend),
+    ('\u{93c}', '\u{93c}', IndicConjunctBreak::Extend),
+    ('\u{941}', '\u{948}', IndicConjunctBreak::Extend),
+    ('\u{94d}', '\u{94d}', IndicConjunctBreak::Linker),
+    ('\u{951}', '\u{957}', IndicConjunctBreak::Extend),
+    ('\u{958}', '\u{95f}', IndicConjunctBreak::Consonant),
+    ('\u{962}', '\u{963}', IndicConjunctBreak::Extend),
+    ('\u{978}', '\u{97f}', IndicConjunctBreak::Consonant),
+    ('\u{981}', '\u{981}', IndicConjunctBreak::Extend),
+    ('\u{995}', '\u{9a8}', IndicConjunctBreak::Consonant),
+    ('\u{9aa}', '\u{9b0}', IndicConjunctBreak::Consonant),
+    ('\u{9b2}', '\u{9b2}', IndicConjunctBreak::Consonant),
+    ('\u{9b6}', '\u{9b9}', IndicConjunctBreak::Consonant),
+    ('\u{9bc}', '\u{9bc}', IndicConjunctBreak::Extend),
+    ('\u{9be}', '\u{9be}', IndicConjunctBreak::Extend),
+    ('\u{9c1}', '\u{9c4}', IndicConjunctBreak::Extend),
+    ('\u{9cd}', '\u{9cd}', IndicConjunctBreak::Linker),
+    ('\u{9d7}', '\u{9d7}', IndicConjunctBreak::Extend),
+    ('\u{9dc}', '\u{9dd}', IndicConjunctBreak::Consonant),
+    ('\u{9df}', '\u{9df}', IndicConjunctBreak::Consonant),
+    ('\u{9e2}', '\u{9e3}', IndicConjunctBreak::Extend),
+    ('\u{9f0}', '\u{9f1}', IndicConjunctBreak::Consonant),
+    ('\u{9fe}', '\u{9fe}', IndicConjunctBreak::Extend),
+    ('\u{a01}', '\u{a02}', IndicConjunctBreak::Extend),
+    ('\u{a3c}', '\u{a3c}', IndicConjunctBreak::Extend),
+    ('\u{a41}', '\u{a42}', IndicConjunctBreak::Extend),
+    ('\u{a47}', '\u{a48}', IndicConjunctBreak::Extend),
+    ('\u{a4b}', '\u{a4d}', IndicConjunctBreak::Extend),
+    ('\u{a51}', '\u{a51}', IndicConjunctBreak::Extend),
+    ('\u{a70}', '\u{a71}', IndicConjunctBreak::Extend),
+    ('\u{a75}', '\u{a75}', IndicConjunctBreak::Extend),
+    ('\u{a81}', '\u{a82}', IndicConjunctBreak::Extend),
+    ('\u{a95}', '\u{aa8}', IndicConjunctBreak::Consonant),
+    ('\u{aaa}', '\u{ab0}', IndicConjunctBreak::Consonant),
+    ('\u{ab2}', '\u{ab3}', IndicConjunctBreak::Consonant),
+    ('\u{ab5}', '\u{ab9}', IndicConjunctBreak::Consonant),
+    ('\u{abc}', '\u{abc}', IndicConjunctBreak::Extend),
+    ('\u{ac1}', '\u{ac5}', IndicConjunctBreak::Extend),
+    ('\u{ac7}', '\u{ac8}', IndicConjunctBreak::Extend),
+    ('\u{acd}', '\u{acd}', IndicConjunctBreak::Linker),
+    ('\u{ae2}', '\u{ae3}', IndicConjunctBreak::Extend),
+    ('\u{af9}', '\u{af9}', IndicConjunctBreak::Consonant),
+    ('\u{afa}', '\u{aff}', IndicConjunctBreak::Extend),
+    ('\u{b01}', '\u{b01}', IndicConjunctBreak::Extend),
+    ('\u{b15}', '\u{b28}', IndicConjunctBreak::Consonant),
+    ('\u{b2a}', '\u{b30}', IndicConjunctBreak::Consonant),
+    ('\u{b32}', '\u{b33}', IndicConjunctBreak::Consonant),
+    ('\u{b35}', '\u{b39}', IndicConjunctBreak::Consonant),
+    ('\u{b3c}', '\u{b3c}', IndicConjunctBreak::Extend),
+    ('\u{b3e}', '\u{b3f}', IndicConjunctBreak::Extend),
+    ('\u{b41}', '\u{b44}', IndicConjunctBreak::Extend),
+    ('\u{b4d}', '\u{b4d}', IndicConjunctBreak::Linker),
+    ('\u{b55}', '\u{b57}', IndicConjunctBreak::Extend),
+    ('\u{b5c}', '\u{b5d}', IndicConjunctBreak::Consonant),
+    ('\u{b5f}', '\u{b5f}', IndicConjunctBreak::Consonant),
+    ('\u{b62}', '\u{b63}', IndicConjunctBreak::Extend),
+    ('\u{b71}', '\u{b71}', IndicConjunctBreak::Consonant),
+    ('\u{b82}', '\u{b82}', IndicConjunctBreak::Extend),
+    ('\u{bbe}', '\u{bbe}', IndicConjunctBreak::Extend),
+    ('\u{bc0}', '\u{bc0}', IndicConjunctBreak::Extend),
+    ('\u{bcd}', '\u{bcd}', IndicConjunctBreak::Extend),
+    ('\u{bd7}', '\u{bd7}', IndicConjunctBreak::Extend),
+    ('\u{c00}', '\u{c00}', IndicConjunctBreak::Extend),
+    ('\u{c04}', '\u{c04}', IndicConjunctBreak::Extend),
+    ('\u{c15}', '\u{c28}', IndicConjunctBreak::Consonant),
+    ('\u{c2a}', '\u{c39}', IndicConjunctBreak::Consonant),
+    ('\u{c3c}', '\u{c3c}', IndicConjunctBreak::Extend),
+    ('\u{c3e}', '\u{c40}', IndicConjunctBreak::Extend),
+    ('\u{c46}', '\u{c48}', IndicConjunctBreak::Extend),
+    ('\u{c4a}', '\u{c4c}', IndicConjunctBreak::Extend),
+    ('\u{c4d}', '\u{c4d}', IndicConjunctBreak::Linker),
+    ('\u{c55}', '\u{c56}', IndicConjunctBreak::Extend),
+    ('\u{c58}', '\u{c5a}', IndicConjunctBreak::Consonant),
+    ('\u{c62}', '\u{c63}', IndicConjunctBreak::Extend),
+    ('\u{c81}', '\u{c81}', IndicConjunctBreak::Extend),
+    ('\u{cbc}', '\u{cbc}', IndicConjunctBreak::Extend),
+    ('\u{cbf}', '\u{cc0}', IndicConjunctBreak::Extend),
+    ('\u{cc2}', '\u{cc2}', IndicConjunctBreak::Extend),
+    ('\u{cc6}', '\u{cc8}', IndicConjunctBreak::Extend),
+    ('\u{cca}', '\u{ccd}', IndicConjunctBreak::Extend),
+    ('\u{cd5}', '\u{cd6}', IndicConjunctBreak::Extend),
+    ('\u{ce2}', '\u{ce3}', IndicConjunctBreak::Extend),
+    ('\u{d00}', '\u{d01}', IndicConjunctBreak::Extend),
+    ('\u{d15}', '\u{d3a}', IndicConjunctBreak::Consonant),
+    ('\u{d3b}', '\u{d3c}', IndicConjunctBreak::Extend),
+    ('\u{d3e}', '\u{d3e}', IndicConjunctBreak::Extend),
+    ('\u{d41}', '\u{d44}', IndicConjunctBreak::Extend),
+    ('\u{d4d}', '\u{d4d}', IndicConjunctBreak::Linker),
+    ('\u{d57}', '\u{d57}', IndicConjunctBreak::Extend),
+    ('\u{d62}', '\u{d63}', IndicConjunctBreak::Extend),
+    ('\u{d81}', '\u{d81}', IndicConjunctBreak::Extend),
+    ('\u{dca}', '\u{dca}', IndicConjunctBreak::Extend),
+    ('\u{dcf}', '\u{dcf}', IndicConjunctBreak::Extend),
+    ('\u{dd2}', '\u{dd4}', IndicConjunctBreak::Extend),
+    ('\u{dd6}', '\u{dd6}', IndicConjunctBreak::Extend),
+    ('\u{ddf}', '\u{ddf}', IndicConjunctBreak::Extend),
+    ('\u{e31}', '\u{e31}', IndicConjunctBreak::Extend),
+    ('\u{e34}', '\u{e3a}', IndicConjunctBreak::Extend),
+    ('\u{e47}', '\u{e4e}', IndicConjunctBreak::Extend),
+    ('\u{eb1}', '\u{eb1}', IndicConjunctBreak::Extend),
+    ('\u{eb4}', '\u{ebc}', IndicConjunctBreak::Extend),
+    ('\u{ec8}', '\u{ece}', IndicConjunctBreak::Extend),
+    ('\u{f18}', '\u{f19}', IndicConjunctBreak::Extend),
+    ('\u{f35}', '\u{f35}', IndicConjunctBreak::Extend),
+    ('\u{f37}', '\u{f37}', IndicConjunctBreak::Extend),
+    ('\u{f39}', '\u{f39}', IndicConjunctBreak::Extend),
+    ('\u{f71}', '\u{f7e}', IndicConjunctBreak::Extend),
+    ('\u{f80}', '\u{f84}', IndicConjunctBreak::Extend),
+    ('\u{f86}', '\u{f87}', IndicConjunctBreak::Extend),
+    ('\u{f8d}', '\u{f97}', IndicConjunctBreak::Extend),
+    ('\u{f99}', '\u{fbc}', IndicConjunctBreak::Extend),
+    ('\u{fc6}', '\u{fc6}', IndicConjunctBreak::Extend),
+    ('\u{102d}', '\u{1030}', IndicConjunctBreak::Extend),
+    ('\u{1032}', '\u{1037}', IndicConjunctBreak::Extend),
+    ('\u{1039}', '\u{103a}', IndicConjunctBreak::Extend),
+    ('\u{103d}', '\u{103e}', IndicConjunctBreak::Extend),
+    ('\u{1058}', '\u{1059}', IndicConjunctBreak::Extend),
+    ('\u{105e}', '\u{1060}', IndicConjunctBreak::Extend),
+    ('\u{1071}', '\u{1074}', IndicConjunctBreak::Extend),
+    ('\u{1082}', '\u{1082}', IndicConjunctBreak::Extend),
+    ('\u{1085}', '\u{1086}', IndicConjunctBreak::Extend),
+    ('\u{108d}', '\u{108d}', IndicConjunctBreak::Extend),
+    ('\u{109d}', '\u{109d}', IndicConjunctBreak::Extend),
+    ('\u{135d}', '\u{135f}', IndicConjunctBreak::Extend),
+    ('\u{1712}', '\u{1715}', IndicConjunctBreak::Extend),
+    ('\u{1732}', '\u{1734}', IndicConjunctBreak::Extend),
+    ('\u{1752}', '\u{1753}', IndicConjunctBreak::Extend),
+    ('\u{1772}', '\u{1773}', IndicConjunctBreak::Extend),
+    ('\u{17b4}', '\u{17b5}', IndicConjunctBreak::Extend),
+    ('\u{17b7}', '\u{17bd}', IndicConjunctBreak::Extend),
+    ('\u{17c6}', '\u{17c6}', IndicConjunctBreak::Extend),
+    ('\u{17c9}', '\u{17d3}', IndicConjunctBreak::Extend),
+    ('\u{17dd}', '\u{17dd}', IndicConjunctBreak::Extend),
+    ('\u{180b}', '\u{180d}', IndicConjunctBreak::Extend),
+    ('\u{180f}', '\u{180f}', IndicConjunctBreak::Extend),
+    ('\u{1885}', '\u{1886}', IndicConjunctBreak::Extend),
+    ('\u{18a9}', '\u{18a9}', IndicConjunctBreak::Extend),
+    ('\u{1920}', '\u{1922}', IndicConjunctBreak::Extend),
+    ('\u{1927}', '\u{1928}', IndicConjunctBreak::Extend),
+    ('\u{1932}', '\u{1932}', IndicConjunctBreak::Extend),
+    ('\u{1939}', '\u{193b}', IndicConjunctBreak::Extend),
+    ('\u{1a17}', '\u{1a18}', IndicConjunctBreak::Extend),
+    ('\u{1a1b}', '\u{1a1b}', IndicConjunctBreak::Extend),
+    ('\u{1a56}', '\u{1a56}', IndicConjunctBreak::Extend),
+    ('\u{1a58}', '\u{1a5e}', IndicConjunctBreak::Extend),
+    ('\u{1a60}', '\u{1a60}', IndicConjunctBreak::Extend),
+    ('\u{1a62}', '\u{1a62}', IndicConjunctBreak::Extend),
+    ('\u{1a65}', '\u{1a6c}', IndicConjunctBreak::Extend),
+    ('\u{1a73}', '\u{1a7c}', IndicConjunctBreak::Extend),
+    ('\u{1a7f}', '\u{1a7f}', IndicConjunctBreak::Extend),
+    ('\u{1ab0}', '\u{1ace}', IndicConjunctBreak::Extend),
+    ('\u{1b00}', '\u{1b03}', IndicConjunctBreak::Extend),
+    ('\u{1b34}', '\u{1b3d}', IndicConjunctBreak::Extend),
+    ('\u{1b42}', '\u{1b44}', IndicConjunctBreak::Extend),
+    ('\u{1b6b}', '\u{1b73}', IndicConjunctBreak::Extend),
+    ('\u{1b80}', '\u{1b81}', IndicConjunctBreak::Extend),
+    ('\u{1ba2}', '\u{1ba5}', IndicConjunctBreak::Extend),
+    ('\u{1ba8}', '\u{1bad}', IndicConjunctBreak::Extend),
+    ('\u{1be6}', '\u{1be6}', IndicConjunctBreak::Extend),
+    ('\u{1be8}', '\u{1be9}', IndicConjunctBreak::Extend),
+    ('\u{1bed}', '\u{1bed}', IndicConjunctBreak::Extend),
+    ('\u{1bef}', '\u{1bf3}', IndicConjunctBreak::Extend),
+    ('\u{1c2c}', '\u{1c33}', IndicConjunctBreak::Extend),
+    ('\u{1c36}', '\u{1c37}', IndicConjunctBreak::Extend),
+    ('\u{1cd0}', '\u{1cd2}', IndicConjunctBreak::Extend),
+    ('\u{1cd4}', '\u{1ce0}', IndicConjunctBreak::Extend),
+    ('\u{1ce2}', '\u{1ce8}', IndicConjunctBreak::Extend),
+    ('\u{1ced}', '\u{1ced}', IndicConjunctBreak::Extend),
+    ('\u{1cf4}', '\u{1cf4}', IndicConjunctBreak::Extend),
+    ('\u{1cf8}', '\u{1cf9}', IndicConjunctBreak::Extend),
+    ('\u{1dc0}', '\u{1dff}', IndicConjunctBreak::Extend),
+    ('\u{200d}', '\u{200d}', IndicConjunctBreak::Extend),
+    ('\u{20d0}', '\u{20f0}', IndicConjunctBreak::Extend),
+    ('\u{2cef}', '\u{2cf1}', IndicConjunctBreak::Extend),
+    ('\u{2d7f}', '\u{2d7f}', IndicConjunctBreak::Extend),
+    ('\u{2de0}', '\u{2dff}', IndicConjunctBreak::Extend),
+    ('\u{302a}', '\u{302f}', IndicConjunctBreak::Extend),
+    ('\u{3099}', '\u{309a}', IndicConjunctBreak::Extend),
+    ('\u{a66f}', '\u{a672}', IndicConjunctBreak::Extend),
+    ('\u{a674}', '\u{a67d}', IndicConjunctBreak::Extend),
+    ('\u{a69e}', '\u{a69f}', IndicConjunctBreak::Extend),
+    ('\u{a6f0}', '\u{a6f1}', IndicConjunctBreak::Extend),
+    ('\u{a802}', '\u{a802}', IndicConjunctBreak::Extend),
+    ('\u{a806}', '\u{a806}', IndicConjunctBreak::Extend),
+    ('\u{a80b}', '\u{a80b}', IndicConjunctBreak::Extend),
+    ('\u{a825}', '\u{a826}', IndicConjunctBreak::Extend),
+    ('\u{a82c}', '\u{a82c}', IndicConjunctBreak::Extend),
+    ('\u{a8c4}', '\u{a8c5}', IndicConjunctBreak::Extend),
+    ('\u{a8e0}', '\u{a8f1}', IndicConjunctBreak::Extend),
+    ('\u{a8ff}', '\u{a8ff}', IndicConjunctBreak::Extend),
+    ('\u{a926}', '\u{a92d}', IndicConjunctBreak::Extend),
+    ('\u{a947}', '\u{a951}', IndicConjunctBreak::Extend),
+    ('\u{a953}', '\u{a953}', IndicConjunctBreak::Extend),
+    ('\u{a980}', '\u{a982}', IndicConjunctBreak::Extend),
+    ('\u{a9b3}', '\u{a9b3}', IndicConjunctBreak::Extend),
+    ('\u{a9b6}', '\u{a9b9}', IndicConjunctBreak::Extend),
+    ('\u{a9bc}', '\u{a9bd}', IndicConjunctBreak::Extend),
+    ('\u{a9c0}', '\u{a9c0}', IndicConjunctBreak::Extend),
+    ('\u{a9e5}', '\u{a9e5}', IndicConjunctBreak::Extend),
+    ('\u{aa29}', '\u{aa2e}', IndicConjunctBreak::Extend),
+    ('\u{aa31}', '\u{aa32}', IndicConjunctBreak::Extend),
+    ('\u{aa35}', '\u{aa36}', IndicConjunctBreak::Extend),
+    ('\u{aa43}', '\u{aa43}', IndicConjunctBreak::Extend),
+    ('\u{aa4c}', '\u{aa4c}', IndicConjunctBreak::Extend),
+    ('\u{aa7c}', '\u{aa7c}', IndicConjunctBreak::Extend),
+    ('\u{aab0}', '\u{aab0}', IndicConjunctBreak::Extend),
+    ('\u{aab2}', '\u{aab4}', IndicConjunctBreak::Extend),
+    ('\u{aab7}', '\u{aab8}', IndicConjunctBreak::Extend),
+    ('\u{aabe}', '\u{aabf}', IndicConjunctBreak::Extend),
+    ('\u{aac1}', '\u{aac1}', IndicConjunctBreak::Extend),
+    ('\u{aaec}', '\u{aaed}', IndicConjunctBreak::Extend),
+    ('\u{aaf6}', '\u{aaf6}', IndicConjunctBreak::Extend),
+    ('\u{abe5}', '\u{abe5}', IndicConjunctBreak::Extend),
+    ('\u{abe8}', '\u{abe8}', IndicConjunctBreak::Extend),
+    ('\u{abed}', '\u{abed}', IndicConjunctBreak::Extend),
+    ('\u{fb1e}', '\u{fb1e}', IndicConjunctBreak::Extend),
+    ('\u{fe00}', '\u{fe0f}', IndicConjunctBreak::Extend),
+    ('\u{fe20}', '\u{fe2f}', IndicConjunctBreak::Extend),
+    ('\u{ff9e}', '\u{ff9f}', IndicConjunctBreak::Extend),
+    ('\u{101fd}', '\u{101fd}', IndicConjunctBreak::Extend),
+    ('\u{102e0}', '\u{102e0}', IndicConjunctBreak::Extend),
+    ('\u{10376}', '\u{1037a}', IndicConjunctBreak::Extend),
+    ('\u{10a01}', '\u{10a03}', IndicConjunctBreak::Extend),
+    ('\u{10a05}', '\u{10a06}', IndicConjunctBreak::Extend),
+    ('\u{10a0c}', '\u{10a0f}', IndicConjunctBreak::Extend),
+    ('\u{10a38}', '\u{10a3a}', IndicConjunctBreak::Extend),
+    ('\u{10a3f}', '\u{10a3f}', IndicConjunctBreak::Extend),
+    ('\u{10ae5}', '\u{10ae6}', IndicConjunctBreak::Extend),
+    ('\u{10d24}', '\u{10d27}', IndicConjunctBreak::Extend),
+    ('\u{10d69}', '\u{10d6d}', IndicConjunctBreak::Extend),
+    ('\u{10eab}', '\u{10eac}', IndicConjunctBreak::Extend),
+    ('\u{10efc}', '\u{10eff}', IndicConjunctBreak::Extend),
+    ('\u{10f46}', '\u{10f50}', IndicConjunctBreak::Extend),
+    ('\u{10f82}', '\u{10f85}', IndicConjunctBreak::Extend),
+    ('\u{11001}', '\u{11001}', IndicConjunctBreak::Extend),
+    ('\u{11038}', '\u{11046}', IndicConjunctBreak::Extend),
+    ('\u{11070}', '\u{11070}', IndicConjunctBreak::Extend),
+    ('\u{11073}', '\u{11074}', IndicConjunctBreak::Extend),
+    ('\u{1107f}', '\u{11081}', IndicConjunctBreak::Extend),
+    ('\u{110b3}', '\u{110b6}', IndicConjunctBreak::Extend),
+    ('\u{110b9}', '\u{110ba}', IndicConjunctBreak::Extend),
+    ('\u{110c2}', '\u{110c2}', IndicConjunctBreak::Extend),
+    ('\u{11100}', '\u{11102}', IndicConjunctBreak::Extend),
+    ('\u{11127}', '\u{1112b}', IndicConjunctBreak::Extend),
+    ('\u{1112d}', '\u{11134}', IndicConjunctBreak::Extend),
+    ('\u{11173}', '\u{11173}', IndicConjunctBreak::Extend),
+    ('\u{11180}', '\u{11181}', IndicConjunctBreak::Extend),
+    ('\u{111b6}', '\u{111be}', IndicConjunctBreak::Extend),
+    ('\u{111c0}', '\u{111c0}', IndicConjunctBreak::Extend),
+    ('\u{111c9}', '\u{111cc}', IndicConjunctBreak::Extend),
+    ('\u{111cf}', '\u{111cf}', IndicConjunctBreak::Extend),
+    ('\u{1122f}', '\u{11231}', IndicConjunctBreak::Extend),
+    ('\u{11234}', '\u{11237}', IndicConjunctBreak::Extend),
+    ('\u{1123e}', '\u{1123e}', IndicConjunctBreak::Extend),
+    ('\u{11241}', '\u{11241}', IndicConjunctBreak::Extend),
+    ('\u{112df}', '\u{112df}', IndicConjunctBreak::Extend),
+    ('\u{112e3}', '\u{112ea}', IndicConjunctBreak::Extend),
+    ('\u{11300}', '\u{11301}', IndicConjunctBreak::Extend),
+    ('\u{1133b}', '\u{1133c}', IndicConjunctBreak::Extend),
+    ('\u{1133e}', '\u{1133e}', IndicConjunctBreak::Extend),
+    ('\u{11340}', '\u{11340}', IndicConjunctBreak::Extend),
+    ('\u{1134d}', '\u{1134d}', IndicConjunctBreak::Extend),
+    ('\u{11357}', '\u{11357}', IndicConjunctBreak::Extend),
+    ('\u{11366}', '\u{1136c}', IndicConjunctBreak::Extend),
+    ('\u{11370}', '\u{11374}', IndicConjunctBreak::Extend),
+    ('\u{113b8}', '\u{113b8}', IndicConjunctBreak::Extend),
+    ('\u{113bb}', '\u{113c0}', IndicConjunctBreak::Extend),
+    ('\u{113c2}', '\u{113c2}', IndicConjunctBreak::Extend),
+    ('\u{113c5}', '\u{113c5}', IndicConjunctBreak::Extend),
+    ('\u{113c7}', '\u{113c9}', IndicConjunctBreak::Extend),
+    ('\u{113ce}', '\u{113d0}', IndicConjunctBreak::Extend),
+    ('\u{113d2}', '\u{113d2}', IndicConjunctBreak::Extend),
+    ('\u{113e1}', '\u{113e2}', IndicConjunctBreak::Extend),
+    ('\u{11438}', '\u{1143f}', IndicConjunctBreak::Extend),
+    ('\u{11442}', '\u{11444}', IndicConjunctBreak::Extend),
+    ('\u{11446}', '\u{11446}', IndicConjunctBreak::Extend),
+    ('\u{1145e}', '\u{1145e}', IndicConjunctBreak::Extend),
+    ('\u{114b0}', '\u{114b0}', IndicConjunctBreak::Extend),
+    ('\u{114b3}', '\u{114b8}', IndicConjunctBreak::Extend),
+    ('\u{114ba}', '\u{114ba}', IndicConjunctBreak::Extend),
+    ('\u{114bd}', '\u{114bd}', IndicConjunctBreak::Extend),
+    ('\u{114bf}', '\u{114c0}', IndicConjunctBreak::Extend),
+    ('\u{114c2}', '\u{114c3}', IndicConjunctBreak::Extend),
+    ('\u{115af}', '\u{115af}', IndicConjunctBreak::Extend),
+    ('\u{115b2}', '\u{115b5}', IndicConjunctBreak::Extend),
+    ('\u{115bc}', '\u{115bd}', IndicConjunctBreak::Extend),
+    ('\u{115bf}', '\u{115c0}', IndicConjunctBreak::Extend),
+    ('\u{115dc}', '\u{115dd}', IndicConjunctBreak::Extend),
+    ('\u{11633}', '\u{1163a}', IndicConjunctBreak::Extend),
+    ('\u{1163d}', '\u{1163d}', IndicConjunctBreak::Extend),
+    ('\u{1163f}', '\u{11640}', IndicConjunctBreak::Extend),
+    ('\u{116ab}', '\u{116ab}', IndicConjunctBreak::Extend),
+    ('\u{116ad}', '\u{116ad}', IndicConjunctBreak::Extend),
+    ('\u{116b0}', '\u{116b7}', IndicConjunctBreak::Extend),
+    ('\u{1171d}', '\u{1171d}', IndicConjunctBreak::Extend),
+    ('\u{1171f}', '\u{1171f}', IndicConjunctBreak::Extend),
+    ('\u{11722}', '\u{11725}', IndicConjunctBreak::Extend),
+    ('\u{11727}', '\u{1172b}', IndicConjunctBreak::Extend),
+    ('\u{1182f}', '\u{11837}', IndicConjunctBreak::Extend),
+    ('\u{11839}', '\u{1183a}', IndicConjunctBreak::Extend),
+    ('\u{11930}', '\u{11930}', IndicConjunctBreak::Extend),
+    ('\u{1193b}', '\u{1193e}', IndicConjunctBreak::Extend),
+    ('\u{11943}', '\u{11943}', IndicConjunctBreak::Extend),
+    ('\u{119d4}', '\u{119d7}', IndicConjunctBreak::Extend),
+    ('\u{119da}', '\u{119db}', IndicConjunctBreak::Extend),
+    ('\u{119e0}', '\u{119e0}', IndicConjunctBreak::Extend),
+    ('\u{11a01}', '\u{11a0a}', IndicConjunctBreak::Extend),
+    ('\u{11a33}', '\u{11a38}', IndicConjunctBreak::Extend),
+    ('\u{11a3b}', '\u{11a3e}', IndicConjunctBreak::Extend),
+    ('\u{11a47}', '\u{11a47}', IndicConjunctBreak::Extend),
+    ('\u{11a51}', '\u{11a56}', IndicConjunctBreak::Extend),
+    ('\u{11a59}', '\u{11a5b}', IndicConjunctBreak::Extend),
+    ('\u{11a8a}', '\u{11a96}', IndicConjunctBreak::Extend),
+    ('\u{11a98}', '\u{11a99}', IndicConjunctBreak::Extend),
+    ('\u{11c30}', '\u{11c36}', IndicConjunctBreak::Extend),
+    ('\u{11c38}', '\u{11c3d}', IndicConjunctBreak::Extend),
+    ('\u{11c3f}', '\u{11c3f}', IndicConjunctBreak::Extend),
+    ('\u{11c92}', '\u{11ca7}', IndicConjunctBreak::Extend),
+    ('\u{11caa}', '\u{11cb0}', IndicConjunctBreak::Extend),
+    ('\u{11cb2}', '\u{11cb3}', IndicConjunctBreak::Extend),
+    ('\u{11cb5}', '\u{11cb6}', IndicConjunctBreak::Extend),
+    ('\u{11d31}', '\u{11d36}', IndicConjunctBreak::Extend),
+    ('\u{11d3a}', '\u{11d3a}', IndicConjunctBreak::Extend),
+    ('\u{11d3c}', '\u{11d3d}', IndicConjunctBreak::Extend),
+    ('\u{11d3f}', '\u{11d45}', IndicConjunctBreak::Extend),
+    ('\u{11d47}', '\u{11d47}', IndicConjunctBreak::Extend),
+    ('\u{11d90}', '\u{11d91}', IndicConjunctBreak::Extend),
+    ('\u{11d95}', '\u{11d95}', IndicConjunctBreak::Extend),
+    ('\u{11d97}', '\u{11d97}', IndicConjunctBreak::Extend),
+    ('\u{11ef3}', '\u{11ef4}', IndicConjunctBreak::Extend),
+    ('\u{11f00}', '\u{11f01}', IndicConjunctBreak::Extend),
+    ('\u{11f36}', '\u{11f3a}', IndicConjunctBreak::Extend),
+    ('\u{11f40}', '\u{11f42}', IndicConjunctBreak::Extend),
+    ('\u{11f5a}', '\u{11f5a}', IndicConjunctBreak::Extend),
+    ('\u{13440}', '\u{13440}', IndicConjunctBreak::Extend),
+    ('\u{13447}', '\u{13455}', IndicConjunctBreak::Extend),
+    ('\u{1611e}', '\u{16129}', IndicConjunctBreak::Extend),
+    ('\u{1612d}', '\u{1612f}', IndicConjunctBreak::Extend),
+    ('\u{16af0}', '\u{16af4}', IndicConjunctBreak::Extend),
+    ('\u{16b30}', '\u{16b36}', IndicConjunctBreak::Extend),
+    ('\u{16f4f}', '\u{16f4f}', IndicConjunctBreak::Extend),
+    ('\u{16f8f}', '\u{16f92}', IndicConjunctBreak::Extend),
+    ('\u{16fe4}', '\u{16fe4}', IndicConjunctBreak::Extend),
+    ('\u{16ff0}', '\u{16ff1}', IndicConjunctBreak::Extend),
+    ('\u{1bc9d}', '\u{1bc9e}', IndicConjunctBreak::Extend),
+    ('\u{1cf00}', '\u{1cf2d}', IndicConjunctBreak::Extend),
+    ('\u{1cf30}', '\u{1cf46}', IndicConjunctBreak::Extend),
+    ('\u{1d165}', '\u{1d169}', IndicConjunctBreak::Extend),
+    ('\u{1d16d}', '\u{1d172}', IndicConjunctBreak::Extend),
+    ('\u{1d17b}', '\u{1d182}', IndicConjunctBreak::Extend),
+    ('\u{1d185}', '\u{1d18b}', IndicConjunctBreak::Extend),
+    ('\u{1d1aa}', '\u{1d1ad}', IndicConjunctBreak::Extend),
+    ('\u{1d242}', '\u{1d244}', IndicConjunctBreak::Extend),
+    ('\u{1da00}', '\u{1da36}', IndicConjunctBreak::Extend),
+    ('\u{1da3b}', '\u{1da6c}', IndicConjunctBreak::Extend),
+    ('\u{1da75}', '\u{1da75}', IndicConjunctBreak::Extend),
+    ('\u{1da84}', '\u{1da84}', IndicConjunctBreak::Extend),
+    ('\u{1da9b}', '\u{1da9f}', IndicConjunctBreak::Extend),
+    ('\u{1daa1}', '\u{1daaf}', IndicConjunctBreak::Extend),
+    ('\u{1e000}', '\u{1e006}', IndicConjunctBreak::Extend),
+    ('\u{1e008}', '\u{1e018}', IndicConjunctBreak::Extend),
+    ('\u{1e01b}', '\u{1e021}', IndicConjunctBreak::Extend),
+    ('\u{1e023}', '\u{1e024}', IndicConjunctBreak::Extend),
+    ('\u{1e026}', '\u{1e02a}', IndicConjunctBreak::Extend),
+    ('\u{1e08f}', '\u{1e08f}', IndicConjunctBreak::Extend),
+    ('\u{1e130}', '\u{1e136}', IndicConjunctBreak::Extend),
+    ('\u{1e2ae}', '\u{1e2ae}', IndicConjunctBreak::Extend),
+    ('\u{1e2ec}', '\u{1e2ef}', IndicConjunctBreak::Extend),
+    ('\u{1e4ec}', '\u{1e4ef}', IndicConjunctBreak::Extend),
+    ('\u{1e5ee}', '\u{1e5ef}', IndicConjunctBreak::Extend),
+    ('\u{1e8d0}', '\u{1e8d6}', IndicConjunctBreak::Extend),
+    ('\u{1e944}', '\u{1e94a}', IndicConjunctBreak::Extend),
+    ('\u{1f3fb}', '\u{1f3ff}', IndicConjunctBreak::Extend),
+    ('\u{e0020}', '\u{e007f}', IndicConjunctBreak::Extend),
+    ('\u{e0100}', '\u{e01ef}', IndicConjunctBreak::Extend),
+];
@@ -2,13 +2,17 @@
 
 use std::fmt::{self, Display};
 
-use crate::engine::ast::{CharClass, Predicate};
+use crate::engine::ast::{ByteClass, CharClass, Predicate};
 
 /// Executable instructions.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Instruction {
     /// Match a single character against a character class.
     CharClass(CharClass),
+    /// Match a single raw byte against a byte class.
+    ///
+    /// Engines that decode characters match it against ASCII characters only.
+    ByteClass(ByteClass),
     /// Evaluate a zero-width assertion.
     Assert(Predicate),
     /// Store the start index of a capture group.
@@ -21,6 +25,14 @@ pub enum Instruction {
     Split(usize, usize),
     /// Unconditional jump.
     Jump(usize),
+    /// Call a subroutine at the given address, pushing a return frame.
+    Call(usize),
+    /// Return from the innermost subroutine call.
+    Return,
+    /// Reset the reported match start to the current position (`\K`).
+    ResetMatchStart,
+    /// Match one extended grapheme cluster (`\X`).
+    GraphemeCluster,
     /// Successful match terminator.
     Match,
 }
@@ -39,12 +51,27 @@ impl Display for Instruction {
                 }
                 write!(f, "]")
             }
+            Instruction::ByteClass(class) => {
+                let neg = if class.negated { "^" } else { "" };
+                write!(f, "byteclass {neg}[")?;
+                for (i, range) in class.ranges.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{:#04x}-{:#04x}", range.start, range.end)?;
+                }
+                write!(f, "]")
+            }
             Instruction::Assert(predicate) => write!(f, "assert {predicate:?}"),
             Instruction::SaveStart(index) => write!(f, "save_start {index}"),
             Instruction::SaveEnd(index) => write!(f, "save_end {index}"),
             Instruction::Backref(index) => write!(f, "backref {index}"),
             Instruction::Split(addr1, addr2) => write!(f, "split {addr1:>04}, {addr2:>04}"),
             Instruction::Jump(addr) => write!(f, "jump {addr:>04}"),
+            Instruction::Call(addr) => write!(f, "call {addr:>04}"),
+            Instruction::Return => write!(f, "return"),
+            Instruction::ResetMatchStart => write!(f, "reset_match_start"),
+            Instruction::GraphemeCluster => write!(f, "grapheme_cluster"),
             Instruction::Match => write!(f, "match"),
         }
     }
@@ -53,7 +80,7 @@ impl Display for Instruction {
 #[cfg(test)]
 mod tests {
     use crate::engine::{
-        ast::{CharClass, CharRange, Predicate},
+        ast::{ByteClass, ByteRange, CharClass, CharRange, Predicate},
         instruction::Instruction,
     };
 
@@ -86,6 +113,16 @@ mod tests {
         assert_eq!(format!("{}", Instruction::Backref(1)), "backref 1");
         assert_eq!(format!("{}", Instruction::Split(2, 10)), "split 0002, 0010");
         assert_eq!(format!("{}", Instruction::Jump(10)), "jump 0010");
+        assert_eq!(format!("{}", Instruction::Call(3)), "call 0003");
+        assert_eq!(format!("{}", Instruction::Return), "return");
+        assert_eq!(
+            format!("{}", Instruction::ResetMatchStart),
+            "reset_match_start"
+        );
+        assert_eq!(
+            format!("{}", Instruction::GraphemeCluster),
+            "grapheme_cluster"
+        );
         assert_eq!(format!("{}", Instruction::Match), "match");
     }
 
@@ -103,4 +140,19 @@ mod tests {
             "charclass ^[a-a]"
         );
     }
+
+    #[test]
+    fn test_instruction_fmt_byte_class() {
+        let class = ByteClass::new(
+            vec![ByteRange {
+                start: 0x80,
+                end: 0xff,
+            }],
+            true,
+        );
+        assert_eq!(
+            format!("{}", Instruction::ByteClass(class)),
+            "byteclass ^[0x80-0xff]"
+        );
+    }
 }
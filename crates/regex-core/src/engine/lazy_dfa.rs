@@ -0,0 +1,425 @@
+//! Lazily built DFA answering whether a line contains a match.
+//!
+//! The DFA runs the UTF-8 byte program of the pattern, so lines are read as
+//! bytes without decoding. A DFA state is the set of instructions waiting
+//! for the next byte, before their epsilon closure is taken, together with
+//! the kind of the previous byte. Deferring the closure until the next byte
+//! is known lets `^`, `$` and `\b` be resolved exactly. Instruction 0 is
+//! added back to every state, which makes the search unanchored.
+//!
+//! States and transitions are determinized on demand and cached across
+//! searches, with one transition per byte class. When the cache outgrows
+//! `MAX_STATES` it is flushed; a search that flushes it more than
+//! `MAX_FLUSHES` times gives up so the caller can fall back to an NFA
+//! engine. Each search takes a cache from a pool and returns it after, so
+//! threads sharing a DFA search concurrently, each with its own cache.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::engine::{
+    ast::Predicate,
+    evaluator::EvalError,
+    instruction::Instruction,
+    utf8::{ByteClasses, ByteInstruction, ByteKind, compile_utf8},
+};
+
+/// Cached states kept before the cache is flushed.
+const MAX_STATES: usize = 4096;
+/// Flushes tolerated in one search before giving up.
+const MAX_FLUSHES: usize = 3;
+
+/// Evaluates an assertion between the `prev` byte (`None` at the start)
+/// and the `next` one (`None` at the end).
+///
+/// A search always starts at the beginning of the text, so `\G` holds
+/// exactly where `\A` does.
+pub(crate) fn eval_predicate(
+    predicate: Predicate,
+    prev: Option<ByteKind>,
+    next: Option<u8>,
+) -> bool {
+    let next_is_word = next.is_some_and(|byte| ByteKind::of(byte) == ByteKind::Word);
+    match predicate {
+        Predicate::StartOfLine => matches!(prev, None | Some(ByteKind::LineFeed)),
+        Predicate::EndOfLine => matches!(next, None | Some(b'\n')),
+        Predicate::StartOfCrlfLine => match prev {
+            None | Some(ByteKind::LineFeed) => true,
+            Some(ByteKind::CarriageReturn) => next != Some(b'\n'),
+            _ => false,
+        },
+        Predicate::EndOfCrlfLine => match next {
+            None | Some(b'\r') => true,
+            Some(b'\n') => prev != Some(ByteKind::CarriageReturn),
+            Some(_) => false,
+        },
+        Predicate::StartOfText | Predicate::StartOfSearch => prev.is_none(),
+        Predicate::EndOfText => next.is_none(),
+        Predicate::WordBoundary => (prev == Some(ByteKind::Word)) != next_is_word,
+        Predicate::NonWordBoundary => (prev == Some(ByteKind::Word)) == next_is_word,
+    }
+}
+
+/// Identity of a DFA state.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct StateKey {
+    /// Sorted instructions reached before their epsilon closure.
+    frontier: Vec<usize>,
+    /// Kind of the previous byte; `None` at the start of the text.
+    prev: Option<ByteKind>,
+}
+
+/// Result of feeding one byte to a state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    /// A match ended before the byte.
+    Match,
+    /// Continue in the state with this index.
+    To(usize),
+}
+
+/// One cached DFA state.
+struct State {
+    key: StateKey,
+    /// Transition on each byte class, once computed.
+    transitions: Vec<Option<Transition>>,
+    /// Whether a match ends at the end of the text, once computed.
+    matches_at_end: Option<bool>,
+}
+
+/// States and transitions built so far.
+#[derive(Default)]
+struct Cache {
+    states: Vec<State>,
+    ids: HashMap<StateKey, usize>,
+}
+
+/// Lazy DFA over a compiled program; see the module documentation.
+pub(crate) struct LazyDfa {
+    program: Vec<ByteInstruction>,
+    classes: ByteClasses,
+    /// Caches not in use by a search.
+    pool: Mutex<Vec<Cache>>,
+}
+
+impl LazyDfa {
+    /// Creates an empty DFA for `inst`, or `None` if `inst` cannot be
+    /// compiled to bytes.
+    pub(crate) fn new(inst: &[Instruction]) -> Option<Self> {
+        let program = compile_utf8(inst)?;
+        let classes = ByteClasses::new(&program);
+        Some(Self {
+            program,
+            classes,
+            pool: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Returns whether `haystack` contains a match, or `None` if the cache
+    /// thrashed.
+    pub(crate) fn is_match(&self, haystack: &[u8]) -> Result<Option<bool>, EvalError> {
+        let mut cache = self.pool().pop().unwrap_or_default();
+        let is_match = cache.is_match(&self.program, &self.classes, haystack);
+        self.pool().push(cache);
+        is_match
+    }
+
+    /// Locks the pool of idle caches.
+    fn pool(&self) -> MutexGuard<'_, Vec<Cache>> {
+        self.pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Cache {
+    fn is_match(
+        &mut self,
+        program: &[ByteInstruction],
+        classes: &ByteClasses,
+        haystack: &[u8],
+    ) -> Result<Option<bool>, EvalError> {
+        let mut flushes = 0;
+        let mut current = self.intern(start_key(), classes);
+
+        for &byte in haystack {
+            let class = classes.get(byte);
+            let transition = match self.states[current].transitions[class] {
+                Some(transition) => transition,
+                None => match self.compute(program, classes, current, byte, &mut flushes)? {
+                    Some(transition) => transition,
+                    None => return Ok(None),
+                },
+            };
+            match transition {
+                Transition::Match => return Ok(Some(true)),
+                Transition::To(next) => current = next,
+            }
+        }
+
+        if let Some(matches) = self.states[current].matches_at_end {
+            return Ok(Some(matches));
+        }
+        let matches = matches_at_end(program, &self.states[current].key)?;
+        self.states[current].matches_at_end = Some(matches);
+        Ok(Some(matches))
+    }
+
+    /// Returns the index of the state for `key`, adding it if needed.
+    fn intern(&mut self, key: StateKey, classes: &ByteClasses) -> usize {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let id = self.states.len();
+        self.ids.insert(key.clone(), id);
+        self.states.push(State {
+            key,
+            transitions: vec![None; classes.len()],
+            matches_at_end: None,
+        });
+        id
+    }
+
+    /// Determinizes the transition of state `current` on `byte` and caches
+    /// it for the class of `byte`.
+    ///
+    /// Returns `None` once this search has flushed the cache too often.
+    fn compute(
+        &mut self,
+        program: &[ByteInstruction],
+        classes: &ByteClasses,
+        mut current: usize,
+        byte: u8,
+        flushes: &mut usize,
+    ) -> Result<Option<Transition>, EvalError> {
+        let key = self.states[current].key.clone();
+
+        let transition = match step(program, &key, byte)? {
+            None => Transition::Match,
+            Some(next_key) => {
+                if !self.ids.contains_key(&next_key) && self.states.len() >= MAX_STATES {
+                    *flushes += 1;
+                    if *flushes > MAX_FLUSHES {
+                        return Ok(None);
+                    }
+                    self.states.clear();
+                    self.ids.clear();
+                    current = self.intern(key, classes);
+                }
+                Transition::To(self.intern(next_key, classes))
+            }
+        };
+
+        self.states[current].transitions[classes.get(byte)] = Some(transition);
+        Ok(Some(transition))
+    }
+}
+
+/// Returns the state a search starts in.
+pub(crate) fn start_key() -> StateKey {
+    StateKey {
+        frontier: vec![0],
+        prev: None,
+    }
+}
+
+/// Returns the state reached from `key` on `byte`, or `None` if a match
+/// ends before `byte`.
+pub(crate) fn step(
+    program: &[ByteInstruction],
+    key: &StateKey,
+    byte: u8,
+) -> Result<Option<StateKey>, EvalError> {
+    let (waiting, matched) = closure(program, &key.frontier, key.prev, Some(byte))?;
+    if matched {
+        return Ok(None);
+    }
+
+    let mut frontier = vec![0];
+    for pc in waiting {
+        if let ByteInstruction::ByteRange(low, high) = program[pc]
+            && (low..=high).contains(&byte)
+        {
+            frontier.push(pc + 1);
+        }
+    }
+    frontier.sort_unstable();
+    frontier.dedup();
+    Ok(Some(StateKey {
+        frontier,
+        prev: Some(ByteKind::of(byte)),
+    }))
+}
+
+/// Returns whether a match ends at the end of the text in state `key`.
+pub(crate) fn matches_at_end(
+    program: &[ByteInstruction],
+    key: &StateKey,
+) -> Result<bool, EvalError> {
+    Ok(closure(program, &key.frontier, key.prev, None)?.1)
+}
+
+/// Follows epsilon transitions from `frontier` between `prev` and `next`.
+///
+/// Returns the `ByteRange` instructions reached and whether `Match` is
+/// reachable.
+fn closure(
+    program: &[ByteInstruction],
+    frontier: &[usize],
+    prev: Option<ByteKind>,
+    next: Option<u8>,
+) -> Result<(Vec<usize>, bool), EvalError> {
+    let mut seen = vec![false; program.len()];
+    let mut stack: Vec<usize> = frontier.to_vec();
+    let mut waiting = Vec::new();
+
+    while let Some(pc) = stack.pop() {
+        let seen_pc = seen.get_mut(pc).ok_or(EvalError::InvalidPC)?;
+        if *seen_pc {
+            continue;
+        }
+        *seen_pc = true;
+
+        match &program[pc] {
+            ByteInstruction::ByteRange(..) => waiting.push(pc),
+            ByteInstruction::Match => return Ok((waiting, true)),
+            ByteInstruction::Assert(predicate) => {
+                if eval_predicate(*predicate, prev, next) {
+                    stack.push(pc + 1);
+                }
+            }
+            ByteInstruction::Save(_) => stack.push(pc + 1),
+            ByteInstruction::Jump(addr) => stack.push(*addr),
+            ByteInstruction::Split(left, right) => {
+                stack.push(*right);
+                stack.push(*left);
+            }
+            ByteInstruction::Fail => {}
+        }
+    }
+
+    Ok((waiting, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LazyDfa, MAX_STATES};
+    use crate::engine::{
+        compiler::compile,
+        evaluator::eval,
+        parser::{ParseOptions, parse, parse_with_options},
+    };
+
+    #[test]
+    fn test_new_rejects_unsupported_programs() {
+        let supported = |pattern: &str| LazyDfa::new(&compile(&parse(pattern).unwrap()).unwrap());
+        assert!(supported("^(a|b)*\\bc$").is_some());
+        assert!(supported("(a)\\1").is_none());
+        assert!(supported("a(?R)?b").is_none());
+        assert!(supported("\\X").is_none());
+    }
+
+    #[test]
+    fn test_is_match_agrees_with_evaluator() {
+        let patterns = [
+            "abc",
+            "a*",
+            "(a|b)*abb",
+            "^foo",
+            "bar$",
+            "^$",
+            "x[^y]+z",
+            "\\Gab",
+            "(a|b){2,3}c",
+            "o\\Kb",
+            "[α-ω]+s",
+            "^.$",
+            "a[^b]c",
+            "é|日本",
+        ];
+        let inputs = [
+            "",
+            "abc",
+            "xabbx",
+            "foo bar",
+            "a\nfoo",
+            "bar\nx",
+            "\n",
+            "foobar",
+            "xyz xaz",
+            "abab",
+            "aabc",
+            "λόγος",
+            "é",
+            "日",
+            "a日c",
+            "x日本語z",
+            "a\u{10348}c",
+        ];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let dfa = LazyDfa::new(&inst).unwrap();
+            for input in inputs {
+                let expected = eval(&inst, input).unwrap();
+                // Run twice so the second pass reads cached transitions.
+                for _ in 0..2 {
+                    let actual = dfa.is_match(input.as_bytes()).unwrap();
+                    assert_eq!(actual, Some(expected), "{pattern} on {input:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_match_crlf_anchors() {
+        let options = ParseOptions {
+            crlf: true,
+            ..ParseOptions::default()
+        };
+        let inst = compile(&parse_with_options("^foo$", options).unwrap()).unwrap();
+        let dfa = LazyDfa::new(&inst).unwrap();
+        for input in ["foo\r\nbar", "x\r\nfoo\r\n", "foo\n", "\rfoo", "xfoo\r\n"] {
+            assert_eq!(
+                dfa.is_match(input.as_bytes()).unwrap(),
+                Some(eval(&inst, input).unwrap()),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_match_gives_up_when_cache_thrashes() {
+        // Remembering the last 13 bytes needs 2^13 states.
+        let inst = compile(&parse("a(a|b){12}c").unwrap()).unwrap();
+        let dfa = LazyDfa::new(&inst).unwrap();
+        let mut seed: u32 = 1;
+        let text: Vec<u8> = (0..MAX_STATES * 8)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                if (seed >> 16) & 1 == 0 { b'a' } else { b'b' }
+            })
+            .collect();
+        assert_eq!(dfa.is_match(&text).unwrap(), None);
+        assert_eq!(dfa.is_match(b"abbbbbbbbbbbbc").unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_is_match_from_several_threads() {
+        let inst = compile(&parse("fo+|ba[rz]").unwrap()).unwrap();
+        let dfa = LazyDfa::new(&inst).unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for input in ["xfoo", "bar", "baq", ""] {
+                        let expected = eval(&inst, input).unwrap();
+                        assert_eq!(dfa.is_match(input.as_bytes()).unwrap(), Some(expected));
+                    }
+                });
+            }
+        });
+        // Every cache went back to the pool.
+        let pool = dfa.pool();
+        assert!((1..=4).contains(&pool.len()));
+        assert!(pool.iter().all(|cache| !cache.states.is_empty()));
+    }
+}
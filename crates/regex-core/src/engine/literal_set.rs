@@ -0,0 +1,148 @@
+//! Matcher for patterns whose language is a finite set of literals.
+//!
+//! `analyze_ast` lists such languages in leftmost-first priority order, so
+//! `abc|def` or `gr[ae]y` need neither an automaton over the program nor
+//! the NFA: `is_match` is a substring search, and `find` takes the leftmost
+//! position where a literal starts and picks the literal there by
+//! priority, or by length in leftmost-longest mode.
+
+use crate::engine::{aho_corasick::AhoCorasick, evaluator::MatchKind, memchr::memmem};
+
+/// Finite literal language; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct LiteralSet {
+    /// The literals in priority order.
+    literals: Vec<Vec<char>>,
+    /// Automaton over all literals.
+    searcher: AhoCorasick,
+    /// The only literal, searched with `memmem`.
+    single: Option<String>,
+}
+
+impl LiteralSet {
+    /// Builds the matcher of `literals`, or returns `None` if the set is
+    /// empty or contains the empty string.
+    pub(crate) fn new(literals: &[String]) -> Option<Self> {
+        if literals.iter().any(String::is_empty) {
+            return None;
+        }
+        let searcher = AhoCorasick::new(literals)?;
+        let single = match literals {
+            [literal] => Some(literal.clone()),
+            _ => None,
+        };
+        Some(Self {
+            literals: literals
+                .iter()
+                .map(|literal| literal.chars().collect())
+                .collect(),
+            searcher,
+            single,
+        })
+    }
+
+    /// Returns whether the set has a single literal.
+    pub(crate) fn is_single(&self) -> bool {
+        self.single.is_some()
+    }
+
+    /// Returns whether a literal occurs in `text`.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        match &self.single {
+            Some(literal) => memmem(text.as_bytes(), literal.as_bytes()).is_some(),
+            None => self.searcher.is_match(text),
+        }
+    }
+
+    /// Finds the leftmost match in `chars` that starts at or after
+    /// `search_start`, as character indices.
+    pub(crate) fn find(
+        &self,
+        chars: &[char],
+        search_start: usize,
+        kind: MatchKind,
+    ) -> Option<(usize, usize)> {
+        let start = self.searcher.leftmost_start(chars, search_start)?;
+        let rest = &chars[start..];
+        let mut matching = self
+            .literals
+            .iter()
+            .filter(|literal| rest.starts_with(literal))
+            .map(Vec::len);
+        let len = match kind {
+            MatchKind::LeftmostFirst => matching.next()?,
+            MatchKind::LeftmostLongest => matching.max()?,
+        };
+        Some((start, start + len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LiteralSet;
+    use crate::engine::{
+        ast::analyze_ast,
+        compiler::compile,
+        evaluator::{MatchKind, eval_find},
+        parser::parse,
+    };
+
+    #[test]
+    fn test_new() {
+        assert!(LiteralSet::new(&[]).is_none());
+        assert!(LiteralSet::new(&["a".to_string(), String::new()]).is_none());
+        assert!(LiteralSet::new(&["abc".to_string()]).unwrap().is_single());
+        assert!(
+            !LiteralSet::new(&["abc".to_string(), "def".to_string()])
+                .unwrap()
+                .is_single()
+        );
+    }
+
+    #[test]
+    fn test_find_agrees_with_evaluator() {
+        let patterns = [
+            "abc",
+            "abc|def",
+            "gr[ae]y",
+            "(a|ab)(c|bcd)",
+            "colou?r",
+            "in|int|integer",
+            "日本|本語",
+        ];
+        let inputs = [
+            "",
+            "xabcdef",
+            "grey gray",
+            "abcd abbcd",
+            "color colour",
+            "ab",
+            "int integer in",
+            "x日本語",
+        ];
+        for pattern in patterns {
+            let ast = parse(pattern).unwrap();
+            let inst = compile(&ast).unwrap();
+            let literals = LiteralSet::new(&analyze_ast(&ast).literals.unwrap()).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                let expected = eval_find(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+                assert_eq!(
+                    literals.is_match(input),
+                    expected.is_some(),
+                    "{pattern} on {input:?}"
+                );
+                for kind in [MatchKind::LeftmostFirst, MatchKind::LeftmostLongest] {
+                    for start in 0..=chars.len() {
+                        let expected = eval_find(&inst, &chars, start, kind).unwrap();
+                        assert_eq!(
+                            literals.find(&chars, start, kind),
+                            expected,
+                            "{pattern} on {input:?} at {start} ({kind:?})"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Byte and substring search over raw bytes.
+//!
+//! With the `simd` feature on x86-64, both searches compare 16 haystack
+//! bytes at a time with SSE2, or 32 with AVX2 when the CPU supports it
+//! (checked at run time). Substring search tests the first and last byte
+//! of the needle at every position of a block at once and only compares
+//! the whole needle where both agree. Other targets and builds without the
+//! feature use the scalar loops.
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+use scalar as imp;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+use x86 as imp;
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+pub(crate) fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    imp::memchr(needle, haystack)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+pub(crate) fn memmem(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        _ if needle.len() > haystack.len() => None,
+        &[byte] => memchr(byte, haystack),
+        _ => imp::memmem(haystack, needle),
+    }
+}
+
+/// Scalar searches, also used for the tails shorter than a vector.
+mod scalar {
+    pub(super) fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+        haystack.iter().position(|&byte| byte == needle)
+    }
+
+    /// Finds `needle`, which must not be empty, by searching for its first
+    /// byte and comparing the rest.
+    pub(super) fn memmem(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        let (&first, rest) = needle.split_first()?;
+        let last_start = haystack.len().checked_sub(needle.len())?;
+        let mut start = 0;
+        while start <= last_start {
+            let pos = start + memchr(first, &haystack[start..=last_start])?;
+            if haystack[pos + 1..pos + needle.len()] == *rest {
+                return Some(pos);
+            }
+            start = pos + 1;
+        }
+        None
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::{
+        __m128i, __m256i, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+        _mm_set1_epi8, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_loadu_si256,
+        _mm256_movemask_epi8, _mm256_set1_epi8,
+    };
+
+    use super::scalar;
+
+    pub(super) fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2.
+            return unsafe { memchr_avx2(needle, haystack) };
+        }
+        // SAFETY: SSE2 is part of the x86-64 baseline.
+        unsafe { memchr_sse2(needle, haystack) }
+    }
+
+    /// Finds a needle of at least two bytes in a haystack at least as long.
+    pub(super) fn memmem(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2.
+            return unsafe { memmem_avx2(haystack, needle) };
+        }
+        // SAFETY: SSE2 is part of the x86-64 baseline.
+        unsafe { memmem_sse2(haystack, needle) }
+    }
+
+    #[target_feature(enable = "sse2")]
+    fn memchr_sse2(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let splat = _mm_set1_epi8(needle as i8);
+        let mut i = 0;
+        while i + 16 <= haystack.len() {
+            // SAFETY: the 16 bytes at `i` are in bounds.
+            let chunk = unsafe { _mm_loadu_si128(haystack.as_ptr().add(i).cast::<__m128i>()) };
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(chunk, splat)) as u32;
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += 16;
+        }
+        scalar::memchr(needle, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn memchr_avx2(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let splat = _mm256_set1_epi8(needle as i8);
+        let mut i = 0;
+        while i + 32 <= haystack.len() {
+            // SAFETY: the 32 bytes at `i` are in bounds.
+            let chunk = unsafe { _mm256_loadu_si256(haystack.as_ptr().add(i).cast::<__m256i>()) };
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(chunk, splat)) as u32;
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += 32;
+        }
+        scalar::memchr(needle, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "sse2")]
+    fn memmem_sse2(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        let last = needle.len() - 1;
+        let first_splat = _mm_set1_epi8(needle[0] as i8);
+        let last_splat = _mm_set1_epi8(needle[last] as i8);
+        let mut i = 0;
+        while i + last + 16 <= haystack.len() {
+            // SAFETY: both loads end at or before `i + last + 16`, which is
+            // in bounds.
+            let (first_chunk, last_chunk) = unsafe {
+                (
+                    _mm_loadu_si128(haystack.as_ptr().add(i).cast::<__m128i>()),
+                    _mm_loadu_si128(haystack.as_ptr().add(i + last).cast::<__m128i>()),
+                )
+            };
+            let candidates = _mm_and_si128(
+                _mm_cmpeq_epi8(first_chunk, first_splat),
+                _mm_cmpeq_epi8(last_chunk, last_splat),
+            );
+            let mut mask = _mm_movemask_epi8(candidates) as u32;
+            while mask != 0 {
+                let pos = i + mask.trailing_zeros() as usize;
+                if haystack[pos + 1..pos + last] == needle[1..last] {
+                    return Some(pos);
+                }
+                mask &= mask - 1;
+            }
+            i += 16;
+        }
+        scalar::memmem(&haystack[i..], needle).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn memmem_avx2(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        let last = needle.len() - 1;
+        let first_splat = _mm256_set1_epi8(needle[0] as i8);
+        let last_splat = _mm256_set1_epi8(needle[last] as i8);
+        let mut i = 0;
+        while i + last + 32 <= haystack.len() {
+            // SAFETY: both loads end at or before `i + last + 32`, which is
+            // in bounds.
+            let (first_chunk, last_chunk) = unsafe {
+                (
+                    _mm256_loadu_si256(haystack.as_ptr().add(i).cast::<__m256i>()),
+                    _mm256_loadu_si256(haystack.as_ptr().add(i + last).cast::<__m256i>()),
+                )
+            };
+            let candidates = _mm256_and_si256(
+                _mm256_cmpeq_epi8(first_chunk, first_splat),
+                _mm256_cmpeq_epi8(last_chunk, last_splat),
+            );
+            let mut mask = _mm256_movemask_epi8(candidates) as u32;
+            while mask != 0 {
+                let pos = i + mask.trailing_zeros() as usize;
+                if haystack[pos + 1..pos + last] == needle[1..last] {
+                    return Some(pos);
+                }
+                mask &= mask - 1;
+            }
+            i += 32;
+        }
+        scalar::memmem(&haystack[i..], needle).map(|pos| i + pos)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{memchr_avx2, memchr_sse2, memmem_avx2, memmem_sse2};
+
+        #[test]
+        fn test_vector_widths_agree() {
+            let mut haystack: Vec<u8> = (0..100u8).map(|i| b'a' + i % 5).collect();
+            haystack.extend_from_slice(b"zq needle");
+            let has_avx2 = is_x86_feature_detected!("avx2");
+            for start in 0..haystack.len() {
+                let haystack = &haystack[start..];
+                for needle in [b'z', b'e', b'a'] {
+                    let expected = haystack.iter().position(|&byte| byte == needle);
+                    // SAFETY: SSE2 is part of the x86-64 baseline.
+                    assert_eq!(unsafe { memchr_sse2(needle, haystack) }, expected);
+                    if has_avx2 {
+                        // SAFETY: the CPU supports AVX2.
+                        assert_eq!(unsafe { memchr_avx2(needle, haystack) }, expected);
+                    }
+                }
+                for needle in [&b"zq"[..], b"needle", b"cdeab", b"xx"] {
+                    if needle.len() > haystack.len() {
+                        continue;
+                    }
+                    let expected = haystack.windows(needle.len()).position(|w| w == needle);
+                    // SAFETY: SSE2 is part of the x86-64 baseline.
+                    assert_eq!(unsafe { memmem_sse2(haystack, needle) }, expected);
+                    if has_avx2 {
+                        // SAFETY: the CPU supports AVX2.
+                        assert_eq!(unsafe { memmem_avx2(haystack, needle) }, expected);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{memchr, memmem, scalar};
+
+    /// Haystacks spanning several vectors, with needles near both ends.
+    fn haystacks() -> Vec<Vec<u8>> {
+        let mut haystacks: Vec<Vec<u8>> = vec![
+            Vec::new(),
+            b"a".to_vec(),
+            b"abcabcabd".to_vec(),
+            "ログ: error 日本".as_bytes().to_vec(),
+        ];
+        for len in [15, 16, 17, 31, 32, 33, 64, 100] {
+            let mut haystack: Vec<u8> = (0..len).map(|i| b'a' + (i % 7) as u8).collect();
+            haystacks.push(haystack.clone());
+            haystack[len - 1] = b'z';
+            haystacks.push(haystack.clone());
+            haystack[len / 2] = b'z';
+            haystack.extend_from_slice(b"needle");
+            haystacks.push(haystack);
+        }
+        haystacks
+    }
+
+    #[test]
+    fn test_memchr_agrees_with_scalar() {
+        for haystack in haystacks() {
+            for needle in [b'a', b'g', b'z', b'n', 0xE6, b'x'] {
+                assert_eq!(
+                    memchr(needle, &haystack),
+                    haystack.iter().position(|&byte| byte == needle),
+                    "{needle} in {haystack:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_memmem_agrees_with_naive_search() {
+        let needles: [&[u8]; 9] = [
+            b"",
+            b"a",
+            b"ab",
+            b"abd",
+            b"gaz",
+            b"needle",
+            b"zabcdefgabcdefgabcdefgabcdefgabcdefg",
+            "日本".as_bytes(),
+            b"missing",
+        ];
+        for haystack in haystacks() {
+            for needle in needles {
+                let expected =
+                    (0..=haystack.len()).find(|&pos| haystack[pos..].starts_with(needle));
+                assert_eq!(
+                    memmem(&haystack, needle),
+                    expected,
+                    "{needle:?} in {haystack:?}"
+                );
+                if !needle.is_empty() {
+                    assert_eq!(scalar::memmem(&haystack, needle), expected);
+                }
+            }
+        }
+    }
+}
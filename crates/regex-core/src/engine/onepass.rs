@@ -0,0 +1,424 @@
+//! One-pass DFA for anchored programs whose captures are never ambiguous.
+//!
+//! A program is one-pass when, from every state, the next character
+//! decides which path to follow: the character instructions reachable
+//! through epsilon transitions have pairwise disjoint classes, and each of
+//! them is reachable along a single path. The capture slots and assertions
+//! on that path are then known ahead of time and stored on the transition,
+//! so a search keeps one state and one set of slots and scans the input
+//! once, without backtracking or thread lists.
+//!
+//! Only programs anchored at the start (by `^`, `\G`, or the start-of-text
+//! assertion of globs) are accepted, so a search tries a single start
+//! position. `^` may also hold after a line terminator; `can_search`
+//! rejects such inputs so the caller falls back to another engine.
+
+use std::collections::HashMap;
+
+use crate::engine::{
+    ast::Predicate,
+    evaluator::{Slots, eval_assert, eval_byte_class, eval_char_class, max_capture_index},
+    instruction::Instruction,
+    pike_vm::to_slots,
+};
+
+/// Largest Unicode scalar value, bounding negated classes.
+const MAX_CHAR: u32 = char::MAX as u32;
+
+/// Where an epsilon path ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// A character instruction at this address.
+    Consume(usize),
+    /// The match instruction.
+    Match,
+}
+
+/// One epsilon path out of a state.
+#[derive(Debug, Clone)]
+struct Transition {
+    target: Target,
+    /// Assertions along the path, all of which must hold.
+    assertions: Vec<Predicate>,
+    /// Capture positions set along the path: `2 * n` is the start of group
+    /// `n` and `2 * n + 1` its end.
+    saves: Vec<usize>,
+}
+
+/// One-pass DFA; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct OnePass {
+    inst: Vec<Instruction>,
+    /// Transitions of the state entered at each address, in priority order.
+    states: HashMap<usize, Vec<Transition>>,
+    /// Whether some start path relies on `^` rather than the start of the
+    /// text or search.
+    line_anchored: bool,
+    slot_count: usize,
+}
+
+impl OnePass {
+    /// Builds the one-pass DFA of `inst`, or returns `None` if the program
+    /// is not anchored or not one-pass.
+    pub(crate) fn new(inst: &[Instruction]) -> Option<Self> {
+        let start = closure(inst, 0)?;
+        let mut line_anchored = false;
+        for transition in &start {
+            let has = |predicates: &[Predicate]| {
+                transition
+                    .assertions
+                    .iter()
+                    .any(|predicate| predicates.contains(predicate))
+            };
+            if has(&[Predicate::StartOfText, Predicate::StartOfSearch]) {
+                continue;
+            }
+            if !has(&[Predicate::StartOfLine, Predicate::StartOfCrlfLine]) {
+                return None;
+            }
+            line_anchored = true;
+        }
+
+        let mut states = HashMap::from([(0, start)]);
+        for (pc, instruction) in inst.iter().enumerate() {
+            if matches!(
+                instruction,
+                Instruction::CharClass(_) | Instruction::ByteClass(_)
+            ) {
+                states.insert(pc + 1, closure(inst, pc + 1)?);
+            }
+        }
+
+        Some(Self {
+            inst: inst.to_vec(),
+            states,
+            line_anchored,
+            slot_count: (max_capture_index(inst) + 1) * 2,
+        })
+    }
+
+    /// Returns whether a search from `search_start` can be answered with a
+    /// single scan.
+    pub(crate) fn can_search(&self, chars: &[char], search_start: usize) -> bool {
+        !self.line_anchored
+            || !chars
+                .get(search_start..)
+                .unwrap_or_default()
+                .iter()
+                .any(|&ch| ch == '\n' || ch == '\r')
+    }
+
+    /// Finds the leftmost-first match starting at `search_start`.
+    ///
+    /// Returns the capture spans as character indices; slot 0 is the whole
+    /// match.
+    pub(crate) fn search(&self, chars: &[char], search_start: usize) -> Option<Slots> {
+        let mut positions: Vec<Option<usize>> = vec![None; self.slot_count];
+        positions[0] = Some(search_start);
+        let mut matched = None;
+        let mut state = 0;
+
+        for char_index in search_start..=chars.len() {
+            let current_char = chars.get(char_index).copied();
+            let mut next = None;
+            for transition in &self.states[&state] {
+                if !transition
+                    .assertions
+                    .iter()
+                    .all(|&predicate| eval_assert(predicate, chars, char_index, search_start))
+                {
+                    continue;
+                }
+                match transition.target {
+                    Target::Match => {
+                        let mut found = positions.clone();
+                        apply(&mut found, &transition.saves, char_index);
+                        found[1] = Some(char_index);
+                        matched = Some(found);
+                        // Lower-priority paths can no longer win.
+                        break;
+                    }
+                    Target::Consume(pc) => {
+                        let accepts = match &self.inst[pc] {
+                            Instruction::CharClass(class) => eval_char_class(class, current_char),
+                            Instruction::ByteClass(class) => eval_byte_class(class, current_char),
+                            _ => false,
+                        };
+                        if accepts && next.is_none() {
+                            next = Some((pc, transition));
+                        }
+                    }
+                }
+            }
+
+            let Some((pc, transition)) = next else {
+                break;
+            };
+            apply(&mut positions, &transition.saves, char_index);
+            state = pc + 1;
+        }
+
+        matched.as_ref().map(to_slots)
+    }
+}
+
+/// Sets each capture position in `saves` to `char_index`.
+fn apply(positions: &mut [Option<usize>], saves: &[usize], char_index: usize) {
+    for &slot in saves {
+        positions[slot] = Some(char_index);
+    }
+}
+
+/// Follows the epsilon transitions from `pc` in priority order.
+///
+/// Returns `None` if the paths make the program not one-pass: an epsilon
+/// loop, an instruction reached twice, overlapping character classes, or
+/// an instruction the DFA cannot run.
+fn closure(inst: &[Instruction], pc: usize) -> Option<Vec<Transition>> {
+    let mut transitions: Vec<Transition> = Vec::new();
+    let mut visited = vec![false; inst.len()];
+    let mut stack = vec![(pc, Vec::new(), Vec::new())];
+
+    while let Some((mut pc, mut assertions, mut saves)) = stack.pop() {
+        loop {
+            let seen = visited.get_mut(pc)?;
+            if *seen {
+                return None;
+            }
+            *seen = true;
+
+            match &inst[pc] {
+                Instruction::Jump(addr) => pc = *addr,
+                Instruction::Split(left, right) => {
+                    stack.push((*right, assertions.clone(), saves.clone()));
+                    pc = *left;
+                }
+                Instruction::Assert(predicate) => {
+                    assertions.push(*predicate);
+                    pc += 1;
+                }
+                Instruction::SaveStart(index) => {
+                    saves.push(2 * index);
+                    pc += 1;
+                }
+                Instruction::SaveEnd(index) => {
+                    saves.push(2 * index + 1);
+                    pc += 1;
+                }
+                Instruction::ResetMatchStart => {
+                    saves.push(0);
+                    pc += 1;
+                }
+                Instruction::CharClass(_) | Instruction::ByteClass(_) => {
+                    transitions.push(Transition {
+                        target: Target::Consume(pc),
+                        assertions,
+                        saves,
+                    });
+                    break;
+                }
+                Instruction::Match => {
+                    transitions.push(Transition {
+                        target: Target::Match,
+                        assertions,
+                        saves,
+                    });
+                    break;
+                }
+                Instruction::Backref(_)
+                | Instruction::Call(_)
+                | Instruction::Return
+                | Instruction::GraphemeCluster => return None,
+            }
+        }
+    }
+
+    let classes: Vec<Vec<(u32, u32)>> = transitions
+        .iter()
+        .filter_map(|transition| match transition.target {
+            Target::Consume(pc) => Some(char_ranges(&inst[pc])),
+            Target::Match => None,
+        })
+        .collect();
+    for (i, left) in classes.iter().enumerate() {
+        if classes[i + 1..].iter().any(|right| overlaps(left, right)) {
+            return None;
+        }
+    }
+
+    Some(transitions)
+}
+
+/// Returns the sorted, merged scalar value ranges a character instruction
+/// accepts.
+fn char_ranges(instruction: &Instruction) -> Vec<(u32, u32)> {
+    let (ranges, negated, max): (Vec<(u32, u32)>, bool, u32) = match instruction {
+        Instruction::CharClass(class) => (
+            class
+                .ranges
+                .iter()
+                .map(|range| (u32::from(range.start), u32::from(range.end)))
+                .collect(),
+            class.negated,
+            MAX_CHAR,
+        ),
+        // Engines that decode characters match byte classes against ASCII only.
+        Instruction::ByteClass(class) => (
+            class
+                .ranges
+                .iter()
+                .filter(|range| range.start <= 0x7F)
+                .map(|range| (u32::from(range.start), u32::from(range.end.min(0x7F))))
+                .collect(),
+            class.negated,
+            0x7F,
+        ),
+        _ => (Vec::new(), false, 0),
+    };
+
+    let mut ranges: Vec<(u32, u32)> = ranges
+        .into_iter()
+        .filter(|(start, end)| start <= end)
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    if !negated {
+        return merged;
+    }
+
+    let mut complement = Vec::with_capacity(merged.len() + 1);
+    let mut next = 0;
+    for (start, end) in merged {
+        if next < start {
+            complement.push((next, start - 1));
+        }
+        next = end + 1;
+    }
+    if next <= max {
+        complement.push((next, max));
+    }
+    complement
+}
+
+/// Returns whether two sorted range lists share a value.
+fn overlaps(left: &[(u32, u32)], right: &[(u32, u32)]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) = (left.get(i), right.get(j)) {
+        if a_start <= b_end && b_start <= a_end {
+            return true;
+        }
+        if a_end < b_end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnePass;
+    use crate::engine::{
+        compiler::compile,
+        parser::{ParseOptions, parse, parse_with_options},
+        pike_vm,
+    };
+
+    fn build(pattern: &str) -> Option<OnePass> {
+        OnePass::new(&compile(&parse(pattern).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_detects_one_pass_programs() {
+        assert!(build("^([0-9]{4})-([0-9]{2})-([0-9]{2})$").is_some());
+        assert!(build("^(a|b)c").is_some());
+        assert!(build("^([a-z]+)=([^;]*)").is_some());
+        assert!(build("\\Gx*y").is_some());
+        assert!(build("^$").is_some());
+
+        // Not anchored.
+        assert!(build("([0-9]+)-").is_none());
+        assert!(build("^a|b").is_none());
+        // The next character does not decide the path.
+        assert!(build("^(a|ab)").is_none());
+        assert!(build("^([a-z]*)([a-c])").is_none());
+        assert!(build("^(a*)*").is_none());
+        // Instructions the DFA cannot run.
+        assert!(build("^(a)\\1").is_none());
+        assert!(build("^\\X").is_none());
+    }
+
+    #[test]
+    fn test_search_agrees_with_pike_vm() {
+        let patterns = [
+            "^([0-9]{4})-([0-9]{2})-([0-9]{2})$",
+            "^(a|b)+c",
+            "^([a-z]+)=([^;]*);?",
+            "^x(y?)z",
+            "^([0-9]{2,3})(-)?",
+            "^ab\\Kc",
+            "^(é|λ)+$",
+            "\\Gx*",
+        ];
+        let inputs = [
+            "2024-01-31",
+            "2024-1-31",
+            "abbac",
+            "bca",
+            "key=value;rest",
+            "k=",
+            "xyz",
+            "xz",
+            "123-4",
+            "12",
+            "abc",
+            "éλé",
+            "xxy",
+            "",
+        ];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            let onepass = OnePass::new(&inst).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                for start in 0..=chars.len() {
+                    let expected = pike_vm::search(&inst, &chars, start, None, false).unwrap();
+                    assert!(onepass.can_search(&chars, start));
+                    assert_eq!(
+                        onepass.search(&chars, start),
+                        expected,
+                        "{pattern} on {input:?} at {start}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_anchor_needs_fallback() {
+        let onepass = build("^b").unwrap();
+        let chars: Vec<char> = "a\nb".chars().collect();
+        assert!(!onepass.can_search(&chars, 0));
+        assert!(onepass.can_search(&chars, 2));
+        assert_eq!(onepass.search(&chars, 2), Some(vec![Some((2, 3))]));
+
+        let options = ParseOptions {
+            crlf: true,
+            ..ParseOptions::default()
+        };
+        let inst = compile(&parse_with_options("^b", options).unwrap()).unwrap();
+        let chars: Vec<char> = "a\rb".chars().collect();
+        assert!(!OnePass::new(&inst).unwrap().can_search(&chars, 0));
+
+        assert!(build("\\Gb").unwrap().can_search(&chars, 0));
+    }
+}
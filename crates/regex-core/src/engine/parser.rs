@@ -2,13 +2,72 @@
 //!
 //! The parser converts a pattern string into an `Ast` used by the compiler.
 
-use crate::engine::ast::{Ast, CharClass, CharRange, Predicate};
+use std::collections::HashMap;
+
+use crate::engine::ast::{Ast, ByteClass, ByteRange, CharClass, CharRange, Predicate};
+use crate::engine::syntax::{self, Syntax};
 use thiserror::Error;
 
 const SPECIAL_CHARS: [char; 14] = [
     '*', '+', '?', '|', '(', ')', '[', ']', '{', '}', '\\', '.', '^', '$',
 ];
 
+/// Horizontal whitespace matched by `\h` (Perl/PCRE definition).
+const HORIZONTAL_WHITESPACE: [CharRange; 9] = [
+    CharRange {
+        start: '\t',
+        end: '\t',
+    },
+    CharRange {
+        start: ' ',
+        end: ' ',
+    },
+    CharRange {
+        start: '\u{A0}',
+        end: '\u{A0}',
+    },
+    CharRange {
+        start: '\u{1680}',
+        end: '\u{1680}',
+    },
+    CharRange {
+        start: '\u{180E}',
+        end: '\u{180E}',
+    },
+    CharRange {
+        start: '\u{2000}',
+        end: '\u{200A}',
+    },
+    CharRange {
+        start: '\u{202F}',
+        end: '\u{202F}',
+    },
+    CharRange {
+        start: '\u{205F}',
+        end: '\u{205F}',
+    },
+    CharRange {
+        start: '\u{3000}',
+        end: '\u{3000}',
+    },
+];
+
+/// Vertical whitespace matched by `\v` (Perl/PCRE definition).
+const VERTICAL_WHITESPACE: [CharRange; 3] = [
+    CharRange {
+        start: '\n',
+        end: '\r',
+    },
+    CharRange {
+        start: '\u{85}',
+        end: '\u{85}',
+    },
+    CharRange {
+        start: '\u{2028}',
+        end: '\u{2029}',
+    },
+];
+
 /// Errors that can occur while parsing a pattern string.
 #[derive(Debug, Error, PartialEq)]
 pub enum ParseError {
@@ -39,6 +98,42 @@ pub enum ParseError {
     /// Missing numeric argument in repetition syntax.
     #[error("missing repeat argument")]
     MissingRepeatArgument,
+    /// Invalid or empty group name in `(?<name>...)` or `(?&name)`.
+    #[error("invalid group name")]
+    InvalidGroupName,
+    /// The same group name is declared more than once.
+    #[error("duplicate group name: {0}")]
+    DuplicateGroupName(String),
+    /// A subroutine call refers to a group name that is never declared.
+    #[error("unknown group name: {0}")]
+    UnknownGroupName(String),
+    /// Invalid group number in a subroutine call (for example, `(?-0)`).
+    #[error("invalid subroutine call")]
+    InvalidSubroutineCall,
+    /// Malformed `\x` escape, or one that names no character (or no byte
+    /// with Unicode mode disabled).
+    #[error("invalid hexadecimal escape")]
+    InvalidHexEscape,
+    /// `(?-u)` used where the haystack must stay valid UTF-8.
+    #[error("Unicode mode can only be disabled in bytes::Regex")]
+    UnicodeRequired,
+    /// A POSIX-syntax escape with no native equivalent, such as the word
+    /// boundary `\b`.
+    #[error("unsupported escape: \\{0}")]
+    UnsupportedEscape(char),
+}
+
+/// Options that change how a pattern is parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ParseOptions {
+    /// Lowercases literal characters so they match lowercased input.
+    pub ignore_case: bool,
+    /// Treats `\r\n` as one line terminator for `^`/`$` and excludes `\r`/`\n` from `.`.
+    pub crlf: bool,
+    /// Syntax dialect the pattern is written in.
+    pub syntax: Syntax,
+    /// Accepts `(?-u)`, whose raw byte classes can match invalid UTF-8.
+    pub allow_invalid_utf8: bool,
 }
 
 /// Internal parser state.
@@ -50,28 +145,68 @@ struct Parser {
     pos: usize,
     /// Next capture-group index (1-based).
     captures: usize,
+    /// Options supplied by the caller.
+    options: ParseOptions,
+    /// Declared group names and their capture indices.
+    group_names: HashMap<String, usize>,
+    /// Names referenced by `(?&name)` before their declaration.
+    unresolved_names: Vec<String>,
+    /// Whether Unicode mode is enabled; `(?-u)` disables it.
+    unicode: bool,
 }
 
 /// Parses `pattern` and returns its AST representation.
+#[allow(dead_code)]
 pub fn parse(pattern: &str) -> Result<Ast, ParseError> {
-    let mut parser = Parser::new(pattern);
-    let ast = parser.parse_expression()?;
-    if parser.peek().is_some() {
-        return Err(ParseError::UnexpectedChar(parser.peek().unwrap()));
+    parse_with_options(pattern, ParseOptions::default())
+}
+
+/// Parses `pattern` with `options` and returns its AST representation.
+///
+/// Named subroutine calls may refer to groups declared later in the pattern,
+/// so a second pass runs with the names collected by the first one.
+pub(crate) fn parse_with_options(pattern: &str, options: ParseOptions) -> Result<Ast, ParseError> {
+    let pattern = syntax::to_native(pattern, options.syntax)?;
+    let pattern = pattern.as_ref();
+    let mut parser = Parser::new(pattern, options);
+    let ast = parser.parse_pattern()?;
+    if parser.unresolved_names.is_empty() {
+        return Ok(ast);
+    }
+
+    let group_names = std::mem::take(&mut parser.group_names);
+    let mut parser = Parser::new(pattern, options);
+    parser.group_names = group_names;
+    let ast = parser.parse_pattern()?;
+    match parser.unresolved_names.into_iter().next() {
+        Some(name) => Err(ParseError::UnknownGroupName(name)),
+        None => Ok(ast),
     }
-    Ok(ast)
 }
 
 impl Parser {
     /// Creates a parser from a pattern string.
-    fn new(pattern: &str) -> Self {
+    fn new(pattern: &str, options: ParseOptions) -> Self {
         Self {
             input: pattern.chars().collect(),
             pos: 0,
             captures: 1,
+            options,
+            group_names: HashMap::new(),
+            unresolved_names: Vec::new(),
+            unicode: true,
         }
     }
 
+    /// Parses the whole pattern and rejects trailing input.
+    fn parse_pattern(&mut self) -> Result<Ast, ParseError> {
+        let ast = self.parse_expression()?;
+        if let Some(ch) = self.peek() {
+            return Err(ParseError::UnexpectedChar(ch));
+        }
+        Ok(ast)
+    }
+
     /// Parses alternation expressions: `seq ('|' seq)*`.
     fn parse_expression(&mut self) -> Result<Ast, ParseError> {
         let mut left = self.parse_sequence()?;
@@ -162,18 +297,9 @@ impl Parser {
             Some('(') => {
                 self.next();
                 if self.consume_if('?') {
-                    return Err(ParseError::UnexpectedChar('?'));
+                    return self.parse_extended_group();
                 }
-                let capture_index = self.captures;
-                self.captures += 1;
-                let expr = self.parse_expression()?;
-                if !self.consume_if(')') {
-                    return Err(ParseError::MissingParenthesis);
-                }
-                Ok(Ast::Capture {
-                    expr: Box::new(expr),
-                    index: capture_index,
-                })
+                self.parse_capture_group()
             }
             Some('[') => {
                 self.next();
@@ -181,21 +307,23 @@ impl Parser {
             }
             Some('.') => {
                 self.next();
-                Ok(Ast::CharClass(CharClass::new(
-                    vec![CharRange {
-                        start: '\u{0000}',
-                        end: '\u{10FFFF}',
-                    }],
-                    false,
-                )))
+                Ok(self.parse_dot())
             }
             Some('^') => {
                 self.next();
-                Ok(Ast::Assertion(Predicate::StartOfLine))
+                Ok(Ast::Assertion(if self.options.crlf {
+                    Predicate::StartOfCrlfLine
+                } else {
+                    Predicate::StartOfLine
+                }))
             }
             Some('$') => {
                 self.next();
-                Ok(Ast::Assertion(Predicate::EndOfLine))
+                Ok(Ast::Assertion(if self.options.crlf {
+                    Predicate::EndOfCrlfLine
+                } else {
+                    Predicate::EndOfLine
+                }))
             }
             Some('\\') => {
                 self.next();
@@ -204,70 +332,420 @@ impl Parser {
             Some(ch) if Self::is_special_char(ch) => Err(ParseError::UnexpectedChar(ch)),
             Some(_) => {
                 let ch = self.next().ok_or(ParseError::UnexpectedEnd)?;
-                Ok(Self::parse_single_char(ch))
+                Ok(self.parse_literal(ch))
             }
             None => Err(ParseError::UnexpectedEnd),
         }
     }
 
+    /// Builds the class for `.`.
+    ///
+    /// In CRLF mode, `.` excludes the line terminators `\r` and `\n`.
+    /// With Unicode mode disabled, `.` matches one byte.
+    fn parse_dot(&self) -> Ast {
+        if !self.unicode {
+            let ranges = if self.options.crlf {
+                vec![
+                    ByteRange {
+                        start: b'\n',
+                        end: b'\n',
+                    },
+                    ByteRange {
+                        start: b'\r',
+                        end: b'\r',
+                    },
+                ]
+            } else {
+                Vec::new()
+            };
+            return Ast::ByteClass(ByteClass::new(ranges, true));
+        }
+
+        if self.options.crlf {
+            return Ast::CharClass(CharClass::new(
+                vec![
+                    CharRange {
+                        start: '\n',
+                        end: '\n',
+                    },
+                    CharRange {
+                        start: '\r',
+                        end: '\r',
+                    },
+                ],
+                true,
+            ));
+        }
+
+        Ast::CharClass(CharClass::new(
+            vec![CharRange {
+                start: '\u{0000}',
+                end: '\u{10FFFF}',
+            }],
+            false,
+        ))
+    }
+
+    /// Builds the predefined class for `\h`, `\H`, `\v`, `\V`, or `\N`.
+    fn shorthand_class(ch: char) -> CharClass {
+        match ch {
+            'h' => CharClass::new(HORIZONTAL_WHITESPACE.to_vec(), false),
+            'H' => CharClass::new(HORIZONTAL_WHITESPACE.to_vec(), true),
+            'v' => CharClass::new(VERTICAL_WHITESPACE.to_vec(), false),
+            'V' => CharClass::new(VERTICAL_WHITESPACE.to_vec(), true),
+            _ => CharClass::new(
+                vec![CharRange {
+                    start: '\n',
+                    end: '\n',
+                }],
+                true,
+            ),
+        }
+    }
+
+    /// Builds the AST for `\R`: `\r\n` or any single Unicode line break.
+    ///
+    /// Like `(?>\r\n|\v)`, a lone `\r` only matches when no `\n` follows,
+    /// so that backtracking never splits a CRLF.
+    fn parse_linebreak() -> Ast {
+        let crlf = Ast::Concat(vec![
+            Self::parse_single_char('\r'),
+            Self::parse_single_char('\n'),
+        ]);
+        let lone_cr = Ast::Concat(vec![
+            Self::parse_single_char('\r'),
+            Ast::Assertion(Predicate::StartOfCrlfLine),
+        ]);
+        let mut others = VERTICAL_WHITESPACE.to_vec();
+        others[0].end = '\x0c';
+        let others = Ast::CharClass(CharClass::new(others, false));
+        Ast::Alternate(
+            Box::new(crlf),
+            Box::new(Ast::Alternate(Box::new(lone_cr), Box::new(others))),
+        )
+    }
+
+    /// Parses a capturing group body after `(` (and an optional name) has been consumed.
+    fn parse_capture_group(&mut self) -> Result<Ast, ParseError> {
+        let capture_index = self.captures;
+        self.captures += 1;
+        let outer_unicode = self.unicode;
+        let expr = self.parse_expression();
+        self.unicode = outer_unicode;
+        let expr = expr?;
+        if !self.consume_if(')') {
+            return Err(ParseError::MissingParenthesis);
+        }
+        Ok(Ast::Capture {
+            expr: Box::new(expr),
+            index: capture_index,
+        })
+    }
+
+    /// Parses a group after `(?` has been consumed.
+    ///
+    /// Supports named captures (`(?<name>...)`, `(?P<name>...)`),
+    /// subroutine calls (`(?R)`, `(?1)`, `(?-1)`, `(?+1)`, `(?&name)`, `(?P>name)`)
+    /// and the Unicode flag (`(?u)`, `(?-u)`, `(?u:...)`, `(?-u:...)`).
+    fn parse_extended_group(&mut self) -> Result<Ast, ParseError> {
+        match self.peek() {
+            Some('u') => {
+                self.next();
+                self.parse_unicode_flag(true)
+            }
+            Some('-') if self.input.get(self.pos + 1) == Some(&'u') => {
+                self.pos += 2;
+                self.parse_unicode_flag(false)
+            }
+            Some('<') => {
+                self.next();
+                self.parse_named_capture_group()
+            }
+            Some('P') => {
+                self.next();
+                match self.next() {
+                    Some('<') => self.parse_named_capture_group(),
+                    Some('>') => self.parse_named_call(),
+                    _ => Err(ParseError::UnexpectedChar('?')),
+                }
+            }
+            Some('&') => {
+                self.next();
+                self.parse_named_call()
+            }
+            Some('R') => {
+                self.next();
+                self.finish_subroutine_call(0)
+            }
+            Some('0'..='9' | '+' | '-') => self.parse_numbered_call(),
+            _ => Err(ParseError::UnexpectedChar('?')),
+        }
+    }
+
+    /// Parses the rest of a Unicode flag group after `(?u` or `(?-u`.
+    ///
+    /// `(?u)` and `(?-u)` apply to the rest of the enclosing group;
+    /// `(?u:...)` and `(?-u:...)` only to their body.
+    fn parse_unicode_flag(&mut self, unicode: bool) -> Result<Ast, ParseError> {
+        if !unicode && !self.options.allow_invalid_utf8 {
+            return Err(ParseError::UnicodeRequired);
+        }
+        if self.consume_if(')') {
+            self.unicode = unicode;
+            return Ok(Ast::Empty);
+        }
+        if !self.consume_if(':') {
+            return Err(ParseError::UnexpectedChar('?'));
+        }
+
+        let outer_unicode = std::mem::replace(&mut self.unicode, unicode);
+        let expr = self.parse_expression();
+        self.unicode = outer_unicode;
+        let expr = expr?;
+        if !self.consume_if(')') {
+            return Err(ParseError::MissingParenthesis);
+        }
+        Ok(expr)
+    }
+
+    /// Parses `name>...)` after `(?<` or `(?P<` has been consumed.
+    fn parse_named_capture_group(&mut self) -> Result<Ast, ParseError> {
+        let name = self.parse_group_name('>')?;
+        let index = self.captures;
+        match self.group_names.get(&name) {
+            Some(declared) if *declared != index => {
+                return Err(ParseError::DuplicateGroupName(name));
+            }
+            Some(_) => {}
+            None => {
+                self.group_names.insert(name, index);
+            }
+        }
+        self.parse_capture_group()
+    }
+
+    /// Parses `name)` after `(?&` or `(?P>` has been consumed.
+    fn parse_named_call(&mut self) -> Result<Ast, ParseError> {
+        let name = self.parse_group_name(')')?;
+        let index = match self.group_names.get(&name) {
+            Some(index) => *index,
+            None => {
+                self.unresolved_names.push(name);
+                0
+            }
+        };
+        Ok(Ast::SubroutineCall(index))
+    }
+
+    /// Parses `n)`, `+n)`, or `-n)` after `(?` has been consumed.
+    ///
+    /// Relative numbers count from the most recently opened group (`-`)
+    /// or the next group to be opened (`+`).
+    fn parse_numbered_call(&mut self) -> Result<Ast, ParseError> {
+        let sign = match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.next();
+                Some(sign)
+            }
+            _ => None,
+        };
+
+        let mut number: usize = 0;
+        let mut has_digits = false;
+        while let Some(d) = self.peek().and_then(|ch| ch.to_digit(10)) {
+            self.next();
+            has_digits = true;
+            number = number
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d as usize))
+                .ok_or(ParseError::InvalidSubroutineCall)?;
+        }
+        if !has_digits {
+            return Err(ParseError::InvalidSubroutineCall);
+        }
+
+        let index = match sign {
+            None => Some(number),
+            Some(_) if number == 0 => None,
+            Some('+') => self.captures.checked_add(number - 1),
+            Some(_) => self.captures.checked_sub(number).filter(|index| *index > 0),
+        };
+        let index = index.ok_or(ParseError::InvalidSubroutineCall)?;
+        self.finish_subroutine_call(index)
+    }
+
+    /// Consumes the closing `)` of a subroutine call.
+    fn finish_subroutine_call(&mut self, index: usize) -> Result<Ast, ParseError> {
+        if !self.consume_if(')') {
+            return Err(ParseError::MissingParenthesis);
+        }
+        Ok(Ast::SubroutineCall(index))
+    }
+
+    /// Parses a group name (`[A-Za-z_][A-Za-z0-9_]*`) followed by `terminator`.
+    fn parse_group_name(&mut self, terminator: char) -> Result<String, ParseError> {
+        let mut name = String::new();
+        while let Some(ch) = self.peek() {
+            if ch.is_ascii_alphanumeric() || ch == '_' {
+                self.next();
+                name.push(ch);
+            } else {
+                break;
+            }
+        }
+
+        let starts_with_digit = name.chars().next().is_none_or(|ch| ch.is_ascii_digit());
+        if starts_with_digit || !self.consume_if(terminator) {
+            return Err(ParseError::InvalidGroupName);
+        }
+        Ok(name)
+    }
+
     /// Parses a character class body after `[` has been consumed.
+    ///
+    /// With Unicode mode disabled, the members are bytes and the result is
+    /// an `Ast::ByteClass`.
     fn parse_char_class(&mut self) -> Result<Ast, ParseError> {
         let negated = self.consume_if('^');
-        let mut ranges: Vec<CharRange> = Vec::new();
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
         if self.peek() == Some(']') {
             self.next();
-            ranges.push(CharRange {
-                start: ']',
-                end: ']',
-            });
+            ranges.push((u32::from(']'), u32::from(']')));
         }
         while let Some(ch) = self.peek() {
             if ch == ']' {
                 break;
             }
+            if let Some(members) = self.parse_class_shorthand() {
+                ranges.extend(members);
+                continue;
+            }
             let start = self.parse_class_atom()?;
             if self.consume_if('-') {
                 if let Some(end) = self.peek() {
                     if end == ']' {
-                        ranges.push(CharRange { start, end: start });
-                        ranges.push(CharRange {
-                            start: '-',
-                            end: '-',
-                        });
+                        ranges.push((start, start));
+                        ranges.push((u32::from('-'), u32::from('-')));
                     } else {
                         let end = self.parse_class_atom()?;
                         if end < start {
                             return Err(ParseError::InvalidCharClass);
                         }
-                        ranges.push(CharRange { start, end });
+                        ranges.push((start, end));
                     }
                 } else {
                     return Err(ParseError::MissingBracket);
                 }
             } else {
-                ranges.push(CharRange { start, end: start });
+                ranges.push((start, start));
             }
         }
         if !self.consume_if(']') {
             return Err(ParseError::MissingBracket);
         }
+
+        if !self.unicode {
+            let ranges = ranges
+                .into_iter()
+                .map(|(start, end)| {
+                    Some(ByteRange {
+                        start: u8::try_from(start).ok()?,
+                        end: u8::try_from(end).ok()?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or(ParseError::InvalidCharClass)?;
+            return Ok(Ast::ByteClass(ByteClass::new(ranges, negated)));
+        }
+        let ranges = ranges
+            .into_iter()
+            .map(|(start, end)| {
+                Some(CharRange {
+                    start: char::from_u32(start)?,
+                    end: char::from_u32(end)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ParseError::InvalidCharClass)?;
         Ok(Ast::CharClass(CharClass::new(ranges, negated)))
     }
 
+    /// Parses `\h`, `\H`, `\v` or `\V` inside a character class and
+    /// returns the ranges it adds, or returns `None` without consuming
+    /// anything if the next atom is not one of them.
+    fn parse_class_shorthand(&mut self) -> Option<Vec<(u32, u32)>> {
+        let ch = match (self.peek(), self.input.get(self.pos + 1)) {
+            (Some('\\'), Some(&ch @ ('h' | 'H' | 'v' | 'V'))) => ch,
+            _ => return None,
+        };
+        self.pos += 2;
+        Some(self.class_members(&Self::shorthand_class(ch)))
+    }
+
+    /// Returns the members of `class` as ranges for `parse_char_class`,
+    /// complementing a negated class. With Unicode mode disabled, only its
+    /// ASCII characters are kept and the complement covers every byte.
+    fn class_members(&self, class: &CharClass) -> Vec<(u32, u32)> {
+        let (limit, max) = match self.unicode {
+            true => (u32::from(char::MAX), u32::from(char::MAX)),
+            false => (0x7F, 0xFF),
+        };
+        let mut ranges: Vec<(u32, u32)> = class
+            .ranges
+            .iter()
+            .map(|range| (u32::from(range.start), u32::from(range.end).min(limit)))
+            .filter(|(start, end)| start <= end)
+            .collect();
+        if !class.negated {
+            return ranges;
+        }
+        ranges.sort_unstable();
+        let mut complement = Vec::new();
+        let mut next = 0;
+        for (start, end) in ranges {
+            if start > next {
+                complement.push((next, start - 1));
+            }
+            next = next.max(end + 1);
+        }
+        if next <= max {
+            complement.push((next, max));
+        }
+        complement
+    }
+
     /// Parses one atom inside a character class, including escaped chars.
-    fn parse_class_atom(&mut self) -> Result<char, ParseError> {
-        let ch = self.next().ok_or(ParseError::MissingBracket)?;
-        if ch != '\\' {
-            return Ok(ch);
+    ///
+    /// Returns a scalar value, or a byte with Unicode mode disabled, in
+    /// which case only ASCII characters and `\x` escapes are accepted.
+    fn parse_class_atom(&mut self) -> Result<u32, ParseError> {
+        let mut ch = self.next().ok_or(ParseError::MissingBracket)?;
+        if ch == '\\' {
+            ch = self.next().ok_or(ParseError::TrailingBackslash)?;
+            if ch == 'x' {
+                let value = self.parse_hex_escape()?;
+                return match char::from_u32(value) {
+                    Some(ch) if self.unicode || ch.is_ascii() => {
+                        Ok(u32::from(self.fold_class_char(ch)))
+                    }
+                    _ => Ok(value),
+                };
+            }
+        }
+        if !self.unicode && !ch.is_ascii() {
+            return Err(ParseError::InvalidCharClass);
         }
-        let esc = self.next().ok_or(ParseError::TrailingBackslash)?;
-        Ok(esc)
+        Ok(u32::from(self.fold_class_char(ch)))
     }
 
     /// Parses an escape sequence.
     ///
     /// `\1`, `\2`, ... are parsed as backreferences.
-    /// Other escapes are treated as escaped literals.
+    /// `\K` resets the match start and `\G` anchors to the search start.
+    /// `\R` matches any line break sequence and `\X` one grapheme cluster.
+    /// `\h`, `\v`, `\N` and their negations are predefined classes.
+    /// `\xHH` and `\x{H...}` name a character, or a raw byte with Unicode
+    /// mode disabled. Other escapes are treated as escaped literals.
     fn parse_escape(&mut self) -> Result<Ast, ParseError> {
         let ch = self.next().ok_or(ParseError::TrailingBackslash)?;
         let ast = match ch {
@@ -283,11 +761,68 @@ impl Parser {
                 }
                 Ast::Backreference(num as usize)
             }
-            _ => Self::parse_single_char(ch),
+            'K' => Ast::ResetMatchStart,
+            'G' => Ast::Assertion(Predicate::StartOfSearch),
+            'R' => Self::parse_linebreak(),
+            'X' => Ast::GraphemeCluster,
+            'h' | 'H' | 'v' | 'V' | 'N' => Ast::CharClass(Self::shorthand_class(ch)),
+            'x' => {
+                let value = self.parse_hex_escape()?;
+                match char::from_u32(value) {
+                    Some(ch) if self.unicode || ch.is_ascii() => self.parse_literal(ch),
+                    _ => {
+                        let byte = u8::try_from(value).map_err(|_| ParseError::InvalidHexEscape)?;
+                        Ast::ByteClass(ByteClass::new(
+                            vec![ByteRange {
+                                start: byte,
+                                end: byte,
+                            }],
+                            false,
+                        ))
+                    }
+                }
+            }
+            _ => self.parse_literal(ch),
         };
         Ok(ast)
     }
 
+    /// Parses `HH` or `{H...}` after `\x` has been consumed.
+    ///
+    /// The value must be a Unicode scalar value, or a byte with Unicode
+    /// mode disabled.
+    fn parse_hex_escape(&mut self) -> Result<u32, ParseError> {
+        let braced = self.consume_if('{');
+        let mut value: u32 = 0;
+        let mut digits = 0;
+        while let Some(d) = self.peek().and_then(|ch| ch.to_digit(16)) {
+            if !braced && digits == 2 {
+                break;
+            }
+            self.next();
+            digits += 1;
+            value = value
+                .checked_mul(16)
+                .and_then(|v| v.checked_add(d))
+                .ok_or(ParseError::InvalidHexEscape)?;
+        }
+
+        let is_complete = if braced {
+            digits > 0 && self.consume_if('}')
+        } else {
+            digits == 2
+        };
+        let is_valid = if self.unicode {
+            char::from_u32(value).is_some()
+        } else {
+            value <= u32::from(u8::MAX)
+        };
+        if !is_complete || !is_valid {
+            return Err(ParseError::InvalidHexEscape);
+        }
+        Ok(value)
+    }
+
     /// Parses repetition arguments in `{m}`, `{m,}`, `{m,n}`.
     fn parse_repeat(&mut self) -> Result<(u32, Option<u32>), ParseError> {
         let min = self.parse_number()?;
@@ -366,6 +901,36 @@ impl Parser {
         }
     }
 
+    /// Builds the AST for one literal pattern character.
+    ///
+    /// With `ignore_case`, the character is lowercased (possibly into several
+    /// characters) so that it matches lowercased input.
+    fn parse_literal(&self, ch: char) -> Ast {
+        if !self.options.ignore_case {
+            return Self::parse_single_char(ch);
+        }
+
+        let mut lowered: Vec<Ast> = ch.to_lowercase().map(Self::parse_single_char).collect();
+        if lowered.len() == 1 {
+            lowered.pop().unwrap()
+        } else {
+            Ast::Concat(lowered)
+        }
+    }
+
+    /// Lowercases a character-class member when `ignore_case` is enabled.
+    fn fold_class_char(&self, ch: char) -> char {
+        if !self.options.ignore_case {
+            return ch;
+        }
+
+        let mut lowered = ch.to_lowercase();
+        match (lowered.next(), lowered.next()) {
+            (Some(lower), None) => lower,
+            _ => ch,
+        }
+    }
+
     /// Builds an `Ast::CharClass` that matches exactly one literal character.
     fn parse_single_char(ch: char) -> Ast {
         Ast::CharClass(CharClass::new(
@@ -377,8 +942,11 @@ impl Parser {
 
 #[cfg(test)]
 mod tests {
-    use super::{ParseError, Parser, parse};
-    use crate::engine::ast::{Ast, CharClass, CharRange, Predicate};
+    use super::{
+        HORIZONTAL_WHITESPACE, ParseError, ParseOptions, Parser, VERTICAL_WHITESPACE, parse,
+        parse_with_options,
+    };
+    use crate::engine::ast::{Ast, ByteClass, ByteRange, CharClass, CharRange, Predicate};
 
     #[test]
     fn test_parse_abc() {
@@ -789,9 +1357,301 @@ mod tests {
         assert_eq!(actual, expect);
     }
 
+    #[test]
+    fn test_parse_named_capture_group() {
+        let expect = Ast::Capture {
+            expr: Box::new(Ast::Concat(vec![
+                Parser::parse_single_char('a'),
+                Parser::parse_single_char('b'),
+            ])),
+            index: 1,
+        };
+        assert_eq!(parse("(?<name>ab)").unwrap(), expect);
+        assert_eq!(parse("(?P<name>ab)").unwrap(), expect);
+    }
+
+    #[test]
+    fn test_parse_subroutine_calls() {
+        assert_eq!(parse("(?R)").unwrap(), Ast::SubroutineCall(0));
+        assert_eq!(parse("(?0)").unwrap(), Ast::SubroutineCall(0));
+
+        let group = Ast::Capture {
+            expr: Box::new(Parser::parse_single_char('a')),
+            index: 1,
+        };
+        let expect = Ast::Concat(vec![group.clone(), Ast::SubroutineCall(1)]);
+        assert_eq!(parse("(a)(?1)").unwrap(), expect);
+        assert_eq!(parse("(a)(?-1)").unwrap(), expect);
+        assert_eq!(parse("(?<x>a)(?&x)").unwrap(), expect);
+        assert_eq!(parse("(?P<x>a)(?P>x)").unwrap(), expect);
+
+        let expect = Ast::Concat(vec![Ast::SubroutineCall(1), group]);
+        assert_eq!(parse("(?+1)(a)").unwrap(), expect);
+        assert_eq!(parse("(?&x)(?<x>a)").unwrap(), expect);
+    }
+
+    #[test]
+    fn test_parse_ignore_case_lowercases_literals_only() {
+        let options = ParseOptions {
+            ignore_case: true,
+            ..ParseOptions::default()
+        };
+        let actual = parse_with_options("A[B-C](?R)", options).unwrap();
+        let expect = Ast::Concat(vec![
+            Parser::parse_single_char('a'),
+            Ast::CharClass(CharClass::new(
+                vec![CharRange {
+                    start: 'b',
+                    end: 'c',
+                }],
+                false,
+            )),
+            Ast::SubroutineCall(0),
+        ]);
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_error_subroutine_calls() {
+        assert_eq!(
+            parse("(?&missing)"),
+            Err(ParseError::UnknownGroupName("missing".to_string()))
+        );
+        assert_eq!(
+            parse("(?<a>x)(?<a>y)"),
+            Err(ParseError::DuplicateGroupName("a".to_string()))
+        );
+        assert_eq!(parse("(?<1a>x)"), Err(ParseError::InvalidGroupName));
+        assert_eq!(parse("(?<>x)"), Err(ParseError::InvalidGroupName));
+        assert_eq!(parse("(?-0)"), Err(ParseError::InvalidSubroutineCall));
+        assert_eq!(parse("(?-1)"), Err(ParseError::InvalidSubroutineCall));
+        assert_eq!(parse("(?1"), Err(ParseError::MissingParenthesis));
+        assert_eq!(parse("(?:a)"), Err(ParseError::UnexpectedChar('?')));
+    }
+
+    #[test]
+    fn test_parse_reset_match_start_and_start_of_search() {
+        let actual = parse("\\Ga\\Kb").unwrap();
+        let expect = Ast::Concat(vec![
+            Ast::Assertion(Predicate::StartOfSearch),
+            Parser::parse_single_char('a'),
+            Ast::ResetMatchStart,
+            Parser::parse_single_char('b'),
+        ]);
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_parse_grapheme_cluster() {
+        let actual = parse("\\X+").unwrap();
+        let expect = Ast::OneOrMore {
+            expr: Box::new(Ast::GraphemeCluster),
+            greedy: true,
+        };
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_parse_whitespace_shorthand_classes() {
+        let actual = parse("\\h\\H").unwrap();
+        let expect = Ast::Concat(vec![
+            Ast::CharClass(CharClass::new(HORIZONTAL_WHITESPACE.to_vec(), false)),
+            Ast::CharClass(CharClass::new(HORIZONTAL_WHITESPACE.to_vec(), true)),
+        ]);
+        assert_eq!(actual, expect);
+
+        let actual = parse("\\v\\V").unwrap();
+        let expect = Ast::Concat(vec![
+            Ast::CharClass(CharClass::new(VERTICAL_WHITESPACE.to_vec(), false)),
+            Ast::CharClass(CharClass::new(VERTICAL_WHITESPACE.to_vec(), true)),
+        ]);
+        assert_eq!(actual, expect);
+
+        let actual = parse("[\\h,]").unwrap();
+        let mut ranges = HORIZONTAL_WHITESPACE.to_vec();
+        ranges.push(CharRange {
+            start: ',',
+            end: ',',
+        });
+        assert_eq!(actual, Ast::CharClass(CharClass::new(ranges, false)));
+
+        let actual = parse("[^\\V]").unwrap();
+        let ranges = [
+            ('\0', '\u{9}'),
+            ('\u{e}', '\u{84}'),
+            ('\u{86}', '\u{2027}'),
+            ('\u{202a}', char::MAX),
+        ]
+        .map(|(start, end)| CharRange { start, end });
+        assert_eq!(
+            actual,
+            Ast::CharClass(CharClass::new(ranges.to_vec(), true))
+        );
+
+        let actual = parse("\\N").unwrap();
+        let expect = Ast::CharClass(CharClass::new(
+            vec![CharRange {
+                start: '\n',
+                end: '\n',
+            }],
+            true,
+        ));
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_parse_crlf_mode() {
+        let options = ParseOptions {
+            crlf: true,
+            ..ParseOptions::default()
+        };
+        let actual = parse_with_options("^.$", options).unwrap();
+        let expect = Ast::Concat(vec![
+            Ast::Assertion(Predicate::StartOfCrlfLine),
+            Ast::CharClass(CharClass::new(
+                vec![
+                    CharRange {
+                        start: '\n',
+                        end: '\n',
+                    },
+                    CharRange {
+                        start: '\r',
+                        end: '\r',
+                    },
+                ],
+                true,
+            )),
+            Ast::Assertion(Predicate::EndOfCrlfLine),
+        ]);
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn test_parse_linebreak() {
+        let actual = parse("\\R").unwrap();
+        assert_eq!(actual, Parser::parse_linebreak());
+        let Ast::Alternate(crlf, rest) = actual else {
+            panic!("expected alternation");
+        };
+        assert_eq!(
+            *crlf,
+            Ast::Concat(vec![
+                Parser::parse_single_char('\r'),
+                Parser::parse_single_char('\n'),
+            ])
+        );
+        let Ast::Alternate(lone_cr, _) = *rest else {
+            panic!("expected alternation");
+        };
+        assert_eq!(
+            *lone_cr,
+            Ast::Concat(vec![
+                Parser::parse_single_char('\r'),
+                Ast::Assertion(Predicate::StartOfCrlfLine),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_escapes() {
+        assert_eq!(parse("\\x41").unwrap(), Parser::parse_single_char('A'));
+        assert_eq!(parse("\\x{e9}").unwrap(), Parser::parse_single_char('é'));
+        assert_eq!(
+            parse("\\x{1F600}").unwrap(),
+            Parser::parse_single_char('😀')
+        );
+
+        let actual = parse("[\\x61-\\x{63}]").unwrap();
+        let expect = Ast::CharClass(CharClass::new(
+            vec![CharRange {
+                start: 'a',
+                end: 'c',
+            }],
+            false,
+        ));
+        assert_eq!(actual, expect);
+
+        assert_eq!(parse("\\x4"), Err(ParseError::InvalidHexEscape));
+        assert_eq!(parse("\\x{}"), Err(ParseError::InvalidHexEscape));
+        assert_eq!(parse("\\x{41"), Err(ParseError::InvalidHexEscape));
+        assert_eq!(parse("\\x{D800}"), Err(ParseError::InvalidHexEscape));
+        assert_eq!(parse("\\x{110000}"), Err(ParseError::InvalidHexEscape));
+    }
+
+    #[test]
+    fn test_parse_unicode_flag() {
+        let options = ParseOptions {
+            allow_invalid_utf8: true,
+            ..ParseOptions::default()
+        };
+        let byte = |value: u8| {
+            Ast::ByteClass(ByteClass::new(
+                vec![ByteRange {
+                    start: value,
+                    end: value,
+                }],
+                false,
+            ))
+        };
+
+        let actual = parse_with_options("(?-u)\\xFFa", options).unwrap();
+        let expect = Ast::Concat(vec![Ast::Empty, byte(0xFF), Parser::parse_single_char('a')]);
+        assert_eq!(actual, expect);
+
+        // The flag is scoped to the group, and `.` matches one byte.
+        let actual = parse_with_options("((?-u).)\\xFF(?-u:.)", options).unwrap();
+        let any_byte = Ast::ByteClass(ByteClass::new(Vec::new(), true));
+        let expect = Ast::Concat(vec![
+            Ast::Capture {
+                expr: Box::new(Ast::Concat(vec![Ast::Empty, any_byte.clone()])),
+                index: 1,
+            },
+            Parser::parse_single_char('ÿ'),
+            any_byte,
+        ]);
+        assert_eq!(actual, expect);
+
+        let actual = parse_with_options("(?-u)[^\\x00-\\x7Fz]", options).unwrap();
+        let expect = Ast::Concat(vec![
+            Ast::Empty,
+            Ast::ByteClass(ByteClass::new(
+                vec![
+                    ByteRange {
+                        start: 0x00,
+                        end: 0x7F,
+                    },
+                    ByteRange {
+                        start: b'z',
+                        end: b'z',
+                    },
+                ],
+                true,
+            )),
+        ]);
+        assert_eq!(actual, expect);
+
+        assert_eq!(parse("(?-u)a"), Err(ParseError::UnicodeRequired));
+        assert_eq!(
+            parse("(?u)a").unwrap(),
+            Ast::Concat(vec![Ast::Empty, Parser::parse_single_char('a')])
+        );
+        assert_eq!(
+            parse_with_options("(?-u)\\x{100}", options),
+            Err(ParseError::InvalidHexEscape)
+        );
+        assert_eq!(
+            parse_with_options("(?-u)[é]", options),
+            Err(ParseError::InvalidCharClass)
+        );
+        assert_eq!(
+            parse_with_options("(?-u-a)", options),
+            Err(ParseError::UnexpectedChar('?'))
+        );
+    }
+
     #[test]
     fn test_error_unexpected_end() {
-        let mut parser = Parser::new("");
+        let mut parser = Parser::new("", ParseOptions::default());
         let actual = parser.parse_factor();
         assert_eq!(actual, Err(ParseError::UnexpectedEnd));
     }
@@ -0,0 +1,297 @@
+//! Pike VM: runs every NFA thread in lock-step over the input.
+//!
+//! Each input position is visited once and each instruction holds at most
+//! one thread per position, so a search takes O(n·m) time for an input of
+//! length n and a program of length m. Threads are kept in priority order,
+//! which gives the same leftmost-first matches and captures as the
+//! backtracking evaluator.
+//!
+//! Backreferences, subroutine calls and `\X` need more state than a thread
+//! carries; programs using them must go through the evaluator instead.
+
+use crate::engine::{
+    evaluator::{
+        EvalError, Slots, eval_assert, eval_byte_class, eval_char_class, max_capture_index,
+    },
+    instruction::Instruction,
+};
+
+/// Returns whether the Pike VM can run `inst`.
+pub(crate) fn is_supported(inst: &[Instruction]) -> bool {
+    !inst.iter().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Backref(_)
+                | Instruction::Call(_)
+                | Instruction::Return
+                | Instruction::GraphemeCluster
+        )
+    })
+}
+
+/// Thread capture positions: `2 * n` is the start of group `n` and
+/// `2 * n + 1` its end. Group 0 is the whole match.
+type Positions = Vec<Option<usize>>;
+
+/// Threads waiting at one input position, in priority order.
+struct ThreadList {
+    /// Program counter and capture positions of each thread.
+    threads: Vec<(usize, Positions)>,
+    /// Whether an instruction already holds a thread at this position.
+    occupied: Vec<bool>,
+}
+
+impl ThreadList {
+    fn new(len: usize) -> Self {
+        Self {
+            threads: Vec::new(),
+            occupied: vec![false; len],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.threads.clear();
+        self.occupied.fill(false);
+    }
+
+    /// Adds the thread at `pc` and follows its epsilon transitions at
+    /// `char_index`, keeping the priority order of `Split` branches.
+    fn add(
+        &mut self,
+        inst: &[Instruction],
+        chars: &[char],
+        pc: usize,
+        positions: Positions,
+        char_index: usize,
+        search_start: usize,
+    ) -> Result<(), EvalError> {
+        let mut stack = vec![(pc, positions)];
+
+        while let Some((mut pc, mut positions)) = stack.pop() {
+            loop {
+                let occupied = self.occupied.get_mut(pc).ok_or(EvalError::InvalidPC)?;
+                if *occupied {
+                    break;
+                }
+                *occupied = true;
+
+                match &inst[pc] {
+                    Instruction::Jump(addr) => pc = *addr,
+                    Instruction::Split(left, right) => {
+                        stack.push((*right, positions.clone()));
+                        pc = *left;
+                    }
+                    Instruction::Assert(predicate) => {
+                        if !eval_assert(*predicate, chars, char_index, search_start) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::SaveStart(index) => {
+                        let Some(slot) = positions.get_mut(2 * index) else {
+                            break;
+                        };
+                        *slot = Some(char_index);
+                        pc += 1;
+                    }
+                    Instruction::SaveEnd(index) => {
+                        let Some(slot) = positions.get_mut(2 * index + 1) else {
+                            break;
+                        };
+                        *slot = Some(char_index);
+                        pc += 1;
+                    }
+                    Instruction::ResetMatchStart => {
+                        positions[0] = Some(char_index);
+                        pc += 1;
+                    }
+                    Instruction::CharClass(_) | Instruction::ByteClass(_) | Instruction::Match => {
+                        self.threads.push((pc, positions));
+                        break;
+                    }
+                    Instruction::Backref(_)
+                    | Instruction::Call(_)
+                    | Instruction::Return
+                    | Instruction::GraphemeCluster => break,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts thread positions into match slots.
+pub(crate) fn to_slots(positions: &Positions) -> Slots {
+    positions
+        .chunks(2)
+        .map(|pair| match (pair[0], pair[1]) {
+            (Some(start), Some(end)) if start <= end => Some((start, end)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the leftmost-first match that starts at or after `search_start`.
+///
+/// When `starts` is given, matches may only start at those (ascending)
+/// character indices. With `earliest`, the search stops at the first
+/// position where any thread matches; only whether a match exists is then
+/// meaningful.
+pub(crate) fn search(
+    inst: &[Instruction],
+    chars: &[char],
+    search_start: usize,
+    starts: Option<&[usize]>,
+    earliest: bool,
+) -> Result<Option<Slots>, EvalError> {
+    let slot_count = max_capture_index(inst)
+        .checked_add(1)
+        .and_then(|groups| groups.checked_mul(2))
+        .ok_or(EvalError::PCOverFlow)?;
+    let mut current = ThreadList::new(inst.len());
+    let mut next = ThreadList::new(inst.len());
+    let mut starts = starts.map(|starts| starts.iter().copied().peekable());
+    let mut matched: Option<Positions> = None;
+
+    for char_index in search_start..=chars.len() {
+        let may_start = match &mut starts {
+            None => true,
+            Some(starts) => {
+                while starts.next_if(|&start| start < char_index).is_some() {}
+                starts.next_if_eq(&char_index).is_some()
+            }
+        };
+        if matched.is_none() && may_start {
+            let mut positions = vec![None; slot_count];
+            positions[0] = Some(char_index);
+            current.add(inst, chars, 0, positions, char_index, search_start)?;
+        }
+        if current.threads.is_empty() {
+            let no_more_starts = starts.as_mut().is_some_and(|s| s.peek().is_none());
+            if matched.is_some() || no_more_starts {
+                break;
+            }
+            current.clear();
+            continue;
+        }
+
+        let current_char = chars.get(char_index).copied();
+        for (pc, mut positions) in current.threads.drain(..) {
+            match &inst[pc] {
+                Instruction::CharClass(class) if eval_char_class(class, current_char) => {
+                    next.add(inst, chars, pc + 1, positions, char_index + 1, search_start)?;
+                }
+                Instruction::ByteClass(class) if eval_byte_class(class, current_char) => {
+                    next.add(inst, chars, pc + 1, positions, char_index + 1, search_start)?;
+                }
+                Instruction::Match => {
+                    positions[1] = Some(char_index);
+                    matched = Some(positions);
+                    // Lower-priority threads can no longer win.
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if earliest && matched.is_some() {
+            break;
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        next.clear();
+    }
+
+    Ok(matched.as_ref().map(to_slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_supported, search};
+    use crate::engine::{
+        compiler::compile,
+        evaluator::{MatchKind, eval_captures},
+        parser::parse,
+    };
+
+    fn pike_captures(pattern: &str, input: &str) -> Option<Vec<Option<(usize, usize)>>> {
+        let inst = compile(&parse(pattern).unwrap()).unwrap();
+        let chars: Vec<char> = input.chars().collect();
+        search(&inst, &chars, 0, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let supported = |pattern: &str| is_supported(&compile(&parse(pattern).unwrap()).unwrap());
+        assert!(supported("(a|b)*c\\Kd$"));
+        assert!(!supported("(a)\\1"));
+        assert!(!supported("a(?R)?b"));
+        assert!(!supported("\\X"));
+    }
+
+    #[test]
+    fn test_search_captures() {
+        assert_eq!(
+            pike_captures("(a+)(b*)", "xaab"),
+            Some(vec![Some((1, 4)), Some((1, 3)), Some((3, 4))])
+        );
+        assert_eq!(
+            pike_captures("(a|ab)(c|bcd)(d*)", "abcd"),
+            Some(vec![Some((0, 4)), Some((0, 1)), Some((1, 4)), Some((4, 4))])
+        );
+        assert_eq!(pike_captures("ab\\Kc", "xabc"), Some(vec![Some((3, 4))]));
+        assert_eq!(pike_captures("^b", "ab"), None);
+    }
+
+    #[test]
+    fn test_search_agrees_with_evaluator() {
+        let patterns = [
+            "a*",
+            "(a|b)*b",
+            "x(y?)+z",
+            "(ab|a)(bc|c)?",
+            "[0-9]{2,3}-(x|y){1,2}",
+            "\\bfoo\\b",
+            "^$",
+            "(a*)*",
+        ];
+        let inputs = ["", "ab", "aabb", "xyyz", "abc", "12-xy 123-x", "a foo b"];
+        for pattern in patterns {
+            let inst = compile(&parse(pattern).unwrap()).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                for start in 0..=chars.len() {
+                    let expected =
+                        eval_captures(&inst, &chars, start, MatchKind::LeftmostFirst).unwrap();
+                    let expected_span = expected.as_ref().map(|slots| slots[0]);
+                    let actual = search(&inst, &chars, start, None, false).unwrap();
+                    let actual_span = actual.as_ref().map(|slots| slots[0]);
+                    assert_eq!(
+                        actual_span, expected_span,
+                        "{pattern} on {input:?} at {start}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_with_starts() {
+        let inst = compile(&parse("abc").unwrap()).unwrap();
+        let chars: Vec<char> = "abcabc".chars().collect();
+        let span = |starts: &[usize]| {
+            search(&inst, &chars, 0, Some(starts), false)
+                .unwrap()
+                .map(|slots| slots[0])
+        };
+        assert_eq!(span(&[3]), Some(Some((3, 6))));
+        assert_eq!(span(&[1, 2]), None);
+    }
+
+    #[test]
+    fn test_search_is_linear_on_pathological_input() {
+        let inst = compile(&parse("(a|aa)*(a|aa)*c").unwrap()).unwrap();
+        let chars: Vec<char> = "a".repeat(5000).chars().collect();
+        assert_eq!(search(&inst, &chars, 0, None, false).unwrap(), None);
+    }
+}
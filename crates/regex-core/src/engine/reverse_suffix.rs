@@ -0,0 +1,294 @@
+//! Search for patterns that end with a literal, such as
+//! `[a-z.]+@example\.com`.
+//!
+//! Every match ends with the suffix literal, the longest common suffix of
+//! the suffixes `analyze_ast` extracts, so the search scans for the
+//! literal and runs the pattern reversed backwards from the end of each
+//! occurrence. The longest reverse match gives the leftmost start of a
+//! match ending there, and a forward search anchored at that start then
+//! finds its leftmost-first end.
+//!
+//! This needs the first character of the suffix to be read by a single
+//! instruction of the program, outside any loop. A match then contains the
+//! suffix only at its end, so no match ends at an occurrence before the
+//! one ending the leftmost match, and none starts at or before an earlier
+//! occurrence, which bounds each reverse search.
+
+use crate::engine::{
+    ast::{Ast, AstAnalysis},
+    compiler::compile_reverse,
+    evaluator::eval_char_class,
+    instruction::Instruction,
+    memchr::memmem,
+};
+
+/// Shortest suffix worth scanning for instead of running an automaton.
+const MIN_SUFFIX_LEN: usize = 3;
+
+/// Reverse-suffix searcher; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct ReverseSuffix {
+    /// The literal ending every match.
+    suffix: String,
+    /// `suffix` as characters.
+    suffix_chars: Vec<char>,
+    /// Program of the reversed pattern.
+    reverse: Vec<Instruction>,
+}
+
+impl ReverseSuffix {
+    /// Builds the searcher for `ast`, compiled to `code`, or returns `None`
+    /// if its suffix is too short or may occur inside a match, or if the
+    /// program has instructions other than character classes, captures,
+    /// branches and jumps.
+    pub(crate) fn new(ast: &Ast, code: &[Instruction], analysis: &AstAnalysis) -> Option<Self> {
+        let suffix_chars = longest_common_suffix(&analysis.suffixes);
+        if suffix_chars.len() < MIN_SUFFIX_LEN {
+            return None;
+        }
+        let mut readers = Vec::new();
+        for (pc, instruction) in code.iter().enumerate() {
+            match instruction {
+                Instruction::CharClass(class) => {
+                    if eval_char_class(class, Some(suffix_chars[0])) {
+                        readers.push(pc);
+                    }
+                }
+                Instruction::SaveStart(_)
+                | Instruction::SaveEnd(_)
+                | Instruction::Split(..)
+                | Instruction::Jump(_)
+                | Instruction::Match => {}
+                _ => return None,
+            }
+        }
+        let [reader] = readers[..] else {
+            return None;
+        };
+        if reaches(code, reader + 1, reader) {
+            return None;
+        }
+        Some(Self {
+            suffix: suffix_chars.iter().collect(),
+            suffix_chars,
+            reverse: compile_reverse(ast).ok()?,
+        })
+    }
+
+    /// Returns whether `text` contains a match.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let first_len = self.suffix_chars[0].len_utf8();
+        let mut lower = 0;
+        while let Some(pos) = memmem(&text.as_bytes()[lower..], self.suffix.as_bytes()) {
+            let end = lower + pos + self.suffix.len();
+            if self
+                .reverse_match(text[lower..end].chars().rev(), false)
+                .is_some()
+            {
+                return true;
+            }
+            // A match ending later cannot contain this occurrence.
+            lower += pos + first_len;
+        }
+        false
+    }
+
+    /// Returns the start of the leftmost match in `chars` that starts at or
+    /// after `search_start`, as a character index.
+    pub(crate) fn find_start(&self, chars: &[char], search_start: usize) -> Option<usize> {
+        let mut lower = search_start;
+        while let Some(start) = self.find_suffix(chars, lower) {
+            let end = start + self.suffix_chars.len();
+            if let Some(len) = self.reverse_match(chars[lower..end].iter().rev().copied(), true) {
+                return Some(end - len);
+            }
+            lower = start + 1;
+        }
+        None
+    }
+
+    /// Returns the first index at or after `from` where the suffix occurs.
+    fn find_suffix(&self, chars: &[char], mut from: usize) -> Option<usize> {
+        let last_start = chars.len().checked_sub(self.suffix_chars.len())?;
+        while from <= last_start {
+            let start = from
+                + chars[from..=last_start]
+                    .iter()
+                    .position(|&ch| ch == self.suffix_chars[0])?;
+            if chars[start..].starts_with(&self.suffix_chars) {
+                return Some(start);
+            }
+            from = start + 1;
+        }
+        None
+    }
+
+    /// Runs the reversed program anchored at the start of `chars`, which
+    /// yields the text backwards, and returns how many characters the
+    /// longest match reads, or the first match found without `longest`.
+    fn reverse_match(&self, chars: impl Iterator<Item = char>, longest: bool) -> Option<usize> {
+        let mut seen = vec![false; self.reverse.len()];
+        let mut threads = Vec::new();
+        add_thread(&self.reverse, 0, &mut threads, &mut seen);
+        let mut matched = None;
+        let mut read = 0;
+        let mut chars = chars;
+        loop {
+            if threads
+                .iter()
+                .any(|&pc| matches!(self.reverse[pc], Instruction::Match))
+            {
+                matched = Some(read);
+                if !longest {
+                    break;
+                }
+            }
+            let Some(ch) = chars.next() else {
+                break;
+            };
+            seen.fill(false);
+            let mut next = Vec::new();
+            for &pc in &threads {
+                if let Instruction::CharClass(class) = &self.reverse[pc]
+                    && eval_char_class(class, Some(ch))
+                {
+                    add_thread(&self.reverse, pc + 1, &mut next, &mut seen);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            threads = next;
+            read += 1;
+        }
+        matched
+    }
+}
+
+/// Returns the longest string ending every one of `literals`, which is
+/// empty if there are none.
+fn longest_common_suffix(literals: &[String]) -> Vec<char> {
+    let Some((first, rest)) = literals.split_first() else {
+        return Vec::new();
+    };
+    let mut suffix: Vec<char> = first.chars().rev().collect();
+    for literal in rest {
+        let common = suffix
+            .iter()
+            .zip(literal.chars().rev())
+            .take_while(|&(&a, b)| a == b)
+            .count();
+        suffix.truncate(common);
+    }
+    suffix.reverse();
+    suffix
+}
+
+/// Adds the threads reachable from `pc` without reading a character: the
+/// character classes and `Match` instructions.
+fn add_thread(code: &[Instruction], pc: usize, threads: &mut Vec<usize>, seen: &mut [bool]) {
+    let mut stack = vec![pc];
+    while let Some(pc) = stack.pop() {
+        if std::mem::replace(&mut seen[pc], true) {
+            continue;
+        }
+        match code[pc] {
+            Instruction::Split(first, second) => stack.extend([second, first]),
+            Instruction::Jump(target) => stack.push(target),
+            Instruction::SaveStart(_) | Instruction::SaveEnd(_) => stack.push(pc + 1),
+            _ => threads.push(pc),
+        }
+    }
+}
+
+/// Returns whether `target` can be reached from `from`.
+fn reaches(code: &[Instruction], from: usize, target: usize) -> bool {
+    let mut seen = vec![false; code.len()];
+    let mut stack = vec![from];
+    while let Some(pc) = stack.pop() {
+        if pc == target {
+            return true;
+        }
+        if pc >= code.len() || std::mem::replace(&mut seen[pc], true) {
+            continue;
+        }
+        match code[pc] {
+            Instruction::Split(first, second) => stack.extend([first, second]),
+            Instruction::Jump(next) => stack.push(next),
+            Instruction::Match => {}
+            _ => stack.push(pc + 1),
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReverseSuffix;
+    use crate::engine::{
+        ast::analyze_ast,
+        compiler::compile,
+        evaluator::{MatchKind, eval_find},
+        parser::parse,
+    };
+
+    fn reverse_suffix(pattern: &str) -> Option<ReverseSuffix> {
+        let ast = parse(pattern).unwrap();
+        ReverseSuffix::new(&ast, &compile(&ast).unwrap(), &analyze_ast(&ast))
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(reverse_suffix("[a-z0-9.]+@example\\.com").is_some());
+        assert!(reverse_suffix("(foo|ba+r)[0-9]*end").is_some());
+        // Too short, not a single literal, or not the only reader.
+        assert!(reverse_suffix("[a-z]+ab").is_none());
+        assert!(reverse_suffix("[a-z]+(abc|abd)").is_none());
+        assert!(reverse_suffix("[a-z]+abc").is_none());
+        assert!(reverse_suffix("(xabc)+").is_none());
+        assert!(reverse_suffix("[0-9]+abc$").is_none());
+        assert!(reverse_suffix("([0-9]+)\\1abc").is_none());
+    }
+
+    #[test]
+    fn test_find_start_agrees_with_evaluator() {
+        let patterns = [
+            "[a-z0-9.]+@example\\.com",
+            "(foo|ba+r)[0-9]*end",
+            "x?[ab]*cde",
+            "(a|b)?cde",
+        ];
+        let inputs = [
+            "",
+            "mail me@example.com or you@example.com",
+            "@example.com x@example.co",
+            "bar12end fooend baaar9end",
+            "cde abcde xbacde",
+            "ccde bcdcde",
+        ];
+        for pattern in patterns {
+            let ast = parse(pattern).unwrap();
+            let inst = compile(&ast).unwrap();
+            let searcher = reverse_suffix(pattern).unwrap();
+            for input in inputs {
+                let chars: Vec<char> = input.chars().collect();
+                let expected = eval_find(&inst, &chars, 0, MatchKind::LeftmostFirst).unwrap();
+                assert_eq!(
+                    searcher.is_match(input),
+                    expected.is_some(),
+                    "{pattern} on {input:?}"
+                );
+                for start in 0..=chars.len() {
+                    let expected = eval_find(&inst, &chars, start, MatchKind::LeftmostFirst)
+                        .unwrap()
+                        .map(|(start, _)| start);
+                    assert_eq!(
+                        searcher.find_start(&chars, start),
+                        expected,
+                        "{pattern} on {input:?} at {start}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,593 @@
+//! Pattern syntax dialects.
+//!
+//! POSIX basic (BRE) and extended (ERE) patterns are rewritten into the
+//! native Perl-like grammar understood by the parser, following GNU grep.
+
+use std::borrow::Cow;
+
+use crate::engine::parser::ParseError;
+
+/// Escapes that only have a special meaning in the Perl-like syntax.
+const PERL_ONLY_ESCAPES: [char; 10] = ['K', 'G', 'R', 'X', 'h', 'H', 'v', 'V', 'N', 'x'];
+
+/// Inclusive ranges of ASCII characters.
+type ByteRanges = &'static [(u8, u8)];
+
+/// POSIX character class names and the ASCII ranges they stand for, as
+/// in the C locale.
+const POSIX_CLASSES: [(&str, ByteRanges); 12] = [
+    ("alnum", &[(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]),
+    ("alpha", &[(b'A', b'Z'), (b'a', b'z')]),
+    ("blank", &[(b'\t', b'\t'), (b' ', b' ')]),
+    ("cntrl", &[(0x00, 0x1F), (0x7F, 0x7F)]),
+    ("digit", &[(b'0', b'9')]),
+    ("graph", &[(b'!', b'~')]),
+    ("lower", &[(b'a', b'z')]),
+    ("print", &[(b' ', b'~')]),
+    (
+        "punct",
+        &[(b'!', b'/'), (b':', b'@'), (b'[', b'`'), (b'{', b'~')],
+    ),
+    ("space", &[(b'\t', b'\r'), (b' ', b' ')]),
+    ("upper", &[(b'A', b'Z')]),
+    ("xdigit", &[(b'0', b'9'), (b'A', b'F'), (b'a', b'f')]),
+];
+
+/// Syntax dialect used to interpret a pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    /// POSIX basic syntax (`grep -G`).
+    ///
+    /// `\(`, `\)`, `\{`, `\}`, `\|`, `\+`, `\?` are operators and their
+    /// unescaped forms are literals. `*` at the start of an expression,
+    /// `^` not at the start, and `$` not at the end are literals.
+    Basic,
+    /// POSIX extended syntax (`grep -E`).
+    ///
+    /// Perl extensions such as `(?...)` groups and `\K` are not recognized,
+    /// and a `{` that does not start an interval is a literal.
+    Extended,
+    /// Perl-like syntax with every extension this crate supports.
+    #[default]
+    Perl,
+}
+
+/// Rewrites `pattern` from `syntax` into the native Perl-like syntax.
+pub(crate) fn to_native(pattern: &str, syntax: Syntax) -> Result<Cow<'_, str>, ParseError> {
+    match syntax {
+        Syntax::Basic => basic_to_native(pattern).map(Cow::Owned),
+        Syntax::Extended => extended_to_native(pattern).map(Cow::Owned),
+        Syntax::Perl => Ok(Cow::Borrowed(pattern)),
+    }
+}
+
+/// Native pattern being written, with the extent of its last atom.
+struct Output {
+    out: String,
+    /// Where the last atom starts in `out`, or `None` when no atom can be
+    /// repeated here (at the start of an expression or after an anchor).
+    atom_start: Option<usize>,
+    /// Whether a repetition operator already applies to the last atom.
+    repeated: bool,
+    /// Where each group still open starts in `out`.
+    groups: Vec<usize>,
+}
+
+impl Output {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            out: String::with_capacity(capacity),
+            atom_start: None,
+            repeated: false,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Starts a new atom and returns the buffer to write it into.
+    fn begin_atom(&mut self) -> &mut String {
+        self.atom_start = Some(self.out.len());
+        self.repeated = false;
+        &mut self.out
+    }
+
+    fn atom(&mut self, atom: &str) {
+        self.begin_atom().push_str(atom);
+    }
+
+    /// Appends `ch` escaped, as a literal.
+    fn literal(&mut self, ch: char) {
+        let atom = self.begin_atom();
+        atom.push('\\');
+        atom.push(ch);
+    }
+
+    fn open_group(&mut self) {
+        self.groups.push(self.out.len());
+        self.out.push('(');
+        self.atom_start = None;
+    }
+
+    /// Closes the innermost group, which becomes the last atom.
+    fn close_group(&mut self) {
+        self.out.push(')');
+        self.atom_start = self.groups.pop();
+        self.repeated = false;
+    }
+
+    /// Appends `|` or an anchor.
+    fn operator(&mut self, op: char) {
+        self.out.push(op);
+        self.atom_start = None;
+    }
+
+    /// Appends the repetition operator `op` to the last atom.
+    ///
+    /// POSIX applies a second operator to the repeated atom (`a**` is
+    /// `(a*)*`), while the native syntax reads `*?` as lazy and rejects
+    /// `**`, so the repeated atom is wrapped in a group first. That group is
+    /// a `(?u:...)` flag group, which captures nothing.
+    fn repeat(&mut self, op: &str) {
+        if self.repeated
+            && let Some(start) = self.atom_start
+        {
+            self.out.insert_str(start, "(?u:");
+            self.out.push(')');
+        }
+        self.out.push_str(op);
+        self.repeated = true;
+    }
+}
+
+/// Rewrites a POSIX basic pattern.
+fn basic_to_native(pattern: &str) -> Result<String, ParseError> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = Output::with_capacity(pattern.len() + 8);
+    let mut at_expr_start = true;
+    let mut i = 0;
+
+    while let Some(&ch) = chars.get(i) {
+        i += 1;
+        match ch {
+            '\\' => {
+                let Some(&esc) = chars.get(i) else {
+                    out.atom("\\");
+                    break;
+                };
+                i += 1;
+                match esc {
+                    '(' => out.open_group(),
+                    ')' => out.close_group(),
+                    '|' => out.operator('|'),
+                    '{' if !at_expr_start => {
+                        let (interval, end) =
+                            interval(&chars, i, &['\\', '}']).ok_or(ParseError::InvalidRepeatOp)?;
+                        out.repeat(&interval);
+                        i = end;
+                    }
+                    '+' if !at_expr_start => out.repeat("+"),
+                    '?' if !at_expr_start => out.repeat("?"),
+                    _ => match gnu_escape(esc)? {
+                        Some(class) => out.atom(&class),
+                        None => out.literal(esc),
+                    },
+                }
+                at_expr_start = matches!(esc, '(' | '|');
+            }
+            '[' => {
+                i = copy_bracket(&chars, i, out.begin_atom())?;
+                at_expr_start = false;
+            }
+            '^' if at_expr_start => out.operator('^'),
+            '*' if at_expr_start => {
+                out.literal('*');
+                at_expr_start = false;
+            }
+            '*' => out.repeat("*"),
+            '$' if is_basic_expr_end(&chars, i) => {
+                out.operator('$');
+                at_expr_start = false;
+            }
+            '^' | '$' | '+' | '?' | '|' | '(' | ')' | '{' | '}' | ']' => {
+                out.literal(ch);
+                at_expr_start = false;
+            }
+            _ => {
+                out.begin_atom().push(ch);
+                at_expr_start = false;
+            }
+        }
+    }
+
+    Ok(out.out)
+}
+
+/// Returns whether `index` ends a basic expression (end, `\)`, or `\|`).
+fn is_basic_expr_end(chars: &[char], index: usize) -> bool {
+    match chars.get(index) {
+        None => true,
+        Some('\\') => matches!(chars.get(index + 1), Some(')' | '|')),
+        Some(_) => false,
+    }
+}
+
+/// Rewrites a POSIX extended pattern.
+fn extended_to_native(pattern: &str) -> Result<String, ParseError> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = Output::with_capacity(pattern.len() + 8);
+    let mut at_expr_start = true;
+    let mut i = 0;
+
+    while let Some(&ch) = chars.get(i) {
+        i += 1;
+        match ch {
+            '\\' => {
+                let Some(&esc) = chars.get(i) else {
+                    out.atom("\\");
+                    break;
+                };
+                i += 1;
+                match gnu_escape(esc)? {
+                    Some(class) => out.atom(&class),
+                    None if PERL_ONLY_ESCAPES.contains(&esc) => out.begin_atom().push(esc),
+                    None => out.literal(esc),
+                }
+                at_expr_start = false;
+            }
+            '[' => {
+                i = copy_bracket(&chars, i, out.begin_atom())?;
+                at_expr_start = false;
+            }
+            '(' => {
+                out.open_group();
+                at_expr_start = true;
+            }
+            '|' => {
+                out.operator('|');
+                at_expr_start = true;
+            }
+            ')' => {
+                out.close_group();
+                at_expr_start = false;
+            }
+            '^' => out.operator('^'),
+            '$' => {
+                out.operator('$');
+                at_expr_start = false;
+            }
+            '{' if !at_expr_start => match interval(&chars, i, &['}']) {
+                Some((interval, end)) => {
+                    out.repeat(&interval);
+                    i = end;
+                }
+                // Not an interval, so a literal as in GNU grep.
+                None => out.literal('{'),
+            },
+            '*' | '+' | '?' | '{' if at_expr_start => {
+                out.literal(ch);
+                at_expr_start = false;
+            }
+            '*' | '+' | '?' => out.repeat(ch.encode_utf8(&mut [0; 4])),
+            '}' | ']' => {
+                out.literal(ch);
+                at_expr_start = false;
+            }
+            _ => {
+                out.begin_atom().push(ch);
+                at_expr_start = false;
+            }
+        }
+    }
+
+    Ok(out.out)
+}
+
+/// Returns the bracket expression GNU grep gives the class escapes `\w`,
+/// `\W`, `\s` and `\S`, with their ASCII (C locale) members, or `None`
+/// for other escapes.
+///
+/// The anchors `\b`, `\B`, `\<`, `\>`, `` \` `` and `\'` have no native
+/// form and are rejected.
+fn gnu_escape(esc: char) -> Result<Option<String>, ParseError> {
+    let (name, negated) = match esc {
+        'w' | 'W' => ("alnum", esc == 'W'),
+        's' | 'S' => ("space", esc == 'S'),
+        'b' | 'B' | '<' | '>' | '`' | '\'' => return Err(ParseError::UnsupportedEscape(esc)),
+        _ => return Ok(None),
+    };
+    let mut class = String::from(if negated { "[^" } else { "[" });
+    push_ranges(&mut class, class_ranges(name).unwrap_or_default());
+    if name == "alnum" {
+        class.push('_');
+    }
+    class.push(']');
+    Ok(Some(class))
+}
+
+/// Parses the interval `{m}`, `{m,}`, `{m,n}` or `{,n}` whose `{` was just
+/// consumed and which ends with `close`.
+///
+/// Returns the interval in native syntax and the index after `close`, or
+/// `None` if `chars` does not continue with one.
+fn interval(chars: &[char], i: usize, close: &[char]) -> Option<(String, usize)> {
+    let digits = |from: usize| {
+        chars[from.min(chars.len())..]
+            .iter()
+            .take_while(|ch| ch.is_ascii_digit())
+            .count()
+    };
+    let min = digits(i);
+    let mut end = i + min;
+    if chars.get(end) == Some(&',') {
+        let max = digits(end + 1);
+        if min == 0 && max == 0 {
+            return None;
+        }
+        end += 1 + max;
+    } else if min == 0 {
+        return None;
+    }
+    if !chars[end.min(chars.len())..].starts_with(close) {
+        return None;
+    }
+    let mut interval = String::from("{");
+    if min == 0 {
+        interval.push('0');
+    }
+    interval.extend(&chars[i..end]);
+    interval.push('}');
+    Some((interval, end + close.len()))
+}
+
+/// Copies a bracket expression whose `[` was just consumed and returns the
+/// index after its closing `]`.
+///
+/// A backslash is literal inside POSIX brackets, so it is escaped for the
+/// native parser, `[:name:]` classes are replaced by their ranges, and the
+/// single-character collating symbols `[.x.]` and equivalence classes
+/// `[=x=]` by that character. An unknown class is an error, while an
+/// unterminated bracket is copied as is and left for the parser to report.
+fn copy_bracket(chars: &[char], mut i: usize, out: &mut String) -> Result<usize, ParseError> {
+    out.push('[');
+    if chars.get(i) == Some(&'^') {
+        out.push('^');
+        i += 1;
+    }
+    if chars.get(i) == Some(&']') {
+        out.push(']');
+        i += 1;
+    }
+
+    while let Some(&ch) = chars.get(i) {
+        i += 1;
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '[' if chars.get(i) == Some(&':') => match posix_class(chars, i + 1)? {
+                Some((ranges, end)) => {
+                    push_ranges(out, ranges);
+                    i = end;
+                }
+                None => out.push(ch),
+            },
+            '[' if matches!(chars.get(i), Some('.' | '=')) => match collating_element(chars, i) {
+                Some((element, end)) => {
+                    out.push_str(&format!("\\x{{{:X}}}", u32::from(element)));
+                    i = end;
+                }
+                None => out.push(ch),
+            },
+            ']' => {
+                out.push(']');
+                break;
+            }
+            _ => out.push(ch),
+        }
+    }
+
+    Ok(i)
+}
+
+/// Appends `ranges` as native class ranges.
+fn push_ranges(out: &mut String, ranges: &[(u8, u8)]) {
+    for &(start, end) in ranges {
+        out.push_str(&format!("\\x{start:02X}-\\x{end:02X}"));
+    }
+}
+
+/// Returns the ranges of the POSIX class `name`.
+fn class_ranges(name: &str) -> Option<ByteRanges> {
+    POSIX_CLASSES
+        .iter()
+        .find(|(class, _)| *class == name)
+        .map(|(_, ranges)| *ranges)
+}
+
+/// Looks up the class named from `i` up to `:]`, returning its ranges
+/// and the index after `:]`, or `None` without a `:]`.
+fn posix_class(chars: &[char], i: usize) -> Result<Option<(ByteRanges, usize)>, ParseError> {
+    let Some(len) = chars[i..].iter().position(|&ch| ch == ':') else {
+        return Ok(None);
+    };
+    if chars.get(i + len + 1) != Some(&']') {
+        return Ok(None);
+    }
+    let name: String = chars[i..i + len].iter().collect();
+    let ranges = class_ranges(&name).ok_or(ParseError::InvalidCharClass)?;
+    Ok(Some((ranges, i + len + 2)))
+}
+
+/// Reads the one-character collating symbol `.x.]` or equivalence class
+/// `=x=]` starting at `i`, returning the character and the index after
+/// the `]`.
+fn collating_element(chars: &[char], i: usize) -> Option<(char, usize)> {
+    let delimiter = chars[i];
+    let element = *chars.get(i + 1)?;
+    (chars.get(i + 2) == Some(&delimiter) && chars.get(i + 3) == Some(&']'))
+        .then_some((element, i + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Syntax, to_native};
+    use crate::engine::parser::ParseError;
+
+    fn native(pattern: &str, syntax: Syntax) -> String {
+        to_native(pattern, syntax).unwrap().into_owned()
+    }
+
+    #[test]
+    fn test_perl_is_unchanged() {
+        assert_eq!(native("a+(?R)\\K", Syntax::Perl), "a+(?R)\\K");
+    }
+
+    #[test]
+    fn test_basic_operators_and_literals() {
+        assert_eq!(native("\\(ab\\)\\1", Syntax::Basic), "(ab)\\1");
+        assert_eq!(native("a\\{2,3\\}", Syntax::Basic), "a{2,3}");
+        assert_eq!(native("a\\|b", Syntax::Basic), "a|b");
+        assert_eq!(native("a\\+b\\?", Syntax::Basic), "a+b?");
+        assert_eq!(
+            native("a+b?c|(d){e}", Syntax::Basic),
+            "a\\+b\\?c\\|\\(d\\)\\{e\\}"
+        );
+    }
+
+    #[test]
+    fn test_basic_intervals() {
+        assert_eq!(native("x\\{,2\\}", Syntax::Basic), "x{0,2}");
+        assert_eq!(native("x\\{2,\\}", Syntax::Basic), "x{2,}");
+        assert_eq!(native("\\{2\\}", Syntax::Basic), "\\{2\\}");
+        assert_eq!(native("a\\}", Syntax::Basic), "a\\}");
+        for pattern in ["x\\{,\\}", "x\\{a\\}", "x\\{2"] {
+            assert_eq!(
+                to_native(pattern, Syntax::Basic),
+                Err(ParseError::InvalidRepeatOp),
+                "{pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_basic_stray_brackets() {
+        assert_eq!(native("a]", Syntax::Basic), "a\\]");
+        assert_eq!(native("[a\\]]", Syntax::Basic), "[a\\\\]\\]");
+    }
+
+    #[test]
+    fn test_basic_stacked_repeats() {
+        assert_eq!(native("a**", Syntax::Basic), "(?u:a*)*");
+        assert_eq!(native("a*\\?", Syntax::Basic), "(?u:a*)?");
+        assert_eq!(native("\\(ab\\)*\\{2\\}", Syntax::Basic), "(?u:(ab)*){2}");
+        assert_eq!(native("[ab]\\+*", Syntax::Basic), "(?u:[ab]+)*");
+    }
+
+    #[test]
+    fn test_basic_gnu_escapes() {
+        assert_eq!(
+            native("\\w", Syntax::Basic),
+            "[\\x30-\\x39\\x41-\\x5A\\x61-\\x7A_]"
+        );
+        assert_eq!(native("\\S", Syntax::Basic), "[^\\x09-\\x0D\\x20-\\x20]");
+        for esc in ['b', 'B', '<', '>', '`', '\''] {
+            assert_eq!(
+                to_native(&format!("a\\{esc}"), Syntax::Basic),
+                Err(ParseError::UnsupportedEscape(esc))
+            );
+        }
+    }
+
+    #[test]
+    fn test_basic_context_dependent_literals() {
+        assert_eq!(native("*a", Syntax::Basic), "\\*a");
+        assert_eq!(native("^*a", Syntax::Basic), "^\\*a");
+        assert_eq!(native("\\(*a\\)", Syntax::Basic), "(\\*a)");
+        assert_eq!(native("a^b$c", Syntax::Basic), "a\\^b\\$c");
+        assert_eq!(native("^a$", Syntax::Basic), "^a$");
+        assert_eq!(native("\\(^a$\\)", Syntax::Basic), "(^a$)");
+        assert_eq!(native("a$\\|^b", Syntax::Basic), "a$|^b");
+    }
+
+    #[test]
+    fn test_bracket_expressions_are_copied() {
+        assert_eq!(native("[]+(]*", Syntax::Basic), "[]+(]*");
+        assert_eq!(native("[^\\]", Syntax::Basic), "[^\\\\]");
+        assert_eq!(native("[\\n]", Syntax::Extended), "[\\\\n]");
+    }
+
+    #[test]
+    fn test_posix_classes() {
+        for syntax in [Syntax::Basic, Syntax::Extended] {
+            assert_eq!(native("[[:digit:]]", syntax), "[\\x30-\\x39]");
+            assert_eq!(native("a[^[:upper:]_]", syntax), "a[^\\x41-\\x5A_]");
+            assert_eq!(
+                native("[[:blank:][:xdigit:]-]", syntax),
+                "[\\x09-\\x09\\x20-\\x20\\x30-\\x39\\x41-\\x46\\x61-\\x66-]"
+            );
+            assert_eq!(
+                to_native("[[:word:]]", syntax),
+                Err(ParseError::InvalidCharClass)
+            );
+            assert_eq!(native("[[:]", syntax), "[[:]");
+        }
+    }
+
+    #[test]
+    fn test_extended_literal_brace() {
+        assert_eq!(native("b{", Syntax::Extended), "b\\{");
+        assert_eq!(native("b{x}", Syntax::Extended), "b\\{x\\}");
+        assert_eq!(native("b{,}", Syntax::Extended), "b\\{,\\}");
+        assert_eq!(native("b{2,}c{,3}", Syntax::Extended), "b{2,}c{0,3}");
+        assert_eq!(native("b{1,2", Syntax::Extended), "b\\{1,2");
+    }
+
+    #[test]
+    fn test_extended_stray_brackets() {
+        assert_eq!(native("a]b}", Syntax::Extended), "a\\]b\\}");
+        assert_eq!(native("[a\\]]", Syntax::Extended), "[a\\\\]\\]");
+    }
+
+    #[test]
+    fn test_extended_stacked_repeats() {
+        assert_eq!(native("a**", Syntax::Extended), "(?u:a*)*");
+        assert_eq!(native("a+?", Syntax::Extended), "(?u:a+)?");
+        assert_eq!(native("(a|b)?*c", Syntax::Extended), "(?u:(a|b)?)*c");
+        assert_eq!(native("a{2}?b", Syntax::Extended), "(?u:a{2})?b");
+        assert_eq!(native("a*?+", Syntax::Extended), "(?u:(?u:a*)?)+");
+    }
+
+    #[test]
+    fn test_extended_gnu_escapes() {
+        assert_eq!(
+            native("\\W", Syntax::Extended),
+            "[^\\x30-\\x39\\x41-\\x5A\\x61-\\x7A_]"
+        );
+        assert_eq!(native("\\s", Syntax::Extended), "[\\x09-\\x0D\\x20-\\x20]");
+        assert_eq!(
+            to_native("\\<a\\>", Syntax::Extended),
+            Err(ParseError::UnsupportedEscape('<'))
+        );
+        assert_eq!(
+            to_native("a\\b", Syntax::Extended),
+            Err(ParseError::UnsupportedEscape('b'))
+        );
+    }
+
+    #[test]
+    fn test_collating_elements() {
+        for syntax in [Syntax::Basic, Syntax::Extended] {
+            assert_eq!(native("[[.x.]]", syntax), "[\\x{78}]");
+            assert_eq!(native("[[=x=]y]", syntax), "[\\x{78}y]");
+            assert_eq!(native("[[.-.]a]", syntax), "[\\x{2D}a]");
+            assert_eq!(native("[[.a.]-c]", syntax), "[\\x{61}-c]");
+            assert_eq!(native("[[.ab.]]", syntax), "[[.ab.]\\]");
+        }
+    }
+
+    #[test]
+    fn test_extended_drops_perl_extensions() {
+        assert_eq!(native("(a|b)+c{2}", Syntax::Extended), "(a|b)+c{2}");
+        assert_eq!(native("a\\Kb\\.", Syntax::Extended), "aKb\\.");
+        assert_eq!(native("(?R)", Syntax::Extended), "(\\?R)");
+        assert_eq!(native("\\x41", Syntax::Extended), "x41");
+        assert_eq!(native("*a|+b", Syntax::Extended), "\\*a|\\+b");
+    }
+}
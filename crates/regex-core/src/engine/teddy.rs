@@ -0,0 +1,331 @@
+//! Teddy: packed search for several short literals at once.
+//!
+//! Each literal goes into one of 8 buckets, and each of the first 1 to 3
+//! bytes of a literal (as many as the shortest one has) sets its bucket bit
+//! in two 16-entry tables, indexed by the low and high nibble of the byte.
+//! Looking up the nibbles of the haystack bytes at offsets `0..m` of a
+//! candidate start and and-ing the results leaves the buckets whose
+//! literals agree with those bytes; only their literals are then compared
+//! in full. A byte shuffle performs the 16 or 32 lookups of a vector at
+//! once, so the automaton is only built when the `simd` feature is enabled
+//! and the CPU supports SSSE3 (AVX2 is used when available).
+
+/// Most literals a searcher is built for.
+const MAX_LITERALS: usize = 64;
+
+/// Number of buckets, one per bit of a table entry.
+const BUCKETS: usize = 8;
+
+/// Packed multi-literal searcher; see the module documentation.
+#[derive(Debug)]
+pub(crate) struct Teddy {
+    literals: Vec<Vec<u8>>,
+    /// Literals of each bucket.
+    buckets: [Vec<usize>; BUCKETS],
+    /// Low- and high-nibble tables of each fingerprint byte.
+    masks: Vec<[[u8; 16]; 2]>,
+}
+
+impl Teddy {
+    /// Builds the searcher of `literals`, or returns `None` if there are
+    /// none, too many, an empty one, or no SIMD support.
+    pub(crate) fn new(literals: &[String]) -> Option<Self> {
+        if !has_ssse3()
+            || literals.is_empty()
+            || literals.len() > MAX_LITERALS
+            || literals.iter().any(String::is_empty)
+        {
+            return None;
+        }
+
+        let fingerprint_len = literals.iter().map(String::len).min()?.min(3);
+        let mut buckets: [Vec<usize>; BUCKETS] = Default::default();
+        let mut masks = vec![[[0; 16]; 2]; fingerprint_len];
+        for (id, literal) in literals.iter().enumerate() {
+            let bucket = id % BUCKETS;
+            buckets[bucket].push(id);
+            for (mask, &byte) in masks.iter_mut().zip(literal.as_bytes()) {
+                mask[0][usize::from(byte & 0xF)] |= 1 << bucket;
+                mask[1][usize::from(byte >> 4)] |= 1 << bucket;
+            }
+        }
+
+        Some(Self {
+            literals: literals
+                .iter()
+                .map(|literal| literal.as_bytes().to_vec())
+                .collect(),
+            buckets,
+            masks,
+        })
+    }
+
+    /// Returns the leftmost position at or after `at` where a literal
+    /// starts in `haystack`.
+    pub(crate) fn find_at(&self, haystack: &[u8], at: usize) -> Option<usize> {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        let at = match x86::find_at(self, haystack, at) {
+            Ok(pos) => return Some(pos),
+            Err(resume) => resume,
+        };
+
+        (at..haystack.len()).find(|&pos| self.verify(haystack, pos, self.candidates(haystack, pos)))
+    }
+
+    /// Returns the buckets whose fingerprint agrees with `haystack` at `pos`.
+    fn candidates(&self, haystack: &[u8], pos: usize) -> u8 {
+        self.masks
+            .iter()
+            .enumerate()
+            .fold(u8::MAX, |buckets, (offset, [low, high])| {
+                match haystack.get(pos + offset) {
+                    Some(&byte) => {
+                        buckets & low[usize::from(byte & 0xF)] & high[usize::from(byte >> 4)]
+                    }
+                    None => 0,
+                }
+            })
+    }
+
+    /// Returns whether a literal of one of `buckets` starts at `pos`.
+    fn verify(&self, haystack: &[u8], pos: usize, buckets: u8) -> bool {
+        (0..BUCKETS)
+            .filter(|bucket| buckets & (1 << bucket) != 0)
+            .flat_map(|bucket| &self.buckets[bucket])
+            .any(|&id| haystack[pos..].starts_with(&self.literals[id]))
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn has_ssse3() -> bool {
+    is_x86_feature_detected!("ssse3")
+}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn has_ssse3() -> bool {
+    false
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::{
+        __m128i, __m256i, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+        _mm_set1_epi8, _mm_setzero_si128, _mm_shuffle_epi8, _mm_srli_epi16, _mm_storeu_si128,
+        _mm256_and_si256, _mm256_broadcastsi128_si256, _mm256_cmpeq_epi8, _mm256_loadu_si256,
+        _mm256_movemask_epi8, _mm256_set1_epi8, _mm256_setzero_si256, _mm256_shuffle_epi8,
+        _mm256_srli_epi16, _mm256_storeu_si256,
+    };
+
+    use super::Teddy;
+
+    /// Scans whole vectors from `at`, returning the match found or the
+    /// position where the scalar search must resume.
+    pub(super) fn find_at(teddy: &Teddy, haystack: &[u8], at: usize) -> Result<usize, usize> {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2.
+            return unsafe { find_at_avx2(teddy, haystack, at) };
+        }
+        // SAFETY: `Teddy::new` checked for SSSE3.
+        unsafe { find_at_ssse3(teddy, haystack, at) }
+    }
+
+    #[target_feature(enable = "ssse3")]
+    fn find_at_ssse3(teddy: &Teddy, haystack: &[u8], at: usize) -> Result<usize, usize> {
+        let tables: Vec<[__m128i; 2]> = teddy
+            .masks
+            .iter()
+            // SAFETY: each table is 16 bytes long.
+            .map(|tables| tables.map(|table| unsafe { _mm_loadu_si128(table.as_ptr().cast()) }))
+            .collect();
+        let nibble = _mm_set1_epi8(0xF);
+        let reach = teddy.masks.len() - 1;
+
+        let mut i = at;
+        while i + reach + 16 <= haystack.len() {
+            let mut buckets = _mm_set1_epi8(-1);
+            for (offset, [low, high]) in tables.iter().enumerate() {
+                // SAFETY: the 16 bytes at `i + offset` end at or before
+                // `i + reach + 16`, which is in bounds.
+                let chunk =
+                    unsafe { _mm_loadu_si128(haystack.as_ptr().add(i + offset).cast::<__m128i>()) };
+                let low = _mm_shuffle_epi8(*low, _mm_and_si128(chunk, nibble));
+                let high = _mm_shuffle_epi8(*high, _mm_and_si128(_mm_srli_epi16(chunk, 4), nibble));
+                buckets = _mm_and_si128(buckets, _mm_and_si128(low, high));
+            }
+            let empty = _mm_movemask_epi8(_mm_cmpeq_epi8(buckets, _mm_setzero_si128())) as u32;
+            let mut mask = !empty & 0xFFFF;
+            if mask != 0 {
+                let mut lanes = [0u8; 16];
+                // SAFETY: `lanes` is 16 bytes long.
+                unsafe { _mm_storeu_si128(lanes.as_mut_ptr().cast(), buckets) };
+                while mask != 0 {
+                    let lane = mask.trailing_zeros() as usize;
+                    if teddy.verify(haystack, i + lane, lanes[lane]) {
+                        return Ok(i + lane);
+                    }
+                    mask &= mask - 1;
+                }
+            }
+            i += 16;
+        }
+        Err(i)
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn find_at_avx2(teddy: &Teddy, haystack: &[u8], at: usize) -> Result<usize, usize> {
+        let tables: Vec<[__m256i; 2]> = teddy
+            .masks
+            .iter()
+            .map(|tables| {
+                tables.map(|table| {
+                    // SAFETY: each table is 16 bytes long.
+                    let table = unsafe { _mm_loadu_si128(table.as_ptr().cast()) };
+                    // Shuffles look up within each 128-bit half.
+                    _mm256_broadcastsi128_si256(table)
+                })
+            })
+            .collect();
+        let nibble = _mm256_set1_epi8(0xF);
+        let reach = teddy.masks.len() - 1;
+
+        let mut i = at;
+        while i + reach + 32 <= haystack.len() {
+            let mut buckets = _mm256_set1_epi8(-1);
+            for (offset, [low, high]) in tables.iter().enumerate() {
+                // SAFETY: the 32 bytes at `i + offset` end at or before
+                // `i + reach + 32`, which is in bounds.
+                let chunk = unsafe {
+                    _mm256_loadu_si256(haystack.as_ptr().add(i + offset).cast::<__m256i>())
+                };
+                let low = _mm256_shuffle_epi8(*low, _mm256_and_si256(chunk, nibble));
+                let high = _mm256_shuffle_epi8(
+                    *high,
+                    _mm256_and_si256(_mm256_srli_epi16(chunk, 4), nibble),
+                );
+                buckets = _mm256_and_si256(buckets, _mm256_and_si256(low, high));
+            }
+            let empty =
+                _mm256_movemask_epi8(_mm256_cmpeq_epi8(buckets, _mm256_setzero_si256())) as u32;
+            let mut mask = !empty;
+            if mask != 0 {
+                let mut lanes = [0u8; 32];
+                // SAFETY: `lanes` is 32 bytes long.
+                unsafe { _mm256_storeu_si256(lanes.as_mut_ptr().cast(), buckets) };
+                while mask != 0 {
+                    let lane = mask.trailing_zeros() as usize;
+                    if teddy.verify(haystack, i + lane, lanes[lane]) {
+                        return Ok(i + lane);
+                    }
+                    mask &= mask - 1;
+                }
+            }
+            i += 32;
+        }
+        Err(i)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{Teddy, find_at_avx2, find_at_ssse3};
+
+        #[test]
+        fn test_vector_widths_agree() {
+            let literals = ["ab".to_string(), "日本".to_string(), "xyz".to_string()];
+            let Some(teddy) = Teddy::new(&literals) else {
+                return;
+            };
+            let haystack = format!(
+                "{}ab{}日本{}xyz",
+                "-".repeat(40),
+                "+".repeat(17),
+                "=".repeat(33)
+            );
+            let haystack = haystack.as_bytes();
+            for at in 0..=haystack.len() {
+                let expected = (at..haystack.len()).find(|&pos| {
+                    literals
+                        .iter()
+                        .any(|literal| haystack[pos..].starts_with(literal.as_bytes()))
+                });
+                let finish = |found: Result<usize, usize>| {
+                    found.map_or_else(|resume| teddy.find_at(haystack, resume), Some)
+                };
+                // SAFETY: `Teddy::new` checked for SSSE3.
+                assert_eq!(
+                    finish(unsafe { find_at_ssse3(&teddy, haystack, at) }),
+                    expected
+                );
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: the CPU supports AVX2.
+                    assert_eq!(
+                        finish(unsafe { find_at_avx2(&teddy, haystack, at) }),
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Teddy;
+
+    fn literals(literals: &[&str]) -> Vec<String> {
+        literals.iter().map(|literal| literal.to_string()).collect()
+    }
+
+    #[test]
+    fn test_new_rejects_unsupported_sets() {
+        assert!(Teddy::new(&[]).is_none());
+        assert!(Teddy::new(&literals(&["a", ""])).is_none());
+        let many: Vec<String> = (0..65).map(|i| format!("k{i}")).collect();
+        assert!(Teddy::new(&many).is_none());
+    }
+
+    #[test]
+    fn test_find_at_agrees_with_naive_search() {
+        let literal_sets: [&[&str]; 5] = [
+            &["needle"],
+            &["foo", "bar", "baz"],
+            &["a", "bcd", "日本"],
+            &[
+                "error", "warn", "fatal", "panic", "abort", "fail", "crit", "emerg", "alert",
+            ],
+            &["xyz", "xy"],
+        ];
+        let mut haystacks = vec![
+            String::new(),
+            "no literals at all in this rather long line of text".to_string(),
+            "a line that ends with the needle".to_string(),
+        ];
+        for len in [15, 16, 17, 31, 32, 33, 70] {
+            let filler = "-".repeat(len);
+            haystacks.push(format!("{filler}foo{filler}bcd{filler}"));
+            haystacks.push(format!("{filler}日本 panic xyz{filler}alert"));
+        }
+
+        for set in literal_sets {
+            let literals = literals(set);
+            // Without SIMD support there is nothing to compare.
+            let Some(teddy) = Teddy::new(&literals) else {
+                continue;
+            };
+            for haystack in &haystacks {
+                let bytes = haystack.as_bytes();
+                for at in 0..=bytes.len() {
+                    let expected = (at..bytes.len()).find(|&pos| {
+                        literals
+                            .iter()
+                            .any(|literal| bytes[pos..].starts_with(literal.as_bytes()))
+                    });
+                    assert_eq!(
+                        teddy.find_at(bytes, at),
+                        expected,
+                        "{set:?} in {haystack:?} at {at}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,550 @@
+//! UTF-8 byte automata compiled from character programs.
+//!
+//! Each `CharClass` is rewritten as an alternation of byte-range sequences
+//! that together match exactly the UTF-8 encodings of the class members,
+//! so an engine running the resulting `ByteInstruction` program reads the
+//! haystack as `&[u8]` without decoding it into characters.
+//!
+//! A `ByteClass` becomes one single-byte range per member range, so raw
+//! byte classes match anywhere, even inside an encoded character.
+//!
+//! Assertions only look at `\n`, `\r` and ASCII word characters, all of
+//! which are single bytes, so they are evaluated exactly on bytes too.
+
+use crate::engine::{
+    ast::{ByteClass, CharClass, Predicate},
+    evaluator::is_word_char,
+    instruction::Instruction,
+};
+
+/// Largest Unicode scalar value encoded with 1, 2 and 3 bytes.
+const ENCODED_LENGTH_LIMITS: [u32; 3] = [0x7F, 0x7FF, 0xFFFF];
+/// First and last surrogate code points, which have no UTF-8 encoding.
+const SURROGATES: (u32, u32) = (0xD800, 0xDFFF);
+
+/// Executable instructions of a byte program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ByteInstruction {
+    /// Match one byte in the inclusive range.
+    ByteRange(u8, u8),
+    /// Evaluate a zero-width assertion.
+    Assert(Predicate),
+    /// Store the current position in a capture slot: `2 * n` is the start
+    /// of group `n`, `2 * n + 1` its end.
+    Save(usize),
+    /// Branch execution into two instruction addresses.
+    Split(usize, usize),
+    /// Unconditional jump.
+    Jump(usize),
+    /// Never matches; an empty character class.
+    Fail,
+    /// Successful match terminator.
+    Match,
+}
+
+/// Returns the byte-range sequences matching the UTF-8 encodings of the
+/// members of `class`, in ascending order.
+pub(crate) fn class_sequences(class: &CharClass) -> Vec<Vec<(u8, u8)>> {
+    let mut ranges: Vec<(u32, u32)> = class
+        .ranges
+        .iter()
+        .map(|range| (range.start as u32, range.end as u32))
+        .filter(|(start, end)| start <= end)
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    if class.negated {
+        let mut complement = Vec::with_capacity(merged.len() + 1);
+        let mut next = 0;
+        for (start, end) in merged {
+            if next < start {
+                complement.push((next, start - 1));
+            }
+            next = end + 1;
+        }
+        if next <= char::MAX as u32 {
+            complement.push((next, char::MAX as u32));
+        }
+        merged = complement;
+    }
+
+    let mut sequences = Vec::new();
+    for (start, end) in merged {
+        range_sequences(start, end, &mut sequences);
+    }
+    sequences
+}
+
+/// Returns the one-byte sequences matching the members of `class`.
+pub(crate) fn byte_class_sequences(class: &ByteClass) -> Vec<Vec<(u8, u8)>> {
+    let mut ranges: Vec<(u8, u8)> = class
+        .ranges
+        .iter()
+        .map(|range| (range.start, range.end))
+        .filter(|(start, end)| start <= end)
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u8, u8)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if u16::from(start) <= u16::from(*last_end) + 1 => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    if class.negated {
+        let mut complement = Vec::with_capacity(merged.len() + 1);
+        let mut next: u16 = 0;
+        for (start, end) in merged {
+            if next < u16::from(start) {
+                complement.push((next as u8, start - 1));
+            }
+            next = u16::from(end) + 1;
+        }
+        if next <= u16::from(u8::MAX) {
+            complement.push((next as u8, u8::MAX));
+        }
+        merged = complement;
+    }
+
+    merged.into_iter().map(|range| vec![range]).collect()
+}
+
+/// Appends the byte-range sequences of the scalar values in `start..=end`.
+fn range_sequences(start: u32, end: u32, sequences: &mut Vec<Vec<(u8, u8)>>) {
+    let mut pending = vec![(start, end)];
+
+    'ranges: while let Some((start, mut end)) = pending.pop() {
+        if start > end {
+            continue;
+        }
+        // Drop the surrogates, splitting a range that spans them.
+        if start <= SURROGATES.1 && SURROGATES.0 <= end {
+            pending.push((SURROGATES.1 + 1, end));
+            pending.push((start, SURROGATES.0.saturating_sub(1)));
+            if start >= SURROGATES.0 {
+                pending.pop();
+            }
+            continue;
+        }
+
+        loop {
+            // Split where the encoded length changes.
+            for limit in ENCODED_LENGTH_LIMITS {
+                if start <= limit && limit < end {
+                    pending.push((limit + 1, end));
+                    end = limit;
+                }
+            }
+            if end <= 0x7F {
+                sequences.push(vec![(start as u8, end as u8)]);
+                continue 'ranges;
+            }
+
+            // Split until every continuation byte spans its full range
+            // wherever a preceding byte varies.
+            let mut was_split = false;
+            for continuation_bytes in 1..4 {
+                let mask = (1u32 << (6 * continuation_bytes)) - 1;
+                if start & !mask != end & !mask {
+                    if start & mask != 0 {
+                        pending.push(((start | mask) + 1, end));
+                        end = start | mask;
+                        was_split = true;
+                        break;
+                    }
+                    if end & mask != mask {
+                        pending.push((end & !mask, end));
+                        end = (end & !mask) - 1;
+                        was_split = true;
+                        break;
+                    }
+                }
+            }
+            if !was_split {
+                break;
+            }
+        }
+
+        let (mut start_bytes, mut end_bytes) = ([0; 4], [0; 4]);
+        let start_bytes = encode(start, &mut start_bytes);
+        let end_bytes = encode(end, &mut end_bytes);
+        sequences.push(
+            start_bytes
+                .iter()
+                .zip(end_bytes)
+                .map(|(&low, &high)| (low, high))
+                .collect(),
+        );
+    }
+}
+
+/// Encodes the scalar value `value`, which is never a surrogate here.
+fn encode(value: u32, buffer: &mut [u8; 4]) -> &[u8] {
+    let c = char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER);
+    c.encode_utf8(buffer).as_bytes()
+}
+
+/// Compiles a character program into a byte program.
+///
+/// Returns `None` if `inst` uses backreferences, subroutine calls or `\X`,
+/// which need decoded characters.
+pub(crate) fn compile_utf8(inst: &[Instruction]) -> Option<Vec<ByteInstruction>> {
+    // Lay out every instruction first so targets can be translated.
+    let mut sequences = Vec::with_capacity(inst.len());
+    let mut addresses = Vec::with_capacity(inst.len() + 1);
+    let mut next = 0;
+    for instruction in inst {
+        addresses.push(next);
+        next += match instruction {
+            Instruction::CharClass(class) => {
+                let class_sequences = class_sequences(class);
+                let len = class_block_len(&class_sequences);
+                sequences.push(class_sequences);
+                len
+            }
+            Instruction::ByteClass(class) => {
+                let class_sequences = byte_class_sequences(class);
+                let len = class_block_len(&class_sequences);
+                sequences.push(class_sequences);
+                len
+            }
+            Instruction::Backref(_)
+            | Instruction::Call(_)
+            | Instruction::Return
+            | Instruction::GraphemeCluster => return None,
+            _ => 1,
+        };
+    }
+    addresses.push(next);
+
+    let mut program = Vec::with_capacity(next);
+    let mut sequences = sequences.iter();
+    for (pc, instruction) in inst.iter().enumerate() {
+        let instruction = match instruction {
+            Instruction::CharClass(_) | Instruction::ByteClass(_) => {
+                let class_sequences = sequences.next()?;
+                push_class_block(class_sequences, addresses[pc + 1], &mut program);
+                continue;
+            }
+            Instruction::Assert(predicate) => ByteInstruction::Assert(*predicate),
+            Instruction::SaveStart(index) => ByteInstruction::Save(2 * index),
+            Instruction::SaveEnd(index) => ByteInstruction::Save(2 * index + 1),
+            Instruction::ResetMatchStart => ByteInstruction::Save(0),
+            Instruction::Split(left, right) => {
+                ByteInstruction::Split(*addresses.get(*left)?, *addresses.get(*right)?)
+            }
+            Instruction::Jump(addr) => ByteInstruction::Jump(*addresses.get(*addr)?),
+            Instruction::Match => ByteInstruction::Match,
+            Instruction::Backref(_)
+            | Instruction::Call(_)
+            | Instruction::Return
+            | Instruction::GraphemeCluster => return None,
+        };
+        program.push(instruction);
+    }
+    Some(program)
+}
+
+/// Returns the number of instructions `push_class_block` emits.
+fn class_block_len(sequences: &[Vec<(u8, u8)>]) -> usize {
+    let ranges: usize = sequences.iter().map(Vec::len).sum();
+    // A `Split` and a closing `Jump` around every sequence but the last.
+    (ranges + 2 * sequences.len().saturating_sub(1)).max(1)
+}
+
+/// Emits the alternation of `sequences`, continuing at `exit` after any of
+/// them matches.
+fn push_class_block(sequences: &[Vec<(u8, u8)>], exit: usize, program: &mut Vec<ByteInstruction>) {
+    let Some((last, rest)) = sequences.split_last() else {
+        program.push(ByteInstruction::Fail);
+        return;
+    };
+    let ranges = |sequence: &[(u8, u8)]| {
+        sequence
+            .iter()
+            .map(|&(low, high)| ByteInstruction::ByteRange(low, high))
+            .collect::<Vec<_>>()
+    };
+
+    for sequence in rest {
+        let start = program.len() + 1;
+        program.push(ByteInstruction::Split(start, start + sequence.len() + 1));
+        program.extend(ranges(sequence));
+        program.push(ByteInstruction::Jump(exit));
+    }
+    program.extend(ranges(last));
+}
+
+/// Partition of the bytes into classes that a byte program cannot tell
+/// apart, so transition tables need one column per class.
+#[derive(Debug)]
+pub(crate) struct ByteClasses {
+    classes: [u8; 256],
+    /// One byte of each class.
+    representatives: Vec<u8>,
+}
+
+impl ByteClasses {
+    pub(crate) fn new(program: &[ByteInstruction]) -> Self {
+        let ranges: Vec<(u8, u8)> = program
+            .iter()
+            .filter_map(|instruction| match instruction {
+                ByteInstruction::ByteRange(low, high) => Some((*low, *high)),
+                _ => None,
+            })
+            .collect();
+
+        let mut classes = [0; 256];
+        let mut signatures: Vec<(Vec<bool>, ByteKind)> = Vec::new();
+        let mut representatives = Vec::new();
+        for byte in 0..=u8::MAX {
+            let signature = (
+                ranges
+                    .iter()
+                    .map(|&(low, high)| low <= byte && byte <= high)
+                    .collect(),
+                ByteKind::of(byte),
+            );
+            let class = match signatures.iter().position(|known| *known == signature) {
+                Some(class) => class,
+                None => {
+                    signatures.push(signature);
+                    representatives.push(byte);
+                    signatures.len() - 1
+                }
+            };
+            classes[usize::from(byte)] = class as u8;
+        }
+
+        Self {
+            classes,
+            representatives,
+        }
+    }
+
+    /// Returns the number of classes.
+    pub(crate) fn len(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Returns the class of `byte`.
+    pub(crate) fn get(&self, byte: u8) -> usize {
+        usize::from(self.classes[usize::from(byte)])
+    }
+
+    /// Returns one byte of each class, in class order.
+    pub(crate) fn representatives(&self) -> &[u8] {
+        &self.representatives
+    }
+}
+
+/// How assertions see a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ByteKind {
+    LineFeed,
+    CarriageReturn,
+    Word,
+    Other,
+}
+
+impl ByteKind {
+    pub(crate) fn of(byte: u8) -> Self {
+        match byte {
+            b'\n' => ByteKind::LineFeed,
+            b'\r' => ByteKind::CarriageReturn,
+            byte if byte.is_ascii() && is_word_char(char::from(byte)) => ByteKind::Word,
+            _ => ByteKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ByteClasses, ByteInstruction, byte_class_sequences, class_sequences, compile_utf8,
+    };
+    use crate::engine::{
+        ast::{ByteClass, ByteRange, CharClass, CharRange},
+        compiler::compile,
+        parser::parse,
+    };
+
+    fn matches(sequences: &[Vec<(u8, u8)>], bytes: &[u8]) -> bool {
+        sequences.iter().any(|sequence| {
+            sequence.len() == bytes.len()
+                && sequence
+                    .iter()
+                    .zip(bytes)
+                    .all(|(&(low, high), &byte)| low <= byte && byte <= high)
+        })
+    }
+
+    #[test]
+    fn test_class_sequences_match_exactly_the_encodings() {
+        let classes = [
+            CharClass::new(
+                vec![CharRange {
+                    start: 'a',
+                    end: 'z',
+                }],
+                false,
+            ),
+            CharClass::new(
+                vec![CharRange {
+                    start: '\u{7F}',
+                    end: '\u{10400}',
+                }],
+                false,
+            ),
+            CharClass::new(
+                vec![CharRange {
+                    start: '\n',
+                    end: '\n',
+                }],
+                true,
+            ),
+            CharClass::new(
+                vec![
+                    CharRange {
+                        start: 'é',
+                        end: 'é',
+                    },
+                    CharRange {
+                        start: '\u{D7FF}',
+                        end: '\u{E000}',
+                    },
+                ],
+                true,
+            ),
+        ];
+        let samples = [
+            '\0',
+            '\n',
+            'a',
+            'z',
+            '\u{7F}',
+            '\u{80}',
+            'é',
+            '\u{7FF}',
+            '\u{800}',
+            '\u{D7FF}',
+            '\u{E000}',
+            '\u{FFFF}',
+            '\u{10000}',
+            '\u{103FF}',
+            '\u{10400}',
+            '\u{10401}',
+            '\u{10FFFF}',
+        ];
+        for class in &classes {
+            let sequences = class_sequences(class);
+            for c in samples {
+                let in_range = class
+                    .ranges
+                    .iter()
+                    .any(|range| range.start <= c && c <= range.end);
+                let mut buffer = [0; 4];
+                let bytes = c.encode_utf8(&mut buffer).as_bytes();
+                assert_eq!(
+                    matches(&sequences, bytes),
+                    in_range != class.negated,
+                    "{class:?} on {c:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_class_sequences_for_any_char() {
+        let any = CharClass::new(Vec::new(), true);
+        assert_eq!(
+            class_sequences(&any),
+            vec![
+                vec![(0x00, 0x7F)],
+                vec![(0xC2, 0xDF), (0x80, 0xBF)],
+                vec![(0xE0, 0xE0), (0xA0, 0xBF), (0x80, 0xBF)],
+                vec![(0xE1, 0xEC), (0x80, 0xBF), (0x80, 0xBF)],
+                vec![(0xED, 0xED), (0x80, 0x9F), (0x80, 0xBF)],
+                vec![(0xEE, 0xEF), (0x80, 0xBF), (0x80, 0xBF)],
+                vec![(0xF0, 0xF0), (0x90, 0xBF), (0x80, 0xBF), (0x80, 0xBF)],
+                vec![(0xF1, 0xF3), (0x80, 0xBF), (0x80, 0xBF), (0x80, 0xBF)],
+                vec![(0xF4, 0xF4), (0x80, 0x8F), (0x80, 0xBF), (0x80, 0xBF)],
+            ]
+        );
+        assert!(class_sequences(&CharClass::new(Vec::new(), false)).is_empty());
+    }
+
+    #[test]
+    fn test_byte_class_sequences() {
+        let class = ByteClass::new(
+            vec![
+                ByteRange {
+                    start: 0xF0,
+                    end: 0xFF,
+                },
+                ByteRange { start: 0, end: 9 },
+                ByteRange {
+                    start: 0x05,
+                    end: 0x0A,
+                },
+            ],
+            false,
+        );
+        assert_eq!(
+            byte_class_sequences(&class),
+            vec![vec![(0x00, 0x0A)], vec![(0xF0, 0xFF)]]
+        );
+        let negated = ByteClass::new(class.ranges, true);
+        assert_eq!(byte_class_sequences(&negated), vec![vec![(0x0B, 0xEF)]]);
+    }
+
+    #[test]
+    fn test_compile_utf8_relocates_targets() {
+        let inst = compile(&parse("(é|x)*$").unwrap()).unwrap();
+        let program = compile_utf8(&inst).unwrap();
+        for instruction in &program {
+            match instruction {
+                ByteInstruction::Split(left, right) => {
+                    assert!(*left < program.len() && *right < program.len());
+                }
+                ByteInstruction::Jump(addr) => assert!(*addr < program.len()),
+                _ => {}
+            }
+        }
+        assert!(program.contains(&ByteInstruction::ByteRange(0xC3, 0xC3)));
+        assert!(program.contains(&ByteInstruction::ByteRange(0xA9, 0xA9)));
+        assert_eq!(program.last(), Some(&ByteInstruction::Match));
+
+        assert!(compile_utf8(&compile(&parse("(a)\\1").unwrap()).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_byte_classes() {
+        let program = compile_utf8(&compile(&parse("x[a-z]y").unwrap()).unwrap()).unwrap();
+        let classes = ByteClasses::new(&program);
+        // x, y, other lowercase letters, other word bytes, \n, \r, the rest.
+        assert_eq!(classes.len(), 7);
+        assert_eq!(classes.get(b'b'), classes.get(b'q'));
+        assert_eq!(classes.get(b'A'), classes.get(b'7'));
+        assert_eq!(classes.get(b'-'), classes.get(0xF4));
+        assert_ne!(classes.get(b'x'), classes.get(b'b'));
+        assert_ne!(classes.get(b'\n'), classes.get(b'-'));
+        for (class, &byte) in classes.representatives().iter().enumerate() {
+            assert_eq!(classes.get(byte), class);
+        }
+    }
+}
@@ -1,3 +1,4 @@
 //! Error types used by this crate.
 
 pub use crate::engine::{CompileError, EvalError, ParseError, RegexError};
+pub use crate::glob::GlobError;
@@ -0,0 +1,409 @@
+//! Shell glob patterns compiled into a `Regex`.
+//!
+//! Supported syntax:
+//! - `*` matches any sequence of characters and `?` any single character.
+//! - `[abc]`, `[a-z]` and the negated `[!abc]` / `[^abc]` match one character.
+//! - `{a,b}` matches either alternative; alternatives may nest.
+//! - `**` as a whole path component (`**/`, `/**/`, `/**`) matches any
+//!   number of components; elsewhere it behaves like `*`.
+//! - `\` escapes the next character.
+//!
+//! A glob always matches the whole text.
+
+use thiserror::Error;
+
+use crate::{
+    Regex,
+    engine::{Ast, CharClass, CharRange, Predicate},
+    error::RegexError,
+};
+
+/// Path separator that `*`, `?` and `[...]` never match with
+/// `literal_separator`.
+const SEPARATOR: char = '/';
+
+/// Errors returned while translating a glob.
+#[derive(Debug, Error, PartialEq)]
+pub enum GlobError {
+    /// Missing closing `]` for a character class.
+    #[error("missing closing bracket ']'")]
+    MissingBracket,
+    /// Missing closing `}` for an alternation.
+    #[error("missing closing brace '}}'")]
+    MissingBrace,
+    /// Reversed character range (for example, `[z-a]`).
+    #[error("invalid character range")]
+    InvalidRange,
+    /// Trailing `\` at the end of the glob.
+    #[error("trailing backslash")]
+    TrailingBackslash,
+    /// Compiling the translated glob failed.
+    #[error(transparent)]
+    Regex(#[from] RegexError),
+}
+
+/// Configures and builds a `Regex` from a glob.
+#[derive(Debug, Clone)]
+pub struct GlobBuilder {
+    glob: String,
+    literal_separator: bool,
+    case_insensitive: bool,
+}
+
+impl GlobBuilder {
+    /// Creates a builder for `glob` with every option disabled.
+    pub fn new(glob: &str) -> Self {
+        Self {
+            glob: glob.to_string(),
+            literal_separator: false,
+            case_insensitive: false,
+        }
+    }
+
+    /// Makes `*`, `?` and `[...]` stop at `/`, so only `**` crosses
+    /// path components.
+    pub fn literal_separator(&mut self, yes: bool) -> &mut Self {
+        self.literal_separator = yes;
+        self
+    }
+
+    /// Enables case-insensitive matching.
+    pub fn case_insensitive(&mut self, yes: bool) -> &mut Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Translates the glob and compiles it.
+    pub fn build(&self) -> Result<Regex, GlobError> {
+        let ast =
+            GlobParser::new(&self.glob, self.literal_separator, self.case_insensitive).parse()?;
+        Ok(Regex::from_ast(&ast, self.case_insensitive)?)
+    }
+}
+
+/// Internal glob parser state.
+struct GlobParser {
+    /// Glob characters as a random-access array.
+    input: Vec<char>,
+    /// Current cursor position in `input`.
+    pos: usize,
+    /// Whether wildcards stop at `SEPARATOR`.
+    literal_separator: bool,
+    /// Whether literals are lowercased.
+    case_insensitive: bool,
+}
+
+impl GlobParser {
+    fn new(glob: &str, literal_separator: bool, case_insensitive: bool) -> Self {
+        Self {
+            input: glob.chars().collect(),
+            pos: 0,
+            literal_separator,
+            case_insensitive,
+        }
+    }
+
+    /// Parses the whole glob into an AST anchored at both ends.
+    fn parse(mut self) -> Result<Ast, GlobError> {
+        let mut items = vec![Ast::Assertion(Predicate::StartOfText)];
+        items.extend(self.parse_sequence(false)?);
+        items.push(Ast::Assertion(Predicate::EndOfText));
+        Ok(Ast::Concat(items))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn consume_if(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses items up to the end of input, or up to `,`/`}` inside braces.
+    fn parse_sequence(&mut self, in_braces: bool) -> Result<Vec<Ast>, GlobError> {
+        let mut items = Vec::new();
+        while let Some(ch) = self.peek() {
+            if in_braces && (ch == ',' || ch == '}') {
+                break;
+            }
+            self.pos += 1;
+            let item = match ch {
+                '*' => self.parse_star(in_braces),
+                '?' => Ast::CharClass(self.any_char()),
+                '[' => self.parse_class()?,
+                '{' => self.parse_alternation()?,
+                '\\' => {
+                    let esc = self.next().ok_or(GlobError::TrailingBackslash)?;
+                    self.literal(esc)
+                }
+                _ => self.literal(ch),
+            };
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Parses `*` or `**` whose first `*` was just consumed.
+    fn parse_star(&mut self, in_braces: bool) -> Ast {
+        let star_start = self.pos - 1;
+        let mut count = 1;
+        while self.consume_if('*') {
+            count += 1;
+        }
+
+        let at_component_start = star_start == 0 || self.input[star_start - 1] == SEPARATOR;
+        let at_component_end = match self.peek() {
+            None => true,
+            Some(SEPARATOR) => true,
+            Some(',' | '}') => in_braces,
+            Some(_) => false,
+        };
+        if count == 1 || !at_component_start || !at_component_end {
+            return Ast::ZeroOrMore {
+                expr: Box::new(Ast::CharClass(self.any_char())),
+                greedy: true,
+            };
+        }
+
+        let any_path = Ast::ZeroOrMore {
+            expr: Box::new(Ast::CharClass(CharClass::new(Vec::new(), true))),
+            greedy: true,
+        };
+        if !self.consume_if(SEPARATOR) {
+            return any_path;
+        }
+
+        // `**/` matches zero or more whole components, each with its separator.
+        Ast::ZeroOrOne {
+            expr: Box::new(Ast::Concat(vec![any_path, self.literal(SEPARATOR)])),
+            greedy: true,
+        }
+    }
+
+    /// Parses a character class whose `[` was just consumed.
+    fn parse_class(&mut self) -> Result<Ast, GlobError> {
+        let negated = self.consume_if('!') || self.consume_if('^');
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let ch = self.next().ok_or(GlobError::MissingBracket)?;
+            if ch == ']' && !first {
+                break;
+            }
+            first = false;
+
+            let start = self.class_atom(ch)?;
+            let end = if self.peek() == Some('-') && self.input.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                let ch = self.next().ok_or(GlobError::MissingBracket)?;
+                let end = self.class_atom(ch)?;
+                if end < start {
+                    return Err(GlobError::InvalidRange);
+                }
+                end
+            } else {
+                start
+            };
+            ranges.push(CharRange { start, end });
+        }
+
+        if negated && self.literal_separator {
+            ranges.push(CharRange {
+                start: SEPARATOR,
+                end: SEPARATOR,
+            });
+        }
+        Ok(Ast::CharClass(CharClass::new(ranges, negated)))
+    }
+
+    /// Resolves one class member, including an escaped character.
+    fn class_atom(&mut self, ch: char) -> Result<char, GlobError> {
+        let ch = if ch == '\\' {
+            self.next().ok_or(GlobError::TrailingBackslash)?
+        } else {
+            ch
+        };
+        if !self.case_insensitive {
+            return Ok(ch);
+        }
+
+        let mut lowered = ch.to_lowercase();
+        Ok(match (lowered.next(), lowered.next()) {
+            (Some(lower), None) => lower,
+            _ => ch,
+        })
+    }
+
+    /// Parses `{a,b,...}` whose `{` was just consumed.
+    fn parse_alternation(&mut self) -> Result<Ast, GlobError> {
+        let mut alternatives = Vec::new();
+        loop {
+            alternatives.push(Ast::Concat(self.parse_sequence(true)?));
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err(GlobError::MissingBrace),
+            }
+        }
+        let alternation = alternatives
+            .into_iter()
+            .reduce(|left, right| Ast::Alternate(Box::new(left), Box::new(right)));
+        Ok(alternation.unwrap_or(Ast::Empty))
+    }
+
+    /// Class matching one character, except the separator if it is literal.
+    fn any_char(&self) -> CharClass {
+        if self.literal_separator {
+            CharClass::new(
+                vec![CharRange {
+                    start: SEPARATOR,
+                    end: SEPARATOR,
+                }],
+                true,
+            )
+        } else {
+            CharClass::new(Vec::new(), true)
+        }
+    }
+
+    /// Builds a literal, lowercased when matching case-insensitively.
+    fn literal(&self, ch: char) -> Ast {
+        let single = |ch| {
+            Ast::CharClass(CharClass::new(
+                vec![CharRange { start: ch, end: ch }],
+                false,
+            ))
+        };
+        if !self.case_insensitive {
+            return single(ch);
+        }
+
+        let mut lowered: Vec<Ast> = ch.to_lowercase().map(single).collect();
+        if lowered.len() == 1 {
+            lowered.pop().unwrap()
+        } else {
+            Ast::Concat(lowered)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GlobBuilder, GlobError};
+    use crate::Regex;
+
+    fn glob(pattern: &str) -> Regex {
+        GlobBuilder::new(pattern).build().unwrap()
+    }
+
+    fn path_glob(pattern: &str) -> Regex {
+        GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_star_and_question_mark() {
+        let regex = glob("*.rs");
+        assert!(regex.is_match("main.rs").unwrap());
+        assert!(regex.is_match(".rs").unwrap());
+        assert!(regex.is_match("src/main.rs").unwrap());
+        assert!(!regex.is_match("main.rs.bak").unwrap());
+
+        let regex = glob("file?.txt");
+        assert!(regex.is_match("file1.txt").unwrap());
+        assert!(!regex.is_match("file.txt").unwrap());
+        assert!(!regex.is_match("file12.txt").unwrap());
+    }
+
+    #[test]
+    fn test_literal_separator() {
+        let regex = path_glob("*.rs");
+        assert!(regex.is_match("main.rs").unwrap());
+        assert!(!regex.is_match("src/main.rs").unwrap());
+
+        let regex = path_glob("src/?/[!a]");
+        assert!(regex.is_match("src/x/b").unwrap());
+        assert!(!regex.is_match("src///").unwrap());
+    }
+
+    #[test]
+    fn test_recursive_wildcard() {
+        let regex = path_glob("**/src/*.{toml,lock}");
+        assert!(regex.is_match("src/Cargo.toml").unwrap());
+        assert!(regex.is_match("crates/core/src/Cargo.lock").unwrap());
+        assert!(!regex.is_match("crates/src/nested/Cargo.toml").unwrap());
+        assert!(!regex.is_match("src/Cargo.json").unwrap());
+
+        let regex = path_glob("a/**/b");
+        assert!(regex.is_match("a/b").unwrap());
+        assert!(regex.is_match("a/x/y/b").unwrap());
+        assert!(!regex.is_match("ab").unwrap());
+
+        let regex = path_glob("a/**");
+        assert!(regex.is_match("a/x/y").unwrap());
+        assert!(!regex.is_match("b/x").unwrap());
+
+        // Not a whole component, so `**` acts like `*`.
+        let regex = path_glob("a**b");
+        assert!(regex.is_match("axxb").unwrap());
+        assert!(!regex.is_match("a/b").unwrap());
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let regex = glob("[a-c]x[!0-9][]]");
+        assert!(regex.is_match("bxy]").unwrap());
+        assert!(!regex.is_match("dxy]").unwrap());
+        assert!(!regex.is_match("bx1]").unwrap());
+
+        let regex = glob("[-a][b-]");
+        assert!(regex.is_match("-b").unwrap());
+        assert!(regex.is_match("a-").unwrap());
+    }
+
+    #[test]
+    fn test_nested_alternation_and_escapes() {
+        let regex = glob("{a,b{c,d}}\\*");
+        assert!(regex.is_match("a*").unwrap());
+        assert!(regex.is_match("bd*").unwrap());
+        assert!(!regex.is_match("bd").unwrap());
+        assert!(!regex.is_match("b*").unwrap());
+
+        let regex = glob("x{,y}");
+        assert!(regex.is_match("x").unwrap());
+        assert!(regex.is_match("xy").unwrap());
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let regex = GlobBuilder::new("*.[JT]XT")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert!(regex.is_match("README.txt").unwrap());
+        assert!(regex.is_match("a.JXT").unwrap());
+        assert!(!regex.is_match("a.md").unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |pattern: &str| GlobBuilder::new(pattern).build().err();
+        assert_eq!(error("[abc"), Some(GlobError::MissingBracket));
+        assert_eq!(error("{a,b"), Some(GlobError::MissingBrace));
+        assert_eq!(error("[z-a]"), Some(GlobError::InvalidRange));
+        assert_eq!(error("a\\"), Some(GlobError::TrailingBackslash));
+    }
+}
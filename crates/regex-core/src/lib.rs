@@ -1,55 +1,150 @@
-use std::collections::BTreeSet;
-
-use engine::Instruction;
+use engine::{AhoCorasick, Ast, Instruction, MatchKind, OnePass, ParseOptions, Slots};
+use matches::SearchText;
+use strategy::Engine;
 
+mod builder;
+pub mod bytes;
 mod engine;
 pub mod error;
+pub mod glob;
+mod matches;
+mod strategy;
+
+pub use builder::RegexBuilder;
+pub use engine::Syntax;
+pub use matches::{Captures, FindMatches, Match};
+pub use strategy::Strategy;
 
 /// Public API for pattern matching.
 pub struct Regex {
     /// Compiled instruction sequence.
     code: Vec<Instruction>,
-    /// Must-have literal substrings used for a fast pre-filter.
+    /// Must-have literal substrings used for a fast pre-filter, rarest
+    /// first so the most selective one is scanned for first.
     must_literals: Vec<String>,
-    /// Candidate literal substrings used to find likely start positions.
-    needles: Vec<String>,
-    /// Whether this pattern can match the empty string.
-    nullable: bool,
-    /// Whether the instruction stream contains zero-width assertions.
-    has_assertion: bool,
+    /// Automaton over the candidate literal substrings, used to find likely
+    /// start positions.
+    needles: Option<AhoCorasick>,
+    /// Automaton over the literals one of which starts every match, giving
+    /// the only positions where a match can start.
+    prefixes: Option<AhoCorasick>,
     /// Enables case-insensitive matching by lowercasing pattern/input.
     is_ignore_case: bool,
     /// Inverts the final match result.
     is_invert_match: bool,
+    /// Which match `find`, `find_iter` and `captures` report.
+    match_kind: MatchKind,
+    /// Engine answering `is_match`.
+    engine: Engine,
+    /// One-pass DFA answering leftmost-first `find` and `captures`, when
+    /// the program is anchored and one-pass.
+    onepass: Option<OnePass>,
+    /// Program of the reversed pattern when every match ends at a line or
+    /// text end, so the full NFA search only starts from those ends.
+    reverse_from_end: Option<Vec<Instruction>>,
+    /// Program with `\G` never holding, for `find_iter` searches resuming
+    /// past the end of the previous match; `None` without `\G`.
+    past_search: Option<Vec<Instruction>>,
 }
 
 impl Regex {
     /// Create a new `Regex`.
+    ///
+    /// Use `RegexBuilder` for options beyond case-insensitivity and inversion.
     pub fn new(
         pattern: &str,
         is_ignore_case: bool,
         is_invert_match: bool,
     ) -> Result<Self, error::RegexError> {
-        let (code, analysis) = if is_ignore_case {
-            engine::compile_pattern_with_analysis(&pattern.to_lowercase())?
-        } else {
-            engine::compile_pattern_with_analysis(pattern)?
-        };
+        RegexBuilder::new(pattern)
+            .ignore_case(is_ignore_case)
+            .invert_match(is_invert_match)
+            .build()
+    }
+
+    /// Compiles `pattern` with parser `options`; shared by `new` and `RegexBuilder`.
+    ///
+    /// With `dfa_state_limit`, also builds the ahead-of-time DFA; with
+    /// `anchored`, matches may only start where the search starts.
+    fn with_options(
+        pattern: &str,
+        options: ParseOptions,
+        is_invert_match: bool,
+        match_kind: MatchKind,
+        dfa_state_limit: Option<usize>,
+        anchored: bool,
+    ) -> Result<Self, error::RegexError> {
+        let ast = engine::parse_with_options(pattern, options)?;
+        Self::from_parsed(
+            &ast,
+            options.ignore_case,
+            is_invert_match,
+            match_kind,
+            dfa_state_limit,
+            anchored,
+        )
+    }
+
+    /// Compiles an AST built outside the parser, such as a translated glob.
+    ///
+    /// With `is_ignore_case`, the AST must already contain lowercased literals.
+    pub(crate) fn from_ast(ast: &Ast, is_ignore_case: bool) -> Result<Self, error::RegexError> {
+        Self::from_parsed(
+            ast,
+            is_ignore_case,
+            false,
+            MatchKind::default(),
+            None,
+            false,
+        )
+    }
+
+    /// Analyzes and compiles `ast`, selecting the engine that answers
+    /// `is_match`.
+    fn from_parsed(
+        ast: &Ast,
+        is_ignore_case: bool,
+        is_invert_match: bool,
+        match_kind: MatchKind,
+        dfa_state_limit: Option<usize>,
+        anchored: bool,
+    ) -> Result<Self, error::RegexError> {
+        let (code, analysis) = engine::compile_ast_with_analysis(ast, anchored)?;
         let has_assertion = code
             .iter()
             .any(|instruction| matches!(instruction, Instruction::Assert(_)));
+        let engine = Engine::select(&code, ast, &analysis, has_assertion, dfa_state_limit)?;
+        let onepass = match match_kind {
+            MatchKind::LeftmostFirst => OnePass::new(&code),
+            MatchKind::LeftmostLongest => None,
+        };
+        // Only a program anchored at its end gains from running backwards.
+        let reverse_from_end = match (engine::start_anchor(&code), engine::end_anchor(&code)) {
+            (None, Some(_)) => engine::compile_reverse(ast).ok(),
+            _ => None,
+        };
+        let past_search = engine::without_search_anchor(&code);
 
         Ok(Self {
             code,
             must_literals: analysis.must_literals,
-            needles: analysis.needles,
-            nullable: analysis.nullable,
-            has_assertion,
+            needles: AhoCorasick::new(&analysis.needles),
+            prefixes: AhoCorasick::new(&analysis.prefixes),
             is_ignore_case,
             is_invert_match,
+            match_kind,
+            engine,
+            onepass,
+            reverse_from_end,
+            past_search,
         })
     }
 
+    /// Returns the engine chosen to answer `is_match` for this pattern.
+    pub fn strategy(&self) -> Strategy {
+        self.engine.strategy()
+    }
+
     /// Match a line against the compiled pattern.
     pub fn is_match(&self, line: &str) -> Result<bool, error::RegexError> {
         let is_match = if self.is_ignore_case {
@@ -61,62 +156,150 @@ impl Regex {
         Ok(is_match ^ self.is_invert_match)
     }
 
-    /// Matches a line with nullable/must/needle prefilters and a full-eval fallback.
+    /// Returns whether `text` contains a match starting at or after the
+    /// byte offset `start`.
+    ///
+    /// Unlike matching `&text[start..]`, `^` and other assertions still see
+    /// the text before `start`, and `\G` holds at `start`.
+    /// `is_invert_match` applies as for `is_match`.
+    pub fn is_match_at(&self, text: &str, start: usize) -> Result<bool, error::RegexError> {
+        let search_text = SearchText::new(text, self.is_ignore_case);
+        let start = search_text
+            .offsets
+            .partition_point(|&offset| offset < start);
+        Ok(self.find_at(&search_text.chars, start)?.is_some() ^ self.is_invert_match)
+    }
+
+    /// Returns the leftmost match in `text`.
+    ///
+    /// The reported span honors `\K`; `is_invert_match` does not apply here.
+    pub fn find<'h>(&self, text: &'h str) -> Result<Option<Match<'h>>, error::RegexError> {
+        let search_text = SearchText::new(text, self.is_ignore_case);
+        let span = self.find_at(&search_text.chars, 0)?;
+        Ok(span.map(|(start, end)| {
+            let (start, end) = search_text.span(start, end);
+            Match::new(text, start, end)
+        }))
+    }
+
+    /// Returns the capture groups of the leftmost match in `text`.
+    ///
+    /// Group 0 is the whole match and honors `\K`. In leftmost-longest mode
+    /// the groups follow POSIX subexpression rules.
+    pub fn captures<'h>(&self, text: &'h str) -> Result<Option<Captures<'h>>, error::RegexError> {
+        let search_text = SearchText::new(text, self.is_ignore_case);
+        let slots = match self.search_onepass(&search_text.chars, 0) {
+            Some(slots) => slots,
+            None => engine::captures_line(&self.code, &search_text.chars, self.match_kind)?,
+        };
+        Ok(slots.map(|slots| Captures::new(text, &search_text, slots)))
+    }
+
+    /// Returns an iterator over successive non-overlapping matches in `text`.
+    ///
+    /// Each search continues where the previous match ended, so `\G`
+    /// chains matches contiguously.
+    pub fn find_iter<'r, 'h>(&'r self, text: &'h str) -> FindMatches<'r, 'h> {
+        FindMatches::new(self, text, SearchText::new(text, self.is_ignore_case))
+    }
+
+    /// Finds the leftmost match in `chars` that starts at or after
+    /// `search_start`, as character indices.
+    pub(crate) fn find_at(
+        &self,
+        chars: &[char],
+        search_start: usize,
+    ) -> Result<Option<(usize, usize)>, error::RegexError> {
+        if let Engine::Literal(literals) = &self.engine {
+            return Ok(literals.find(chars, search_start, self.match_kind));
+        }
+        if let Engine::ReverseSuffix(reverse_suffix) = &self.engine
+            && self.match_kind == MatchKind::LeftmostFirst
+        {
+            return match reverse_suffix.find_start(chars, search_start) {
+                Some(start) => engine::find_line_from(&self.code, chars, search_start, start),
+                None => Ok(None),
+            };
+        }
+        match self.search_onepass(chars, search_start) {
+            Some(slots) => Ok(slots.and_then(|slots| slots[0])),
+            None => engine::find_line(&self.code, chars, search_start, self.match_kind),
+        }
+    }
+
+    /// Finds the leftmost match starting at or after `search_start` when
+    /// `\G` holds before it, so that no match can use `\G`.
+    pub(crate) fn find_past_search(
+        &self,
+        chars: &[char],
+        search_start: usize,
+    ) -> Result<Option<(usize, usize)>, error::RegexError> {
+        match &self.past_search {
+            Some(code) => engine::find_line(code, chars, search_start, self.match_kind),
+            None => self.find_at(chars, search_start),
+        }
+    }
+
+    /// Runs the one-pass DFA from `search_start`, or returns `None` if it
+    /// is unavailable or cannot answer for `chars`.
+    fn search_onepass(&self, chars: &[char], search_start: usize) -> Option<Option<Slots>> {
+        let onepass = self.onepass.as_ref()?;
+        onepass
+            .can_search(chars, search_start)
+            .then(|| onepass.search(chars, search_start))
+    }
+
+    /// Matches a line with the selected engine behind the must-literal
+    /// prefilter. If the engine is an NFA or the lazy DFA gives up, runs
+    /// the NFA from where the required prefixes occur, or tries where the
+    /// needles occur before a full search.
     fn is_match_line(&self, line: &str) -> Result<bool, error::RegexError> {
-        if self.nullable && !self.has_assertion {
-            return Ok(true);
+        match &self.engine {
+            Engine::MatchAll => return Ok(true),
+            Engine::Literal(literals) => return Ok(literals.is_match(line)),
+            _ => {}
         }
 
         if !self
             .must_literals
             .iter()
-            .all(|literal| line.contains(literal))
+            .all(|literal| engine::memmem(line.as_bytes(), literal.as_bytes()).is_some())
         {
             return Ok(false);
         }
 
-        if self.must_literals.is_empty() && !self.needles.is_empty() {
-            let starts = Self::collect_start_positions_from_needles(line, &self.needles);
-            if !starts.is_empty() && engine::match_line_from_starts(&self.code, line, &starts)? {
-                return Ok(true);
-            }
-        }
-
-        engine::match_line(&self.code, line)
-    }
-
-    fn collect_start_positions_from_needles(line: &str, needles: &[String]) -> Vec<usize> {
-        let mut byte_starts = BTreeSet::new();
-        for needle in needles {
-            if needle.is_empty() {
-                continue;
-            }
-            for (byte_start, _) in line.match_indices(needle) {
-                byte_starts.insert(byte_start);
+        match &self.engine {
+            Engine::Dfa(dfa) => return Ok(dfa.is_match(line.as_bytes())),
+            Engine::ReverseSuffix(reverse_suffix) => return Ok(reverse_suffix.is_match(line)),
+            Engine::Bitap(bitap) => return Ok(bitap.is_match(line)),
+            Engine::LazyDfa(lazy_dfa) => {
+                if let Some(is_match) = lazy_dfa.is_match(line.as_bytes())? {
+                    return Ok(is_match);
+                }
             }
+            Engine::MatchAll | Engine::Literal(_) | Engine::Nfa { .. } => {}
         }
 
-        if byte_starts.is_empty() {
-            return Vec::new();
+        if let Some(prefixes) = &self.prefixes {
+            let starts = prefixes.starts(line);
+            return Ok(
+                !starts.is_empty() && engine::match_line_from_starts(&self.code, line, &starts)?
+            );
         }
 
-        let mut starts = Vec::with_capacity(byte_starts.len());
-        let mut targets = byte_starts.into_iter().peekable();
-        for (char_index, (byte_index, _)) in line.char_indices().enumerate() {
-            while let Some(target) = targets.peek() {
-                if *target == byte_index {
-                    starts.push(char_index);
-                    targets.next();
-                } else {
-                    break;
-                }
-            }
-            if targets.peek().is_none() {
-                break;
+        if self.must_literals.is_empty()
+            && let Some(needles) = &self.needles
+        {
+            let starts = needles.starts(line);
+            if !starts.is_empty() && engine::match_line_from_starts(&self.code, line, &starts)? {
+                return Ok(true);
             }
         }
 
-        starts
+        match &self.reverse_from_end {
+            Some(reverse) => engine::match_line(reverse, &line.chars().rev().collect::<String>()),
+            None => engine::match_line(&self.code, line),
+        }
     }
 }
 
@@ -124,6 +307,12 @@ impl Regex {
 mod tests {
     use super::*;
 
+    fn needles_of(pattern: &str) -> Vec<String> {
+        let (_, analysis) =
+            engine::compile_pattern_with_analysis(pattern, ParseOptions::default()).unwrap();
+        analysis.needles
+    }
+
     #[test]
     fn test_is_match() {
         let regex = Regex::new("ab(c|d)", false, false).unwrap();
@@ -154,6 +343,461 @@ mod tests {
         assert!(!regex.is_match("abcabd").unwrap());
     }
 
+    #[test]
+    fn test_recursion_and_subroutine_calls() {
+        let regex = Regex::new("^(?<paren>\\((?&paren)*\\))$", false, false).unwrap();
+        assert!(regex.is_match("(()())").unwrap());
+        assert!(!regex.is_match("(()").unwrap());
+
+        let regex = Regex::new("^([a-z])(?1)$", true, false).unwrap();
+        assert!(regex.is_match("Ab").unwrap());
+        assert!(!regex.is_match("A1").unwrap());
+    }
+
+    #[test]
+    fn test_find() {
+        let regex = Regex::new("b+", false, false).unwrap();
+        let m = regex.find("aébbc").unwrap().unwrap();
+        assert_eq!(m.range(), 3..5);
+        assert_eq!(m.as_str(), "bb");
+        assert!(regex.find("ac").unwrap().is_none());
+
+        let regex = Regex::new("b+", true, false).unwrap();
+        assert_eq!(regex.find("aBbc").unwrap().unwrap().as_str(), "Bb");
+    }
+
+    #[test]
+    fn test_find_reset_match_start() {
+        let regex = Regex::new("key=\\K[a-z]+", false, false).unwrap();
+        let m = regex.find("x key=value;").unwrap().unwrap();
+        assert_eq!(m.as_str(), "value");
+        assert_eq!(m.range(), 6..11);
+    }
+
+    #[test]
+    fn test_find_iter_start_of_search() {
+        let regex = Regex::new("\\G[0-9]", false, false).unwrap();
+        let matches: Vec<&str> = regex
+            .find_iter("123a45")
+            .map(|m| m.unwrap().as_str())
+            .collect();
+        assert_eq!(matches, vec!["1", "2", "3"]);
+
+        let regex = Regex::new("\\G[0-9]*", false, false).unwrap();
+        let ranges: Vec<_> = regex
+            .find_iter("12a34")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(ranges, vec![0..2]);
+
+        let regex = Regex::new("\\G[0-9]*|b", false, false).unwrap();
+        let ranges: Vec<_> = regex
+            .find_iter("12ab34ab")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(ranges, vec![0..2, 3..4, 4..6, 7..8]);
+
+        let regex = Regex::new("[0-9]", false, false).unwrap();
+        assert_eq!(regex.find_iter("123a45").count(), 5);
+    }
+
+    #[test]
+    fn test_find_iter_empty_matches() {
+        let regex = Regex::new("a*", false, false).unwrap();
+        let ranges: Vec<_> = regex.find_iter("baa").map(|m| m.unwrap().range()).collect();
+        assert_eq!(ranges, vec![0..0, 1..3]);
+    }
+
+    #[test]
+    fn test_find_iter_ignore_case_keeps_characters_whole() {
+        // `İ` lowercases to `i` followed by U+0307.
+        let ranges = |pattern: &str, haystack: &str| -> Vec<_> {
+            Regex::new(pattern, true, false)
+                .unwrap()
+                .find_iter(haystack)
+                .map(|m| m.unwrap().range())
+                .collect()
+        };
+        assert_eq!(ranges("i", "İİ"), vec![0..2, 2..4]);
+        assert_eq!(ranges("x*", "İa"), vec![0..0, 2..2, 3..3]);
+        assert_eq!(ranges("ss|ß", "ẞSSß"), vec![0..3, 3..5, 5..7]);
+        assert_eq!(ranges("ǰ", "J̌ǰ"), vec![3..5]);
+
+        let regex = Regex::new("a(i)", true, false).unwrap();
+        assert_eq!(regex.find("xaİ").unwrap().unwrap().range(), 1..4);
+        let caps = regex.captures("xaİ").unwrap().unwrap();
+        assert_eq!(caps.get(1).map(|m| m.range()), Some(2..4));
+    }
+
+    #[test]
+    fn test_captures() {
+        let regex = Regex::new("([0-9]+)-(x)?([a-z]+)", false, false).unwrap();
+        let caps = regex.captures("id: 12-ab").unwrap().unwrap();
+        assert_eq!(caps.len(), 4);
+        assert_eq!(caps.get(0).map(|m| m.as_str()), Some("12-ab"));
+        assert_eq!(caps.get(1).map(|m| m.range()), Some(4..6));
+        assert_eq!(caps.get(2), None);
+        assert_eq!(caps.get(3).map(|m| m.as_str()), Some("ab"));
+        assert_eq!(caps.get(4), None);
+        assert!(regex.captures("none").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bitap_is_selected_for_short_patterns() {
+        let regex = Regex::new("err[0-9]+", true, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Bitap);
+        assert!(regex.is_match("an ERR7 line").unwrap());
+        assert!(!regex.is_match("an err line").unwrap());
+
+        assert_eq!(
+            Regex::new("^err", false, false).unwrap().strategy(),
+            Strategy::LazyDfa
+        );
+        assert_eq!(
+            Regex::new(&"[ab]".repeat(65), false, false)
+                .unwrap()
+                .strategy(),
+            Strategy::LazyDfa
+        );
+    }
+
+    #[test]
+    fn test_onepass_captures() {
+        let regex = Regex::new("^([0-9]{4})-([0-9]{2})-([0-9]{2})$", false, false).unwrap();
+        assert!(regex.onepass.is_some());
+        let caps = regex.captures("2024-01-31").unwrap().unwrap();
+        let groups: Vec<_> = (0..caps.len())
+            .map(|i| caps.get(i).map(|m| m.as_str()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![Some("2024-01-31"), Some("2024"), Some("01"), Some("31")]
+        );
+        assert!(regex.captures("2024-1-31").unwrap().is_none());
+
+        // `^` may also match after a line break, which the one-pass DFA
+        // leaves to the other engines.
+        let caps = regex.captures("x\n2024-01-31").unwrap().unwrap();
+        assert_eq!(caps.get(1).map(|m| m.range()), Some(2..6));
+
+        assert!(
+            Regex::new("([0-9]+)-", false, false)
+                .unwrap()
+                .onepass
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        let regex = RegexBuilder::new("in|int|integer")
+            .leftmost_longest(true)
+            .build()
+            .unwrap();
+        let found: Vec<_> = regex
+            .find_iter("int integer in")
+            .map(|m| m.unwrap().as_str())
+            .collect();
+        assert_eq!(found, vec!["int", "integer", "in"]);
+
+        let regex = RegexBuilder::new("(a|ab)(c|bcd)(d*)")
+            .leftmost_longest(true)
+            .build()
+            .unwrap();
+        let caps = regex.captures("abcd").unwrap().unwrap();
+        let groups: Vec<_> = (0..caps.len())
+            .map(|i| caps.get(i).map(|m| m.as_str()))
+            .collect();
+        assert_eq!(groups, vec![Some("abcd"), Some("ab"), Some("c"), Some("d")]);
+
+        let regex = Regex::new("(a|ab)(c|bcd)(d*)", false, false).unwrap();
+        let caps = regex.captures("abcd").unwrap().unwrap();
+        assert_eq!(caps.get(1).map(|m| m.as_str()), Some("a"));
+    }
+
+    #[test]
+    fn test_strategy() {
+        let strategy = |pattern: &str| Regex::new(pattern, false, false).unwrap().strategy();
+        assert_eq!(strategy("x*"), Strategy::MatchAll);
+        assert_eq!(strategy("(err)or"), Strategy::Literal);
+        assert_eq!(strategy("err(or|no)"), Strategy::Literals);
+        assert_eq!(strategy("[a-z.]+@example\\.com"), Strategy::ReverseSuffix);
+        assert_eq!(strategy("err[0-9]+"), Strategy::Bitap);
+        assert_eq!(strategy("(a|b)*c$"), Strategy::LazyDfa);
+        assert_eq!(strategy("[a-z]{2000}$"), Strategy::PikeVm);
+        assert_eq!(strategy("(a)\\1"), Strategy::Backtracker);
+        assert_eq!(strategy("\\X$"), Strategy::Backtracker);
+
+        let regex = Regex::new("Error", true, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Literal);
+        assert!(regex.is_match("an ERROR line").unwrap());
+        assert!(!regex.is_match("an err line").unwrap());
+
+        let regex = Regex::new("[a-z]{2000}$", false, false).unwrap();
+        assert!(regex.is_match(&format!("1{}", "x".repeat(2000))).unwrap());
+        assert!(!regex.is_match(&"x".repeat(1999)).unwrap());
+    }
+
+    #[test]
+    fn test_anchored() {
+        let regex = RegexBuilder::new("[a-z]+").anchored(true).build().unwrap();
+        assert!(regex.is_match("abc 123").unwrap());
+        assert!(!regex.is_match("123 abc").unwrap());
+        assert!(regex.is_match_at("123 abc", 4).unwrap());
+        assert!(!regex.is_match_at("123 abc", 3).unwrap());
+        let found: Vec<_> = regex
+            .find_iter("ab cd")
+            .map(|m| m.unwrap().as_str())
+            .collect();
+        assert_eq!(found, vec!["ab"]);
+
+        // An empty match ends the chain instead of skipping the gap.
+        let regex = RegexBuilder::new("[0-9]*").anchored(true).build().unwrap();
+        let ranges: Vec<_> = regex
+            .find_iter("12a34")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(ranges, vec![0..2]);
+
+        // Recursion does not repeat the anchor.
+        let regex = RegexBuilder::new("a(?R)?b").anchored(true).build().unwrap();
+        assert!(regex.is_match("aabb").unwrap());
+        assert!(!regex.is_match("xab").unwrap());
+    }
+
+    #[test]
+    fn test_is_match_at() {
+        let regex = Regex::new("^b|\\Gc", false, false).unwrap();
+        assert!(!regex.is_match_at("ab", 1).unwrap());
+        assert!(regex.is_match_at("a\nb", 2).unwrap());
+        assert!(regex.is_match_at("acx", 1).unwrap());
+        assert!(!regex.is_match_at("acx", 0).unwrap());
+
+        let regex = Regex::new("É", true, false).unwrap();
+        assert!(regex.is_match_at("aé", 1).unwrap());
+        assert!(!regex.is_match_at("aé", 3).unwrap());
+
+        let regex = Regex::new("b", false, true).unwrap();
+        assert!(regex.is_match_at("ab", 2).unwrap());
+        assert!(!regex.is_match_at("ab", 0).unwrap());
+    }
+
+    #[test]
+    fn test_reverse_from_end() {
+        let regex = Regex::new("[a-z]{2000}[0-9]$", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::PikeVm);
+        assert!(regex.reverse_from_end.is_some());
+        assert!(regex.is_match(&format!("1{}9", "x".repeat(2000))).unwrap());
+        assert!(!regex.is_match(&format!("{}9x", "x".repeat(2000))).unwrap());
+        assert!(!regex.is_match(&format!("{}9", "x".repeat(1999))).unwrap());
+
+        assert!(
+            Regex::new("^[a-z]{2000}$", false, false)
+                .unwrap()
+                .reverse_from_end
+                .is_none()
+        );
+        assert!(
+            Regex::new("(a)\\1$", false, false)
+                .unwrap()
+                .reverse_from_end
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        let regex = Regex::new("GR[AE]Y|silver", true, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Literals);
+        assert!(regex.is_match("a Grey cat").unwrap());
+        assert!(!regex.is_match("a green cat").unwrap());
+        let found: Vec<_> = regex
+            .find_iter("gray, SILVER and grey")
+            .map(|m| m.unwrap().range())
+            .collect();
+        assert_eq!(found, vec![0..4, 6..12, 17..21]);
+
+        let regex = RegexBuilder::new("in|int|integer")
+            .leftmost_longest(true)
+            .build()
+            .unwrap();
+        assert_eq!(regex.strategy(), Strategy::Literals);
+        assert_eq!(
+            regex.find("an integer").unwrap().map(|m| m.range()),
+            Some(3..10)
+        );
+        let regex = Regex::new("in|int|integer", false, true).unwrap();
+        assert!(!regex.is_match("print").unwrap());
+    }
+
+    #[test]
+    fn test_dfa() {
+        let regex = RegexBuilder::new("^(get|post) /api(/|$)")
+            .ignore_case(true)
+            .dfa(true)
+            .build()
+            .unwrap();
+        assert_eq!(regex.strategy(), Strategy::Dfa);
+        assert!(regex.is_match("GET /api/users").unwrap());
+        assert!(regex.is_match("post /api").unwrap());
+        assert!(!regex.is_match("GET /apiary").unwrap());
+        assert!(!regex.is_match("x POST /api").unwrap());
+
+        let error = RegexBuilder::new("a(a|b){12}c")
+            .dfa(true)
+            .dfa_state_limit(100)
+            .build()
+            .err();
+        assert_eq!(
+            error,
+            Some(error::RegexError::Compile(
+                error::CompileError::DfaTooLarge(100)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_crlf_mode() {
+        let regex = RegexBuilder::new("foo$").build().unwrap();
+        assert!(!regex.is_match("foo\r\nbar").unwrap());
+
+        let regex = RegexBuilder::new("^foo$").crlf(true).build().unwrap();
+        assert!(regex.is_match("foo\r\nbar").unwrap());
+        assert!(regex.is_match("bar\r\nfoo\r\n").unwrap());
+
+        let regex = RegexBuilder::new("\r^").crlf(true).build().unwrap();
+        assert!(regex.is_match("\rx").unwrap());
+        assert!(!regex.is_match("\r\n").unwrap());
+
+        let regex = RegexBuilder::new("foo.").crlf(true).build().unwrap();
+        assert!(!regex.is_match("foo\r\n").unwrap());
+        assert!(regex.is_match("foo!\r\n").unwrap());
+    }
+
+    #[test]
+    fn test_linebreak_escape() {
+        let regex = Regex::new("a\\Rb", false, false).unwrap();
+        assert_eq!(regex.find("xa\r\nb").unwrap().unwrap().range(), 1..5);
+        assert!(regex.is_match("a\nb").unwrap());
+        assert!(regex.is_match("a\u{2028}b").unwrap());
+        assert!(!regex.is_match("ab").unwrap());
+    }
+
+    #[test]
+    fn test_linebreak_escape_does_not_split_crlf() {
+        let regex = Regex::new("^a\\R{2}b$", false, false).unwrap();
+        assert!(!regex.is_match("a\r\nb").unwrap());
+        assert!(regex.is_match("a\r\n\rb").unwrap());
+        let regex = Regex::new("\\R\n", false, false).unwrap();
+        assert!(!regex.is_match("\r\n").unwrap());
+        assert!(regex.is_match("\r\n\n").unwrap());
+        assert_eq!(
+            Regex::new("\\R", false, false)
+                .unwrap()
+                .find_iter("\r\r\n\n")
+                .map(|m| m.unwrap().range())
+                .collect::<Vec<_>>(),
+            vec![0..1, 1..3, 3..4]
+        );
+    }
+
+    #[test]
+    fn test_grapheme_cluster() {
+        // "e" + combining acute accent, thumbs up with skin tone, "a".
+        let text = "e\u{301}\u{1F44D}\u{1F3FD}a";
+        let regex = Regex::new("^\\X{3}$", false, false).unwrap();
+        assert!(regex.is_match(text).unwrap());
+
+        let regex = Regex::new("^.{3}$", false, false).unwrap();
+        assert!(!regex.is_match(text).unwrap());
+
+        let regex = Regex::new("\\X", false, false).unwrap();
+        let clusters: Vec<&str> = regex.find_iter(text).map(|m| m.unwrap().as_str()).collect();
+        assert_eq!(clusters, vec!["e\u{301}", "\u{1F44D}\u{1F3FD}", "a"]);
+
+        // The conjunct क्ष is one cluster.
+        let regex = Regex::new("^\\X$", false, false).unwrap();
+        assert!(regex.is_match("क्ष").unwrap());
+    }
+
+    #[test]
+    fn test_whitespace_shorthand_classes() {
+        let regex = Regex::new("a\\h+b\\v\\N", false, false).unwrap();
+        assert!(regex.is_match("a \t\u{3000}b\nc").unwrap());
+        assert!(!regex.is_match("a\nb\nc").unwrap());
+        assert!(!regex.is_match("a b\n\n").unwrap());
+
+        let regex = Regex::new("^\\H\\V$", false, false).unwrap();
+        assert!(regex.is_match("xy").unwrap());
+        assert!(!regex.is_match(" y").unwrap());
+        assert!(!regex.is_match("x\u{2028}").unwrap());
+    }
+
+    #[test]
+    fn test_syntax_dialects() {
+        let basic = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .syntax(Syntax::Basic)
+                .build()
+                .unwrap()
+        };
+        let regex = basic("^\\(ab\\)\\{2\\}\\1$");
+        assert!(regex.is_match("ababab").unwrap());
+        assert!(!regex.is_match("abab").unwrap());
+        assert!(basic("a+b?|c").is_match("xa+b?|cx").unwrap());
+        assert!(!basic("a+b?|c").is_match("aab").unwrap());
+        assert!(basic("x\\|y\\+").is_match("yy").unwrap());
+        assert!(basic("*a$b").is_match("*a$b").unwrap());
+
+        let extended = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .syntax(Syntax::Extended)
+                .build()
+                .unwrap()
+        };
+        let regex = extended("^(ab){2}|c+$");
+        assert!(regex.is_match("abab").unwrap());
+        assert!(regex.is_match("xcc").unwrap());
+        assert!(extended("a\\Kb").is_match("aKb").unwrap());
+        assert!(
+            RegexBuilder::new("\\(a")
+                .syntax(Syntax::Basic)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_syntax_dialects_accept_gnu_grep_patterns() {
+        let build = |pattern: &str, syntax: Syntax| {
+            RegexBuilder::new(pattern).syntax(syntax).build().unwrap()
+        };
+        let find = |pattern: &str, syntax: Syntax, text: &str| {
+            build(pattern, syntax)
+                .find(text)
+                .unwrap()
+                .map(|m| m.range())
+        };
+        for syntax in [Syntax::Basic, Syntax::Extended] {
+            assert_eq!(find("a]", syntax, "xa]"), Some(1..3));
+            assert_eq!(find("[a\\]]", syntax, "x\\]"), Some(1..3));
+            assert_eq!(find("ba**", syntax, "baaa"), Some(0..4));
+            assert_eq!(find("[[.x.]]y", syntax, "xy"), Some(0..2));
+            assert_eq!(find("[[=x=]]", syntax, "axb"), Some(1..2));
+            assert_eq!(find("\\w\\s\\W", syntax, "a-b ."), Some(2..5));
+            assert!(
+                RegexBuilder::new("\\bword\\b")
+                    .syntax(syntax)
+                    .build()
+                    .is_err()
+            );
+        }
+        assert_eq!(find("x\\{,2\\}y", Syntax::Basic, "xxxy"), Some(1..4));
+        assert_eq!(find("a}", Syntax::Extended, "a}"), Some(0..2));
+        assert_eq!(find("ba+?", Syntax::Extended, "baaa"), Some(0..4));
+        assert_eq!(find("a{2}*c", Syntax::Extended, "aaac"), Some(1..4));
+    }
+
     #[test]
     fn test_anchor_patterns() {
         let regex_start = Regex::new("^hello", false, false).unwrap();
@@ -183,14 +827,14 @@ mod tests {
     fn test_extracts_must_literals_for_filtering() {
         let regex = Regex::new(".*abc.*", false, false).unwrap();
         assert_eq!(regex.must_literals, vec!["abc".to_string()]);
-        assert_eq!(regex.needles, vec!["abc".to_string()]);
-        assert!(!regex.nullable);
+        assert_eq!(needles_of(".*abc.*"), vec!["abc".to_string()]);
+        assert_ne!(regex.strategy(), Strategy::MatchAll);
 
         let regex = Regex::new("ab*c", false, false).unwrap();
-        assert_eq!(regex.must_literals, vec!["a".to_string(), "c".to_string()]);
+        assert_eq!(regex.must_literals, vec!["c".to_string(), "a".to_string()]);
         assert_eq!(
-            regex.needles,
-            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            needles_of("ab*c"),
+            vec!["b".to_string(), "c".to_string(), "a".to_string()]
         );
     }
 
@@ -224,16 +868,23 @@ mod tests {
     #[test]
     fn test_nullable_fast_path_without_assertion() {
         let regex = Regex::new("a*", false, false).unwrap();
-        assert!(regex.nullable);
-        assert!(!regex.has_assertion);
+        assert_eq!(regex.strategy(), Strategy::MatchAll);
         assert!(regex.is_match("zzz").unwrap());
     }
 
+    #[test]
+    fn test_nullable_fast_path_is_guarded_by_call() {
+        let regex = Regex::new("(a(?1)?b)?", false, false).unwrap();
+        assert_ne!(regex.strategy(), Strategy::MatchAll);
+        assert!(regex.is_match("zzz").unwrap());
+        assert_eq!(regex.find("aabb").unwrap().map(|m| m.range()), Some(0..4));
+        assert!(Regex::new("(?R)?", false, false).is_err());
+    }
+
     #[test]
     fn test_nullable_fast_path_is_guarded_by_assertion() {
         let regex = Regex::new("^$", false, false).unwrap();
-        assert!(regex.nullable);
-        assert!(regex.has_assertion);
+        assert_eq!(regex.strategy(), Strategy::LazyDfa);
         assert!(regex.is_match("").unwrap());
         assert!(!regex.is_match("x").unwrap());
     }
@@ -242,16 +893,87 @@ mod tests {
     fn test_needles_preferred_search_still_matches() {
         let regex = Regex::new("(abc|def)", false, false).unwrap();
         assert!(regex.must_literals.is_empty());
-        assert_eq!(regex.needles, vec!["abc".to_string(), "def".to_string()]);
+        assert_eq!(
+            needles_of("(abc|def)"),
+            vec!["abc".to_string(), "def".to_string()]
+        );
         assert!(regex.is_match("xyzdef").unwrap());
         assert!(!regex.is_match("xyz").unwrap());
     }
 
+    #[test]
+    fn test_needles_guide_nfa_search() {
+        let regex = Regex::new("(abc|déf)x\\1", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Backtracker);
+        assert!(regex.needles.is_some());
+        assert!(regex.is_match("ab déf défxdéf").unwrap());
+        assert!(regex.is_match("abcxabc").unwrap());
+        assert!(!regex.is_match("abcxdéf").unwrap());
+    }
+
+    #[test]
+    fn test_prefixes_guide_nfa_search() {
+        let regex = Regex::new("(ab|cd)[a-z]*\\1", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::Backtracker);
+        assert!(regex.prefixes.is_some());
+        assert!(regex.is_match("xx cdzzcd").unwrap());
+        assert!(regex.is_match("abab").unwrap());
+        assert!(!regex.is_match("abzzcd").unwrap());
+        assert!(!regex.is_match("zz").unwrap());
+    }
+
+    #[test]
+    fn test_reverse_suffix() {
+        let regex = Regex::new("[a-z0-9.]+@EXAMPLE\\.com", true, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::ReverseSuffix);
+        let text = "mail Bob.Smith@example.com, @example.com, x@example.co or a1@Example.COM";
+        assert!(regex.is_match(text).unwrap());
+        assert!(!regex.is_match("@example.com x@example.co").unwrap());
+        let found: Vec<_> = regex.find_iter(text).map(|m| m.unwrap().as_str()).collect();
+        assert_eq!(found, vec!["Bob.Smith@example.com", "a1@Example.COM"]);
+    }
+
     #[test]
     fn test_needles_fallback_to_full_scan_preserves_correctness() {
         let regex = Regex::new("(a|[0-9])", false, false).unwrap();
         assert!(regex.must_literals.is_empty());
-        assert_eq!(regex.needles, vec!["a".to_string()]);
+        assert_eq!(needles_of("(a|[0-9])"), vec!["a".to_string()]);
         assert!(regex.is_match("5").unwrap());
     }
+
+    #[test]
+    fn test_lazy_dfa_is_built_only_for_supported_programs() {
+        assert_eq!(
+            Regex::new("(a|b)*c$", false, false).unwrap().strategy(),
+            Strategy::LazyDfa
+        );
+        assert_eq!(
+            Regex::new("(a)\\1", false, false).unwrap().strategy(),
+            Strategy::Backtracker
+        );
+        assert!(
+            Regex::new("(a)\\1", false, false)
+                .unwrap()
+                .is_match("xaa")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_match_falls_back_when_lazy_dfa_gives_up() {
+        // `$` keeps the bit-parallel automaton from answering first.
+        let regex = Regex::new("a(a|b){12}c$", false, false).unwrap();
+        assert_eq!(regex.strategy(), Strategy::LazyDfa);
+        let line: String = (0..40_000u32)
+            .map(|i| {
+                if i.wrapping_mul(2_654_435_761) >> 31 == 0 {
+                    'a'
+                } else {
+                    'b'
+                }
+            })
+            .collect();
+        assert!(!regex.is_match(&line).unwrap());
+        assert!(regex.is_match(&format!("{line}abbbbbbbbbbbbc")).unwrap());
+    }
 }
@@ -0,0 +1,248 @@
+//! Match spans reported by `Regex::find`, `Regex::find_iter` and `Regex::captures`.
+
+use std::ops::Range;
+
+use crate::{Regex, engine::Slots, error::RegexError};
+
+/// One match in a haystack.
+///
+/// Offsets are byte offsets into the haystack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'h> {
+    haystack: &'h str,
+    start: usize,
+    end: usize,
+}
+
+impl<'h> Match<'h> {
+    /// Creates a match for `haystack[start..end]`.
+    pub(crate) fn new(haystack: &'h str, start: usize, end: usize) -> Self {
+        Self {
+            haystack,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the byte offset where the match starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte offset where the match ends (exclusive).
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the byte range of the match.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns whether the match is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the length of the match in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns the matched text.
+    pub fn as_str(&self) -> &'h str {
+        &self.haystack[self.range()]
+    }
+}
+
+/// Spans of the capture groups of one match.
+///
+/// Group 0 is the whole match. Offsets are byte offsets into the haystack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures<'h> {
+    haystack: &'h str,
+    spans: Vec<Option<(usize, usize)>>,
+}
+
+impl<'h> Captures<'h> {
+    /// Creates captures from evaluator `slots` over `text`.
+    pub(crate) fn new(haystack: &'h str, text: &SearchText, slots: Slots) -> Self {
+        let spans = slots
+            .into_iter()
+            .map(|slot| slot.map(|(start, end)| text.span(start, end)))
+            .collect();
+        Self { haystack, spans }
+    }
+
+    /// Returns the match of group `index`, or `None` if it did not participate.
+    pub fn get(&self, index: usize) -> Option<Match<'h>> {
+        let (start, end) = (*self.spans.get(index)?)?;
+        Some(Match::new(self.haystack, start, end))
+    }
+
+    /// Returns the number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns whether there are no groups, which never happens since group 0
+    /// is always present.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Haystack decoded into the characters the evaluator runs on.
+///
+/// With `lowercase`, each character is replaced by its lowercase form,
+/// which may expand into several characters. Matches are reported in
+/// whole haystack characters, so a span starting or ending inside such an
+/// expansion is widened to the characters around it.
+pub(crate) struct SearchText {
+    /// Characters fed to the evaluator.
+    pub chars: Vec<char>,
+    /// Byte offset in the haystack of each entry in `chars`,
+    /// followed by the haystack length.
+    pub offsets: Vec<usize>,
+}
+
+impl SearchText {
+    /// Decodes `haystack`, optionally lowercasing it.
+    pub(crate) fn new(haystack: &str, lowercase: bool) -> Self {
+        let mut chars = Vec::with_capacity(haystack.len());
+        let mut offsets = Vec::with_capacity(haystack.len() + 1);
+        for (offset, ch) in haystack.char_indices() {
+            if lowercase {
+                for lower in ch.to_lowercase() {
+                    chars.push(lower);
+                    offsets.push(offset);
+                }
+            } else {
+                chars.push(ch);
+                offsets.push(offset);
+            }
+        }
+        offsets.push(haystack.len());
+        Self { chars, offsets }
+    }
+
+    /// Decodes a byte `haystack` like `new`, replacing each invalid UTF-8
+    /// sequence with U+FFFD.
+    pub(crate) fn from_bytes(haystack: &[u8], lowercase: bool) -> Self {
+        let mut text = Self {
+            chars: Vec::with_capacity(haystack.len()),
+            offsets: Vec::with_capacity(haystack.len() + 1),
+        };
+        let mut offset = 0;
+        for chunk in haystack.utf8_chunks() {
+            let valid = Self::new(chunk.valid(), lowercase);
+            text.chars.extend(valid.chars);
+            text.offsets.extend(
+                valid.offsets[..valid.offsets.len() - 1]
+                    .iter()
+                    .map(|o| offset + o),
+            );
+            offset += chunk.valid().len();
+            if !chunk.invalid().is_empty() {
+                text.chars.push(char::REPLACEMENT_CHARACTER);
+                text.offsets.push(offset);
+                offset += chunk.invalid().len();
+            }
+        }
+        text.offsets.push(haystack.len());
+        text
+    }
+
+    /// Returns the haystack byte span of the characters `start..end`.
+    pub(crate) fn span(&self, start: usize, end: usize) -> (usize, usize) {
+        (self.offsets[start], self.offsets[self.boundary(end)])
+    }
+
+    /// Returns the first index at or after `index` where a haystack
+    /// character starts, or the length of `chars`.
+    pub(crate) fn boundary(&self, index: usize) -> usize {
+        char_boundary(&self.offsets, index)
+    }
+}
+
+/// Returns the first position at or after `index` whose entry in
+/// `offsets` starts a haystack character, or the last position.
+///
+/// `offsets` holds the haystack offset of each position followed by the
+/// haystack length; the positions a lowercase expansion adds share the
+/// offset of the character they come from.
+pub(crate) fn char_boundary(offsets: &[usize], mut index: usize) -> usize {
+    while index > 0 && index + 1 < offsets.len() && offsets[index] == offsets[index - 1] {
+        index += 1;
+    }
+    index
+}
+
+/// Iterator over successive non-overlapping matches.
+///
+/// Created by `Regex::find_iter`. Each search starts where the previous
+/// match ended, which is the position `\G` refers to, so a pattern
+/// starting with `\G` stops at the first gap between matches.
+pub struct FindMatches<'r, 'h> {
+    regex: &'r Regex,
+    haystack: &'h str,
+    text: SearchText,
+    next_start: usize,
+    last_end: Option<usize>,
+    done: bool,
+}
+
+impl<'r, 'h> FindMatches<'r, 'h> {
+    /// Creates an iterator over the matches of `regex` in `haystack`.
+    pub(crate) fn new(regex: &'r Regex, haystack: &'h str, text: SearchText) -> Self {
+        Self {
+            regex,
+            haystack,
+            text,
+            next_start: 0,
+            last_end: None,
+            done: false,
+        }
+    }
+}
+
+impl<'h> Iterator for FindMatches<'_, 'h> {
+    type Item = Result<Match<'h>, RegexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done && self.next_start <= self.text.chars.len() {
+            // `\G` stays at the previous match end when an empty match
+            // moved the search past it.
+            let found = if self.last_end.is_none_or(|end| end == self.next_start) {
+                self.regex.find_at(&self.text.chars, self.next_start)
+            } else {
+                self.regex
+                    .find_past_search(&self.text.chars, self.next_start)
+            };
+            let (start, end) = match found {
+                Ok(Some(span)) => span,
+                Ok(None) => break,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            if start == end && self.last_end == Some(end) {
+                // An empty match right after the previous one would be reported twice.
+                self.next_start = self.text.boundary(end + 1);
+                continue;
+            }
+
+            // The next search starts after the whole haystack character
+            // the match ends in.
+            let (start_offset, end_offset) = self.text.span(start, end);
+            self.next_start = self.text.boundary(end);
+            self.last_end = Some(self.next_start);
+            return Some(Ok(Match::new(self.haystack, start_offset, end_offset)));
+        }
+
+        self.done = true;
+        None
+    }
+}
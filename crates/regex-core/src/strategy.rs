@@ -0,0 +1,130 @@
+//! Selection of the engine answering `Regex::is_match`.
+//!
+//! The choice is made once per pattern from its analysis and the
+//! instructions of its program, cheapest engine first: a pattern that
+//! matches everywhere or only matches literal strings needs no automaton
+//! over its program, then come the ahead-of-time DFA when requested, the
+//! reverse-suffix search, the bit-parallel automaton, the lazy DFA and
+//! finally the NFA simulations.
+
+use crate::{
+    engine::{self, Ast, AstAnalysis, Bitap, Dfa, Instruction, LazyDfa, LiteralSet, ReverseSuffix},
+    error::RegexError,
+};
+
+/// Largest program handed to the lazy DFA.
+///
+/// Each new DFA state is a closure over the whole program, and programs
+/// this large tend to need more states than its cache holds, so they go
+/// straight to the Pike VM.
+const LAZY_DFA_MAX_INSTRUCTIONS: usize = 1024;
+
+/// Engine chosen to answer `Regex::is_match`, as reported by
+/// `Regex::strategy`.
+///
+/// Except for `MatchAll`, `Literal` and `Literals`, the must-have literals of the
+/// pattern are checked before the engine runs, so lines missing one of
+/// them are rejected without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Strategy {
+    /// The pattern matches the empty string without assertions, so every
+    /// line contains a match.
+    MatchAll,
+    /// The pattern is a literal string, found with a substring search.
+    Literal,
+    /// The pattern matches one of a few literal strings, such as `abc|def`
+    /// or `gr[ae]y`, found with an Aho-Corasick automaton.
+    Literals,
+    /// The ahead-of-time DFA requested with `RegexBuilder::dfa`.
+    Dfa,
+    /// A substring search for the literal ending every match, such as
+    /// `@example.com` in `[a-z.]+@example\.com`, then the pattern run
+    /// backwards from each occurrence. Leftmost-first `find` uses it too.
+    ReverseSuffix,
+    /// The bit-parallel Shift-And automaton, for short patterns without
+    /// assertions.
+    Bitap,
+    /// The lazy DFA, falling back to the NFA engines when its cache keeps
+    /// filling up.
+    LazyDfa,
+    /// The Pike VM, for programs too large for the lazy DFA. The bounded
+    /// backtracker runs instead on lines short enough for its visited
+    /// table.
+    PikeVm,
+    /// The backtracking evaluator, for backreferences, subroutine calls
+    /// and `\X`.
+    Backtracker,
+}
+
+/// Engine state behind each `Strategy`.
+pub(crate) enum Engine {
+    MatchAll,
+    Literal(LiteralSet),
+    Dfa(Dfa),
+    ReverseSuffix(ReverseSuffix),
+    Bitap(Box<Bitap>),
+    LazyDfa(LazyDfa),
+    /// The Pike VM or the backtracking evaluator, picked per search.
+    Nfa {
+        backtrack: bool,
+    },
+}
+
+impl Engine {
+    /// Chooses the engine for `code`, compiled from `ast`.
+    ///
+    /// With `dfa_state_limit`, the ahead-of-time DFA is built unless a
+    /// cheaper engine applies, and failing to build it is an error.
+    pub(crate) fn select(
+        code: &[Instruction],
+        ast: &Ast,
+        analysis: &AstAnalysis,
+        has_assertion: bool,
+        dfa_state_limit: Option<usize>,
+    ) -> Result<Self, RegexError> {
+        // A subroutine call can fail the search by exceeding the call
+        // depth, so `find` would error where `is_match` answered true.
+        let has_call = code
+            .iter()
+            .any(|instruction| matches!(instruction, Instruction::Call(_)));
+        if analysis.nullable && !has_assertion && !has_call {
+            return Ok(Self::MatchAll);
+        }
+        if let Some(literals) = analysis.literals.as_deref().and_then(LiteralSet::new) {
+            return Ok(Self::Literal(literals));
+        }
+        if let Some(state_limit) = dfa_state_limit {
+            return Ok(Self::Dfa(Dfa::new(code, state_limit)?));
+        }
+        if let Some(reverse_suffix) = ReverseSuffix::new(ast, code, analysis) {
+            return Ok(Self::ReverseSuffix(reverse_suffix));
+        }
+        if let Some(bitap) = Bitap::new(code) {
+            return Ok(Self::Bitap(Box::new(bitap)));
+        }
+        if code.len() <= LAZY_DFA_MAX_INSTRUCTIONS
+            && let Some(lazy_dfa) = LazyDfa::new(code)
+        {
+            return Ok(Self::LazyDfa(lazy_dfa));
+        }
+        Ok(Self::Nfa {
+            backtrack: engine::needs_backtracking(code),
+        })
+    }
+
+    /// Returns the strategy this engine implements.
+    pub(crate) fn strategy(&self) -> Strategy {
+        match self {
+            Self::MatchAll => Strategy::MatchAll,
+            Self::Literal(literals) if literals.is_single() => Strategy::Literal,
+            Self::Literal(_) => Strategy::Literals,
+            Self::Dfa(_) => Strategy::Dfa,
+            Self::ReverseSuffix(_) => Strategy::ReverseSuffix,
+            Self::Bitap(_) => Strategy::Bitap,
+            Self::LazyDfa(_) => Strategy::LazyDfa,
+            Self::Nfa { backtrack: false } => Strategy::PikeVm,
+            Self::Nfa { backtrack: true } => Strategy::Backtracker,
+        }
+    }
+}